@@ -0,0 +1,102 @@
+//! Peak memory of `parse_content` versus `parse_content_streaming` on a 100 MB file
+//!
+//! Run with `cargo bench --bench streaming`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use todo_tree::parser::TodoParser;
+
+/// Size of the generated sample file
+const FILE_SIZE: usize = 100 * 1024 * 1024;
+
+/// Allocator that records the current and peak number of allocated bytes
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = self.current.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            self.peak.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.current.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAlloc = PeakAlloc {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Write a file of source lines with a TODO comment every 100 lines
+fn write_sample(path: &Path) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut written = 0;
+    let mut line = 0;
+    while written < FILE_SIZE {
+        let text = if line % 100 == 0 {
+            format!("    // TODO: generated item {}\n", line)
+        } else {
+            format!("    let value_{} = compute({});\n", line, line)
+        };
+        writer.write_all(text.as_bytes())?;
+        written += text.len();
+        line += 1;
+    }
+    writer.flush()
+}
+
+/// Run `f` and report its item count, time and peak memory above the baseline
+fn measure(name: &str, f: impl FnOnce() -> usize) {
+    let baseline = ALLOCATOR.current.load(Ordering::Relaxed);
+    ALLOCATOR.peak.store(baseline, Ordering::Relaxed);
+
+    let start = Instant::now();
+    let items = f();
+    let elapsed = start.elapsed();
+
+    let peak = ALLOCATOR.peak.load(Ordering::Relaxed) - baseline;
+    println!(
+        "{:<10} {:>8} items {:>8.2?} peak {:>8.1} MB",
+        name,
+        items,
+        elapsed,
+        peak as f64 / (1024.0 * 1024.0)
+    );
+}
+
+fn main() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("generated.rs");
+    write_sample(&path)?;
+
+    let tags = ["TODO", "FIXME", "BUG", "NOTE", "HACK"].map(String::from);
+    let parser = TodoParser::new(&tags, false);
+
+    measure("in-memory", || {
+        let content = std::fs::read_to_string(&path).unwrap();
+        parser.parse_content(&content).len()
+    });
+    measure("streaming", || {
+        let reader = BufReader::new(File::open(&path).unwrap());
+        parser
+            .parse_content_streaming(reader)
+            .collect::<Vec<_>>()
+            .len()
+    });
+
+    Ok(())
+}
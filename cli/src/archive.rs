@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Archive extensions scanned when `scan_archives` is enabled and none are configured
+pub const DEFAULT_ARCHIVE_EXTENSIONS: &[&str] = &["zip", "jar", "tar"];
+
+/// Entries larger than this many (uncompressed) bytes are skipped
+pub const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Call `f` with the inner path and content of each text file in a zip or tar archive
+///
+/// The format is chosen by extension: `.tar` is read as tar, anything else as zip.
+/// Entries are read one at a time, so only one is held in memory. Directories,
+/// entries that are not valid UTF-8 and entries larger than `limit` bytes are skipped.
+pub fn for_each_text_entry<F>(path: &Path, limit: u64, f: F) -> Result<()>
+where
+    F: FnMut(&str, &str),
+{
+    let file =
+        File::open(path).with_context(|| format!("Failed to open archive: {}", path.display()))?;
+
+    let is_tar = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("tar"));
+    if is_tar {
+        tar_entries(file, limit, f)
+            .with_context(|| format!("Failed to read tar archive: {}", path.display()))
+    } else {
+        zip_entries(file, limit, f)
+            .with_context(|| format!("Failed to read zip archive: {}", path.display()))
+    }
+}
+
+fn zip_entries<F: FnMut(&str, &str)>(file: File, limit: u64, mut f: F) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(file)?;
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+
+        let name = entry.name().to_string();
+        if let Some(content) = read_text(&mut entry, limit, &name) {
+            f(&name, &content);
+        }
+    }
+
+    Ok(())
+}
+
+fn tar_entries<F: FnMut(&str, &str)>(file: File, limit: u64, mut f: F) -> Result<()> {
+    let mut archive = tar::Archive::new(file);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if let Some(content) = read_text(&mut entry, limit, &name) {
+            f(&name, &content);
+        }
+    }
+
+    Ok(())
+}
+
+/// Read an entry as UTF-8 text, reading at most one byte past `limit`
+fn read_text<R: Read>(entry: R, limit: u64, name: &str) -> Option<String> {
+    let mut content = String::new();
+    entry
+        .take(limit.saturating_add(1))
+        .read_to_string(&mut content)
+        .ok()?;
+    if content.len() as u64 > limit {
+        tracing::debug!(entry = name, limit, "skipping oversized archive entry");
+        return None;
+    }
+    Some(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn text_entries(path: &Path, limit: u64) -> Result<Vec<(String, String)>> {
+        let mut entries = Vec::new();
+        for_each_text_entry(path, limit, |name, content| {
+            entries.push((name.to_string(), content.to_string()))
+        })?;
+        Ok(entries)
+    }
+
+    #[test]
+    fn test_zip_text_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("vendor.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.add_directory("src/", options).unwrap();
+        writer.start_file("src/lib.rs", options).unwrap();
+        writer.write_all(b"// TODO: vendored").unwrap();
+        writer.start_file("logo.png", options).unwrap();
+        writer.write_all(&[0xff, 0xfe, 0x00]).unwrap();
+        writer.finish().unwrap();
+
+        let entries = text_entries(&path, MAX_ENTRY_SIZE).unwrap();
+        assert_eq!(
+            entries,
+            vec![("src/lib.rs".to_string(), "// TODO: vendored".to_string())]
+        );
+        assert!(text_entries(&path, 8).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tar_text_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("vendor.tar");
+
+        let mut builder = tar::Builder::new(File::create(&path).unwrap());
+        let content = b"# FIXME: vendored";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "pkg/setup.py", &content[..])
+            .unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+
+        let entries = text_entries(&path, MAX_ENTRY_SIZE).unwrap();
+        assert_eq!(
+            entries,
+            vec![("pkg/setup.py".to_string(), "# FIXME: vendored".to_string())]
+        );
+        assert!(text_entries(&path, 8).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("broken.zip");
+        std::fs::write(&path, "not a zip").unwrap();
+
+        assert!(text_entries(&path, MAX_ENTRY_SIZE).is_err());
+    }
+}
@@ -1,5 +1,9 @@
+use crate::config::SchemaFormat;
+use crate::printer::{ColorScheme, JsonSchemaVersion, OutputFormat};
 use clap::{Args, Parser, Subcommand, ValueHint};
+use std::cmp::Ordering;
 use std::path::PathBuf;
+use todo_tree_core::{Priority, TodoItem};
 
 /// A CLI tool to find and display TODO-style comments in your codebase
 ///
@@ -25,22 +29,63 @@ pub struct Cli {
 }
 
 /// Global options available for all commands
-#[derive(Args, Debug, Clone)]
+#[derive(Args, Debug, Clone, Default)]
 pub struct GlobalOptions {
     /// Disable colored output
     #[arg(long, global = true, env = "NO_COLOR")]
     pub no_color: bool,
 
-    /// Enable verbose output
+    /// Enable verbose output (log level `info`)
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
+    /// Enable debug logging (log level `debug`)
+    #[arg(long, global = true)]
+    pub debug: bool,
+
+    /// Log level for messages on stderr (overrides --verbose and --debug)
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<LogLevel>,
+
     /// Path to a custom config file
     #[arg(long, global = true, value_hint = ValueHint::FilePath)]
     pub config: Option<PathBuf>,
+
+    /// Ignore all config files and use the built-in defaults
+    #[arg(long, global = true, conflicts_with = "config")]
+    pub no_config: bool,
+
+    /// Search for the config file starting from this directory instead of the scanned path
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        conflicts_with_all = ["config", "no_config"]
+    )]
+    pub follow_config_from: Option<PathBuf>,
+}
+
+impl GlobalOptions {
+    /// The log level from `--log-level`, `--debug` or `--verbose`, in that order
+    ///
+    /// Without any of them only warnings and errors are logged.
+    pub fn effective_log_level(&self) -> LogLevel {
+        if let Some(level) = self.log_level {
+            level
+        } else if self.debug {
+            LogLevel::Debug
+        } else if self.verbose {
+            LogLevel::Info
+        } else {
+            LogLevel::Warn
+        }
+    }
 }
 
 /// Available commands for the todo-tree CLI
+// Parsed once per run, so the size of `ScanArgs` does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug, Clone)]
 pub enum Commands {
     /// Scan directories for TODO-style comments (default command)
@@ -60,6 +105,34 @@ pub enum Commands {
 
     /// Show statistics about TODOs in the codebase
     Stats(StatsArgs),
+
+    /// Run a language server publishing TODOs as diagnostics
+    Lsp(LspArgs),
+
+    /// Save, compare and list named snapshots of scan results
+    Snapshot(SnapshotArgs),
+
+    /// Parse a single line, useful for debugging tag detection
+    Parse(ParseArgs),
+
+    /// Print the regex used to find tags, optionally as a ripgrep command
+    Pattern(PatternArgs),
+
+    /// Show the TODO item at a file location (FILE:LINE)
+    Show(ShowArgs),
+
+    /// Check that required TODO items reference a ticket
+    Verify(VerifyArgs),
+
+    /// Print a schema of the configuration file for editor autocompletion
+    Schema(SchemaArgs),
+
+    /// Maintain the configuration file
+    Config(ConfigArgs),
+
+    /// Browse TODOs in a fullscreen terminal UI
+    #[command(visible_alias = "i")]
+    Interactive(InteractiveArgs),
 }
 
 /// Arguments for the scan command
@@ -77,22 +150,89 @@ pub struct ScanArgs {
     #[arg(short, long, value_delimiter = ',')]
     pub include: Option<Vec<String>>,
 
+    /// File extensions to include (comma-separated, e.g. rs,toml), added to --include
+    #[arg(long, value_delimiter = ',')]
+    pub ext: Option<Vec<String>>,
+
     /// File patterns to exclude (glob patterns, comma-separated)
     #[arg(short, long, value_delimiter = ',')]
     pub exclude: Option<Vec<String>>,
 
+    /// Read additional exclude patterns from a file (one glob per line, # for comments)
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    pub ignore_pattern_file: Option<PathBuf>,
+
     /// Output results in JSON format
     #[arg(long)]
     pub json: bool,
 
+    /// Output results as minified single-line JSON
+    #[arg(long)]
+    pub json_compact: bool,
+
+    /// JSON layout to write: 1.0 (original fields only) or 1.1 (latest)
+    #[arg(long, value_enum, value_name = "V", default_value = "1.1")]
+    pub json_schema_version: JsonSchemaVersion,
+
     /// Output results in flat format (no tree structure)
     #[arg(long)]
     pub flat: bool,
 
-    /// Maximum depth to scan (0 = unlimited)
+    /// Output one abbreviated line per item (filename:line TAG message)
+    #[arg(long)]
+    pub compact: bool,
+
+    /// Output results as an aligned table
+    #[arg(long)]
+    pub table: bool,
+
+    /// Output a Graphviz DOT graph of the issues items reference (same as --format dot)
+    #[arg(long)]
+    pub dot: bool,
+
+    /// Output results as Markdown (same as --format markdown)
+    #[arg(
+        long,
+        conflicts_with_all = ["json", "json_compact", "flat", "compact", "table", "dot", "format"]
+    )]
+    pub markdown: bool,
+
+    /// Output format (overrides --json, --flat, --compact, --table and --dot)
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Render results through a Tera template file (overrides the output format)
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    pub output_template_file: Option<PathBuf>,
+
+    /// Maximum depth to scan (0 = unlimited, 1 = only files directly in the path)
     #[arg(short, long, default_value = "0")]
     pub depth: usize,
 
+    /// Scan only the files directly in the path, like --depth 1 but without --include/--exclude glob matching
+    #[arg(long, conflicts_with = "depth")]
+    pub no_walk: bool,
+
+    /// Stop scanning after this many files (the result is marked as truncated)
+    #[arg(long, value_name = "N")]
+    pub max_files: Option<usize>,
+
+    /// Give up after this many seconds and show what was found so far
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        conflicts_with_all = ["stdin_filelist", "workspaces_from", "stream", "list_files"]
+    )]
+    pub timeout: Option<u64>,
+
+    /// Number of directory walker threads (0 = auto)
+    #[arg(long, value_name = "N")]
+    pub threads: Option<usize>,
+
+    /// Number of files parsed in parallel (default: number of CPUs)
+    #[arg(long, value_name = "N")]
+    pub parallel_files: Option<usize>,
+
     /// Follow symbolic links
     #[arg(long)]
     pub follow_links: bool,
@@ -101,17 +241,208 @@ pub struct ScanArgs {
     #[arg(long)]
     pub hidden: bool,
 
+    /// Do not respect .gitignore, global gitignore, or .git/info/exclude
+    #[arg(long)]
+    pub no_gitignore: bool,
+
+    /// Respect .svnignore files (automatic when the path contains a .svn directory)
+    #[arg(long)]
+    pub respect_svnignore: bool,
+
+    /// Respect .dockerignore files (automatic when the path contains a Dockerfile)
+    #[arg(long)]
+    pub respect_dockerignore: bool,
+
+    /// Also scan .git, .hg and .svn directories (other hidden files stay skipped; can be slow)
+    #[arg(long)]
+    pub include_vcs_dirs: bool,
+
+    /// Also scan text files inside .zip, .jar and .tar archives (shown as archive.zip!/file)
+    #[arg(long)]
+    pub scan_archives: bool,
+
+    /// Guess each file's encoding (e.g. Latin-1, Shift-JIS, GBK) instead of skipping files that are not UTF-8
+    #[arg(long)]
+    pub detect_encoding: bool,
+
+    /// Only print the files that would be scanned, one per line (a JSON array with --json)
+    #[arg(long, conflicts_with = "stdin_filelist")]
+    pub list_files: bool,
+
+    /// Read the files to scan from stdin, one path per line, instead of walking the directory
+    #[arg(long)]
+    pub stdin_filelist: bool,
+
+    /// Scan the members of the Cargo workspace described by this manifest
+    #[arg(
+        long,
+        value_name = "MANIFEST",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = ["path", "stdin_filelist", "list_files"]
+    )]
+    pub workspaces_from: Option<PathBuf>,
+
+    /// Only scan these workspace members (comma-separated crate names)
+    #[arg(
+        long,
+        value_name = "CRATE",
+        value_delimiter = ',',
+        requires = "workspaces_from"
+    )]
+    pub workspace_filter: Option<Vec<String>>,
+
+    /// Print each file's items as soon as it is scanned (tree, flat and compact output)
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "stdin_filelist",
+            "workspaces_from",
+            "list_files",
+            "since_commit",
+            "first",
+            "last",
+            "offset",
+            "output_template_file",
+            "stats_after_scan",
+        ]
+    )]
+    pub stream: bool,
+
+    /// Only show TODOs on lines added since this commit (uses `git diff`)
+    #[arg(long, value_name = "SHA")]
+    pub since_commit: Option<String>,
+
     /// Case-sensitive tag matching
     #[arg(long)]
     pub case_sensitive: bool,
 
-    /// Sort results by: file, tag, line
+    /// Show only the first N items (after sorting and filtering)
+    #[arg(long, value_name = "N", conflicts_with = "last")]
+    pub first: Option<usize>,
+
+    /// Show only the last N items (after sorting and filtering)
+    #[arg(long, value_name = "N")]
+    pub last: Option<usize>,
+
+    /// Skip the first N items (after sorting and filtering)
+    #[arg(long, value_name = "N")]
+    pub offset: Option<usize>,
+
+    /// Report items with a priority above this as `not ok` (requires --format tap; critical, high, medium, low)
+    #[arg(long, value_name = "PRIORITY")]
+    pub fail_above: Option<Priority>,
+
+    /// Sort the items of each file (all items in flat, compact and table output) by: file, line, priority, created-at, author, tag, message
     #[arg(long, default_value = "file")]
     pub sort: SortOrder,
 
+    /// Re-sort the items of each file by this order, keeping --sort for ties
+    #[arg(long, value_name = "ORDER")]
+    pub sort_within_file: Option<SortOrder>,
+
+    /// Reverse the sort order: files last first, and the items of each file (all items in flat output)
+    #[arg(long)]
+    pub sort_reverse: bool,
+
+    /// Show N lines after each item (tree and flat output; included in JSON)
+    #[arg(short = 'A', long, value_name = "N", default_value = "0")]
+    pub after_context: usize,
+
+    /// Show N lines before each item (tree and flat output; included in JSON)
+    #[arg(short = 'B', long, value_name = "N", default_value = "0")]
+    pub before_context: usize,
+
+    /// Show only the last N components of file paths (1 = file name; links and JSON keep full paths)
+    #[arg(long, value_name = "N")]
+    pub truncate_paths: Option<usize>,
+
+    /// Highlight the tag wherever it appears in a message (tree and flat output)
+    #[arg(long)]
+    pub highlight_matches: bool,
+
     /// Group results by tag instead of by file
     #[arg(long)]
     pub group_by_tag: bool,
+
+    /// Group results by author instead of by file (items without one are "(unassigned)")
+    #[arg(long, conflicts_with = "group_by_tag")]
+    pub group_by_author: bool,
+
+    /// Give each file path its own color
+    #[arg(long)]
+    pub color_by_file: bool,
+
+    /// Palette for tag colors (default: the config's `color_scheme`, then `default`)
+    #[arg(long, value_name = "NAME")]
+    pub color_scheme: Option<ColorScheme>,
+
+    /// Show per-tag counts in tree file headers, e.g. (TODO:3 FIXME:1)
+    #[arg(long, overrides_with = "no_inline_counts")]
+    pub inline_counts: bool,
+
+    /// Show only the total count in tree file headers (default)
+    #[arg(long, overrides_with = "inline_counts")]
+    pub no_inline_counts: bool,
+
+    /// Always emit clickable OSC 8 links, even when terminal support is not detected (Markdown links with --markdown)
+    #[arg(long, overrides_with = "no_hyperlinks")]
+    pub hyperlinks: bool,
+
+    /// Never emit clickable OSC 8 links
+    #[arg(long, overrides_with = "hyperlinks")]
+    pub no_hyperlinks: bool,
+
+    /// Append the statistics block after the results (embedded as `statistics` in JSON)
+    #[arg(long)]
+    pub stats_after_scan: bool,
+
+    /// Time the parser on the first file with TODOs and print the result to stderr
+    #[arg(long, conflicts_with_all = ["stream", "list_files"])]
+    pub benchmark: bool,
+
+    /// Omit file header lines in tree mode, printing only item lines
+    #[arg(long)]
+    pub suppress_header: bool,
+
+    /// Do not print the summary line
+    #[arg(long)]
+    pub no_summary: bool,
+
+    /// Print only the results: no summary, warnings or verbose messages (overrides --verbose)
+    #[arg(short, long)]
+    pub quiet: bool,
+
+    /// Remove author names (e.g. from `TODO(alice):`) from the output
+    #[arg(long)]
+    pub strip_author: bool,
+
+    /// Save the effective configuration (config file plus these flags) to .todorc.json, then scan
+    #[arg(long)]
+    pub write_config: bool,
+
+    /// Save the effective configuration to .todorc.json without scanning
+    #[arg(long)]
+    pub write_config_only: bool,
+
+    /// Print the effective configuration as JSON to stderr, then scan
+    #[arg(long)]
+    pub emit_config: bool,
+
+    /// Fail unless at least this fraction of scanned files is TODO-free (0.0-1.0)
+    #[arg(long, value_name = "RATIO", value_parser = parse_ratio)]
+    pub coverage_threshold: Option<f64>,
+
+    /// Prepend this to every message in the output; `{repo}` becomes the origin remote's name
+    #[arg(long, value_name = "PREFIX")]
+    pub message_prefix: Option<String>,
+
+    /// Title printed as the first line of the output (a `title` field in JSON)
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Append the scan timestamp to the title
+    #[arg(long)]
+    pub title_with_timestamp: bool,
 }
 
 impl Default for ScanArgs {
@@ -120,15 +451,72 @@ impl Default for ScanArgs {
             path: None,
             tags: None,
             include: None,
+            ext: None,
             exclude: None,
+            ignore_pattern_file: None,
             json: false,
+            json_compact: false,
+            json_schema_version: JsonSchemaVersion::default(),
             flat: false,
+            compact: false,
+            table: false,
+            dot: false,
+            markdown: false,
+            format: None,
+            output_template_file: None,
             depth: 0,
+            no_walk: false,
+            max_files: None,
+            threads: None,
+            parallel_files: None,
             follow_links: false,
             hidden: false,
+            no_gitignore: false,
+            respect_svnignore: false,
+            respect_dockerignore: false,
+            include_vcs_dirs: false,
+            scan_archives: false,
+            detect_encoding: false,
+            list_files: false,
+            stdin_filelist: false,
+            stream: false,
+            since_commit: None,
             case_sensitive: false,
+            first: None,
+            last: None,
+            offset: None,
+            fail_above: None,
             sort: SortOrder::File,
+            sort_within_file: None,
+            sort_reverse: false,
+            after_context: 0,
+            before_context: 0,
+            truncate_paths: None,
+            highlight_matches: false,
             group_by_tag: false,
+            group_by_author: false,
+            color_by_file: false,
+            color_scheme: None,
+            inline_counts: false,
+            no_inline_counts: false,
+            hyperlinks: false,
+            no_hyperlinks: false,
+            stats_after_scan: false,
+            benchmark: false,
+            suppress_header: false,
+            no_summary: false,
+            quiet: false,
+            strip_author: false,
+            write_config: false,
+            write_config_only: false,
+            emit_config: false,
+            workspaces_from: None,
+            timeout: None,
+            workspace_filter: None,
+            coverage_threshold: None,
+            message_prefix: None,
+            title: None,
+            title_with_timestamp: false,
         }
     }
 }
@@ -156,6 +544,10 @@ pub struct ListArgs {
     #[arg(long)]
     pub json: bool,
 
+    /// Output results as an aligned table
+    #[arg(long)]
+    pub table: bool,
+
     /// Filter by specific tag
     #[arg(long)]
     pub filter: Option<String>,
@@ -165,6 +557,38 @@ pub struct ListArgs {
     pub case_sensitive: bool,
 }
 
+/// Arguments for the interactive command
+#[derive(Args, Debug, Clone, Default)]
+pub struct InteractiveArgs {
+    /// Directory or file to scan (defaults to current directory)
+    #[arg(value_hint = ValueHint::AnyPath)]
+    pub path: Option<PathBuf>,
+
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+
+    /// File patterns to include (glob patterns, comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub include: Option<Vec<String>>,
+
+    /// File patterns to exclude (glob patterns, comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub exclude: Option<Vec<String>>,
+
+    /// Case-sensitive tag matching
+    #[arg(long)]
+    pub case_sensitive: bool,
+
+    /// Lines of context shown around an item when it is opened with Enter
+    #[arg(short = 'C', long, value_name = "N", default_value = "3")]
+    pub context: usize,
+
+    /// Sort the items of each file by: file, line, priority, created-at, author, tag, message
+    #[arg(long, default_value = "file")]
+    pub sort: SortOrder,
+}
+
 /// Arguments for the tags command
 #[derive(Args, Debug, Clone)]
 pub struct TagsArgs {
@@ -186,7 +610,7 @@ pub struct TagsArgs {
 }
 
 /// Arguments for the init command
-#[derive(Args, Debug, Clone)]
+#[derive(Args, Debug, Clone, Default)]
 pub struct InitArgs {
     /// Configuration format: json or yaml
     #[arg(long, default_value = "json")]
@@ -195,10 +619,18 @@ pub struct InitArgs {
     /// Force overwrite if config file exists
     #[arg(short, long)]
     pub force: bool,
+
+    /// Install a git pre-push hook instead of creating a config file
+    #[arg(long)]
+    pub pre_push: bool,
+
+    /// Maximum number of critical items allowed by the pre-push hook
+    #[arg(long, default_value = "0", requires = "pre_push")]
+    pub max_critical: usize,
 }
 
 /// Arguments for the stats command
-#[derive(Args, Debug, Clone)]
+#[derive(Args, Debug, Clone, Default)]
 pub struct StatsArgs {
     /// Directory or file to scan (defaults to current directory)
     #[arg(value_hint = ValueHint::AnyPath)]
@@ -211,126 +643,412 @@ pub struct StatsArgs {
     /// Output results in JSON format
     #[arg(long)]
     pub json: bool,
-}
 
-/// Sort order for results
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
-pub enum SortOrder {
-    /// Sort by file path
-    #[default]
-    File,
-    /// Sort by line number
-    Line,
-    /// Sort by priority (based on tag type)
-    Priority,
-}
+    /// Break down counts by directory
+    #[arg(long)]
+    pub group_by_dir: bool,
 
-/// Configuration format for init command
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
-pub enum ConfigFormat {
-    #[default]
-    Json,
-    Yaml,
-}
+    /// Show the TODO-free file ratio and fail if it is below this fraction (0.0-1.0)
+    #[arg(long, value_name = "RATIO", value_parser = parse_ratio)]
+    pub coverage_threshold: Option<f64>,
 
-impl Cli {
-    /// Parse CLI arguments
-    pub fn parse_args() -> Self {
-        Self::parse()
-    }
+    /// Break down items by how long they have been in the codebase (uses `git blame`)
+    #[arg(long)]
+    pub with_age: bool,
 
-    /// Get the effective command, defaulting to Scan if none specified
-    pub fn get_command(&self) -> Commands {
-        self.command
-            .clone()
-            .unwrap_or_else(|| Commands::Scan(ScanArgs::default()))
-    }
+    /// Show the N largest groups of items with similar messages (deduplication candidates)
+    #[arg(long, value_name = "N")]
+    pub clusters: Option<usize>,
+
+    /// How similar messages must be to be grouped by --clusters (0.0-1.0)
+    #[arg(long, value_name = "RATIO", default_value = "0.5", value_parser = parse_ratio)]
+    pub cluster_threshold: f64,
+
+    /// Only count items at or above this priority (critical, high, medium, low)
+    #[arg(long, value_name = "PRIORITY")]
+    pub min_priority: Option<Priority>,
 }
 
-/// Convert ScanArgs to ListArgs for the list command
-impl From<ScanArgs> for ListArgs {
-    fn from(scan: ScanArgs) -> Self {
-        Self {
-            path: scan.path,
-            tags: scan.tags,
-            include: scan.include,
-            exclude: scan.exclude,
-            json: scan.json,
-            filter: None,
-            case_sensitive: scan.case_sensitive,
-        }
+/// Parse a ratio between 0.0 and 1.0
+fn parse_ratio(value: &str) -> Result<f64, String> {
+    let ratio: f64 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if (0.0..=1.0).contains(&ratio) {
+        Ok(ratio)
+    } else {
+        Err(format!("{} is not between 0.0 and 1.0", ratio))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Arguments for the lsp command
+#[derive(Args, Debug, Clone, Default)]
+pub struct LspArgs {
+    /// Workspace root used when the client does not provide one
+    #[arg(value_hint = ValueHint::DirPath)]
+    pub path: Option<PathBuf>,
 
-    #[test]
-    fn test_parse_scan_command() {
-        let cli = Cli::parse_from(["todo-tree", "scan", "--tags", "TODO,FIXME"]);
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+}
 
-        match cli.command {
-            Some(Commands::Scan(args)) => {
-                assert_eq!(
-                    args.tags,
-                    Some(vec!["TODO".to_string(), "FIXME".to_string()])
-                );
-            }
-            _ => panic!("Expected Scan command"),
-        }
-    }
+/// Arguments for the snapshot command
+#[derive(Args, Debug, Clone)]
+pub struct SnapshotArgs {
+    #[command(subcommand)]
+    pub command: SnapshotCommand,
+}
 
-    #[test]
-    fn test_parse_scan_with_path() {
-        let cli = Cli::parse_from(["todo-tree", "scan", "./src"]);
+/// Snapshot subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum SnapshotCommand {
+    /// Scan and save the result under a name
+    Save(SnapshotSaveArgs),
 
-        match cli.command {
-            Some(Commands::Scan(args)) => {
-                assert_eq!(args.path, Some(PathBuf::from("./src")));
-            }
-            _ => panic!("Expected Scan command"),
-        }
-    }
+    /// Scan and compare the result against a saved snapshot
+    Diff(SnapshotDiffArgs),
 
-    #[test]
-    fn test_parse_list_command() {
-        let cli = Cli::parse_from(["todo-tree", "list", "--json"]);
+    /// List saved snapshots
+    List(SnapshotListArgs),
+}
 
-        match cli.command {
-            Some(Commands::List(args)) => {
-                assert!(args.json);
-            }
-            _ => panic!("Expected List command"),
-        }
-    }
+/// Options shared by all snapshot subcommands
+#[derive(Args, Debug, Clone, Default)]
+pub struct SnapshotLocation {
+    /// Project directory to scan (defaults to current directory)
+    #[arg(long, value_hint = ValueHint::DirPath)]
+    pub path: Option<PathBuf>,
 
-    #[test]
-    fn test_parse_tags_command() {
-        let cli = Cli::parse_from(["todo-tree", "tags"]);
+    /// Store snapshots in <path>/.todo-tree/snapshots instead of the user data directory
+    #[arg(long)]
+    pub project_local: bool,
+}
 
-        assert!(matches!(cli.command, Some(Commands::Tags(_))));
-    }
+/// Arguments for the snapshot save command
+#[derive(Args, Debug, Clone, Default)]
+pub struct SnapshotSaveArgs {
+    /// Name of the snapshot
+    #[arg(long)]
+    pub name: String,
 
-    #[test]
-    fn test_parse_no_color() {
-        let cli = Cli::parse_from(["todo-tree", "--no-color", "scan"]);
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
 
-        assert!(cli.global.no_color);
-    }
+    #[command(flatten)]
+    pub location: SnapshotLocation,
+}
 
-    #[test]
-    fn test_parse_include_exclude() {
-        let cli = Cli::parse_from([
-            "todo-tree",
-            "scan",
-            "--include",
-            "*.rs,*.py",
-            "--exclude",
-            "target/**,node_modules/**",
-        ]);
+/// Arguments for the snapshot diff command
+#[derive(Args, Debug, Clone, Default)]
+pub struct SnapshotDiffArgs {
+    /// Name of the snapshot to compare against
+    pub name: String,
 
-        match cli.command {
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+
+    #[command(flatten)]
+    pub location: SnapshotLocation,
+}
+
+/// Arguments for the snapshot list command
+#[derive(Args, Debug, Clone, Default)]
+pub struct SnapshotListArgs {
+    #[command(flatten)]
+    pub location: SnapshotLocation,
+}
+
+/// Arguments for the parse command
+#[derive(Args, Debug, Clone, Default)]
+pub struct ParseArgs {
+    /// Line of source code to parse
+    pub line: String,
+
+    /// Explain why the line did or did not match
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+
+    /// Case-sensitive tag matching
+    #[arg(long)]
+    pub case_sensitive: bool,
+}
+
+/// Arguments for the pattern command
+#[derive(Args, Debug, Clone)]
+pub struct PatternArgs {
+    /// Print a complete `rg` invocation with the flags needed to match like todo-tree
+    #[arg(long)]
+    pub for_ripgrep: bool,
+
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+
+    /// Case-sensitive tag matching
+    #[arg(long)]
+    pub case_sensitive: bool,
+}
+
+/// Arguments for the show command
+#[derive(Args, Debug, Clone, Default)]
+pub struct ShowArgs {
+    /// Location of the item, as FILE:LINE
+    #[arg(value_name = "FILE:LINE")]
+    pub location: String,
+
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+}
+
+/// Arguments for the verify command
+#[derive(Args, Debug, Clone, Default)]
+pub struct VerifyArgs {
+    /// Directory or file to scan (defaults to current directory)
+    #[arg(value_hint = ValueHint::AnyPath)]
+    pub path: Option<PathBuf>,
+
+    /// Tags to search for (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub tags: Option<Vec<String>>,
+
+    /// Tags that must reference a ticket (comma-separated, default: FIXME,BUG)
+    #[arg(long, value_delimiter = ',')]
+    pub required_tags: Option<Vec<String>>,
+
+    /// Regex a ticket reference in the author field must match
+    #[arg(long)]
+    pub ticket_regex: Option<String>,
+}
+
+/// Arguments for the schema command
+#[derive(Args, Debug, Clone, Default)]
+pub struct SchemaArgs {
+    /// Schema format: json, toml or yaml
+    #[arg(long, default_value = "json")]
+    pub format: SchemaFormat,
+}
+
+/// Arguments for the config command
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+/// Config subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Migrate settings from older versions and show what changed
+    Upgrade(ConfigUpgradeArgs),
+}
+
+/// Arguments for the config upgrade command
+#[derive(Args, Debug, Clone, Default)]
+pub struct ConfigUpgradeArgs {
+    /// Config file to upgrade (defaults to --config, then the nearest .todorc)
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub file: Option<PathBuf>,
+
+    /// Write the upgraded config back to the file
+    #[arg(long)]
+    pub write: bool,
+}
+
+/// Sort order for results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortOrder {
+    /// Sort by file path
+    #[default]
+    File,
+    /// Sort by line number
+    Line,
+    /// Sort by priority (based on tag type)
+    Priority,
+    /// Sort by the time the line was committed, oldest first (uses `git blame`)
+    CreatedAt,
+    /// Sort alphabetically by author, items without an author last
+    Author,
+    /// Sort alphabetically by tag
+    Tag,
+    /// Sort alphabetically by message, ignoring case (ties by line)
+    Message,
+}
+
+impl SortOrder {
+    /// Compare two items by this order; `File` leaves them in place
+    pub fn compare(self, a: &TodoItem, b: &TodoItem) -> Ordering {
+        match self {
+            SortOrder::File => Ordering::Equal,
+            SortOrder::Line => a.line.cmp(&b.line),
+            SortOrder::Priority => b.priority.cmp(&a.priority),
+            // Items without a commit time (untracked, or outside a repository) last,
+            // in their existing (file) order
+            SortOrder::CreatedAt => a
+                .created_at
+                .unwrap_or(i64::MAX)
+                .cmp(&b.created_at.unwrap_or(i64::MAX)),
+            // Unassigned items last
+            SortOrder::Author => a
+                .author
+                .is_none()
+                .cmp(&b.author.is_none())
+                .then_with(|| a.author.cmp(&b.author)),
+            SortOrder::Tag => a.tag.cmp(&b.tag),
+            SortOrder::Message => a
+                .message
+                .to_lowercase()
+                .cmp(&b.message.to_lowercase())
+                .then(a.line.cmp(&b.line)),
+        }
+    }
+}
+
+/// Verbosity of the log messages written to stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum LogLevel {
+    /// No log messages
+    Off,
+    /// Errors only
+    Error,
+    /// Errors and warnings
+    #[default]
+    Warn,
+    /// Progress information (`--verbose`)
+    Info,
+    /// Details useful when debugging (`--debug`)
+    Debug,
+    /// Everything, including per-file messages
+    Trace,
+}
+
+impl From<LogLevel> for tracing::level_filters::LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Off => Self::OFF,
+            LogLevel::Error => Self::ERROR,
+            LogLevel::Warn => Self::WARN,
+            LogLevel::Info => Self::INFO,
+            LogLevel::Debug => Self::DEBUG,
+            LogLevel::Trace => Self::TRACE,
+        }
+    }
+}
+
+/// Configuration format for init command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl Cli {
+    /// Parse CLI arguments
+    pub fn parse_args() -> Self {
+        Self::parse()
+    }
+
+    /// Get the effective command, defaulting to Scan if none specified
+    pub fn get_command(&self) -> Commands {
+        self.command
+            .clone()
+            .unwrap_or_else(|| Commands::Scan(ScanArgs::default()))
+    }
+}
+
+/// Convert ScanArgs to ListArgs for the list command
+impl From<ScanArgs> for ListArgs {
+    fn from(scan: ScanArgs) -> Self {
+        Self {
+            path: scan.path,
+            tags: scan.tags,
+            include: scan.include,
+            exclude: scan.exclude,
+            json: scan.json,
+            table: scan.table,
+            filter: None,
+            case_sensitive: scan.case_sensitive,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scan_command() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--tags", "TODO,FIXME"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(
+                    args.tags,
+                    Some(vec!["TODO".to_string(), "FIXME".to_string()])
+                );
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_with_path() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "./src"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.path, Some(PathBuf::from("./src")));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_list_command() {
+        let cli = Cli::parse_from(["todo-tree", "list", "--json"]);
+
+        match cli.command {
+            Some(Commands::List(args)) => {
+                assert!(args.json);
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_tags_command() {
+        let cli = Cli::parse_from(["todo-tree", "tags"]);
+
+        assert!(matches!(cli.command, Some(Commands::Tags(_))));
+    }
+
+    #[test]
+    fn test_parse_no_color() {
+        let cli = Cli::parse_from(["todo-tree", "--no-color", "scan"]);
+
+        assert!(cli.global.no_color);
+    }
+
+    #[test]
+    fn test_parse_include_exclude() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--include",
+            "*.rs,*.py",
+            "--exclude",
+            "target/**,node_modules/**",
+        ]);
+
+        match cli.command {
             Some(Commands::Scan(args)) => {
                 assert_eq!(
                     args.include,
@@ -368,6 +1086,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_init_pre_push() {
+        let cli = Cli::parse_from(["todo-tree", "init", "--pre-push", "--max-critical", "2"]);
+
+        match cli.command {
+            Some(Commands::Init(args)) => {
+                assert!(args.pre_push);
+                assert_eq!(args.max_critical, 2);
+            }
+            _ => panic!("Expected Init command"),
+        }
+    }
+
     #[test]
     fn test_sort_order() {
         let cli = Cli::parse_from(["todo-tree", "scan", "--sort", "priority"]);
@@ -401,6 +1132,21 @@ mod tests {
         assert!(cli.global.verbose);
     }
 
+    #[test]
+    fn test_effective_log_level() {
+        let cli = Cli::parse_from(["todo-tree", "scan"]);
+        assert_eq!(cli.global.effective_log_level(), LogLevel::Warn);
+
+        let cli = Cli::parse_from(["todo-tree", "-v", "scan"]);
+        assert_eq!(cli.global.effective_log_level(), LogLevel::Info);
+
+        let cli = Cli::parse_from(["todo-tree", "--debug", "-v", "scan"]);
+        assert_eq!(cli.global.effective_log_level(), LogLevel::Debug);
+
+        let cli = Cli::parse_from(["todo-tree", "scan", "--debug", "--log-level", "off"]);
+        assert_eq!(cli.global.effective_log_level(), LogLevel::Off);
+    }
+
     #[test]
     fn test_parse_config_path() {
         let cli = Cli::parse_from(["todo-tree", "--config", "/path/to/config.json", "scan"]);
@@ -410,6 +1156,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_no_config() {
+        let cli = Cli::parse_from(["todo-tree", "--no-config", "scan"]);
+        assert!(cli.global.no_config);
+        assert!(cli.global.config.is_none());
+
+        let cli = Cli::parse_from(["todo-tree", "init", "--no-config"]);
+        assert!(cli.global.no_config);
+    }
+
+    #[test]
+    fn test_parse_no_config_conflicts_with_config() {
+        let result = Cli::try_parse_from([
+            "todo-tree",
+            "--no-config",
+            "--config",
+            "/path/to/config.json",
+            "scan",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_follow_config_from() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "packages/foo",
+            "--follow-config-from",
+            ".",
+        ]);
+        assert_eq!(cli.global.follow_config_from, Some(PathBuf::from(".")));
+
+        let result = Cli::try_parse_from([
+            "todo-tree",
+            "--follow-config-from",
+            ".",
+            "--no-config",
+            "scan",
+        ]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_list_with_filter() {
         let cli = Cli::parse_from(["todo-tree", "list", "--filter", "TODO"]);
@@ -434,6 +1223,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_stats_group_by_dir() {
+        let cli = Cli::parse_from(["todo-tree", "stats", "--group-by-dir"]);
+
+        match cli.command {
+            Some(Commands::Stats(args)) => {
+                assert!(args.group_by_dir);
+                assert!(!args.json);
+            }
+            _ => panic!("Expected Stats command"),
+        }
+    }
+
     #[test]
     fn test_parse_stats_with_path() {
         let cli = Cli::parse_from(["todo-tree", "stats", "./src"]);
@@ -518,6 +1320,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_scan_no_gitignore() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--no-gitignore"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.no_gitignore);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
     #[test]
     fn test_parse_scan_case_sensitive() {
         let cli = Cli::parse_from(["todo-tree", "scan", "--case-sensitive"]);
@@ -542,6 +1356,828 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_scan_compact() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--compact"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.compact);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_ignore_pattern_file() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--ignore-pattern-file", "ignore.txt"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.ignore_pattern_file, Some(PathBuf::from("ignore.txt")));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_stats_after_scan() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--stats-after-scan"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.stats_after_scan);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_lsp_command() {
+        let cli = Cli::parse_from(["todo-tree", "lsp", "--tags", "TODO,BUG"]);
+
+        match cli.command {
+            Some(Commands::Lsp(args)) => {
+                assert_eq!(args.tags, Some(vec!["TODO".to_string(), "BUG".to_string()]));
+                assert_eq!(args.path, None);
+            }
+            _ => panic!("Expected Lsp command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_output_template_file() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--output-template-file",
+            "templates/slack.tera",
+        ]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(
+                    args.output_template_file,
+                    Some(PathBuf::from("templates/slack.tera"))
+                );
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_respect_dockerignore() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--respect-dockerignore"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.respect_dockerignore);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_respect_svnignore() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--respect-svnignore"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.respect_svnignore);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_color_by_file() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--color-by-file"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.color_by_file);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_snapshot_commands() {
+        let cli = Cli::parse_from(["todo-tree", "snapshot", "save", "--name", "pre-refactor"]);
+        match cli.command {
+            Some(Commands::Snapshot(SnapshotArgs {
+                command: SnapshotCommand::Save(args),
+            })) => {
+                assert_eq!(args.name, "pre-refactor");
+                assert!(!args.location.project_local);
+            }
+            _ => panic!("Expected Snapshot save command"),
+        }
+
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "snapshot",
+            "diff",
+            "pre-refactor",
+            "--project-local",
+        ]);
+        match cli.command {
+            Some(Commands::Snapshot(SnapshotArgs {
+                command: SnapshotCommand::Diff(args),
+            })) => {
+                assert_eq!(args.name, "pre-refactor");
+                assert!(args.location.project_local);
+            }
+            _ => panic!("Expected Snapshot diff command"),
+        }
+
+        let cli = Cli::parse_from(["todo-tree", "snapshot", "list"]);
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Snapshot(SnapshotArgs {
+                command: SnapshotCommand::List(_),
+            }))
+        ));
+    }
+
+    #[test]
+    fn test_parse_parse_command() {
+        let cli = Cli::parse_from(["todo-tree", "parse", "--explain", "// FIXME: fix this"]);
+
+        match cli.command {
+            Some(Commands::Parse(args)) => {
+                assert_eq!(args.line, "// FIXME: fix this");
+                assert!(args.explain);
+            }
+            _ => panic!("Expected Parse command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_pattern_command() {
+        let cli = Cli::parse_from(["todo-tree", "pattern", "--for-ripgrep", "--tags", "TODO"]);
+        match cli.command {
+            Some(Commands::Pattern(args)) => {
+                assert!(args.for_ripgrep);
+                assert_eq!(args.tags, Some(vec!["TODO".to_string()]));
+            }
+            _ => panic!("Expected Pattern command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_max_files() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--max-files", "100"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.max_files, Some(100));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_title() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--title",
+            "Sprint 42 TODO Audit",
+            "--title-with-timestamp",
+        ]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.title.as_deref(), Some("Sprint 42 TODO Audit"));
+                assert!(args.title_with_timestamp);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_since_commit() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--since-commit", "abc123"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert_eq!(args.since_commit.as_deref(), Some("abc123")),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_hyperlinks() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--hyperlinks"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.hyperlinks);
+                assert!(!args.no_hyperlinks);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        let cli = Cli::parse_from(["todo-tree", "scan", "--hyperlinks", "--no-hyperlinks"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(!args.hyperlinks);
+                assert!(args.no_hyperlinks);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_json_compact() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--json-compact"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.json_compact),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_quiet() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "-q", "--verbose"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.quiet),
+            _ => panic!("Expected Scan command"),
+        }
+        assert!(cli.global.verbose);
+    }
+
+    #[test]
+    fn test_parse_scan_pagination() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--first", "10", "--offset", "20"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.first, Some(10));
+                assert_eq!(args.offset, Some(20));
+                assert_eq!(args.last, None);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        let result = Cli::try_parse_from(["todo-tree", "scan", "--first", "1", "--last", "1"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_scan_color_scheme() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--color-scheme", "high-contrast"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.color_scheme, Some(ColorScheme::HighContrast))
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_stdin_filelist() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--stdin-filelist"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.stdin_filelist),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_fail_above() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--format",
+            "tap",
+            "--fail-above",
+            "high",
+        ]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.format, Some(OutputFormat::Tap));
+                assert_eq!(args.fail_above, Some(Priority::High));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        assert!(Cli::try_parse_from(["todo-tree", "scan", "--fail-above", "urgent"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_scan_strip_author() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--strip-author"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.strip_author),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_write_config() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--write-config"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.write_config);
+                assert!(!args.write_config_only);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        let cli = Cli::parse_from(["todo-tree", "scan", "--write-config-only"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.write_config_only),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_sort_created_at() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--sort",
+            "created-at",
+            "--sort-reverse",
+        ]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.sort, SortOrder::CreatedAt);
+                assert!(args.sort_reverse);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_no_walk() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--no-walk"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.no_walk),
+            _ => panic!("Expected Scan command"),
+        }
+
+        assert!(Cli::try_parse_from(["todo-tree", "scan", "--no-walk", "--depth", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_scan_group_by_author() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--group-by-author", "--sort", "author"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.group_by_author);
+                assert_eq!(args.sort, SortOrder::Author);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["todo-tree", "scan", "--group-by-author", "--group-by-tag"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_scan_include_vcs_dirs() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--include-vcs-dirs"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.include_vcs_dirs),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_coverage_threshold() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--coverage-threshold", "0.8"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert_eq!(args.coverage_threshold, Some(0.8)),
+            _ => panic!("Expected Scan command"),
+        }
+
+        let cli = Cli::parse_from(["todo-tree", "stats", "--coverage-threshold", "1"]);
+        match cli.command {
+            Some(Commands::Stats(args)) => assert_eq!(args.coverage_threshold, Some(1.0)),
+            _ => panic!("Expected Stats command"),
+        }
+
+        assert!(Cli::try_parse_from(["todo-tree", "scan", "--coverage-threshold", "80"]).is_err());
+        assert!(Cli::try_parse_from(["todo-tree", "scan", "--coverage-threshold", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_scan_context() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "-A", "3", "--before-context", "1"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.after_context, 3);
+                assert_eq!(args.before_context, 1);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_archives() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--scan-archives"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.scan_archives),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_stats_with_age() {
+        let cli = Cli::parse_from(["todo-tree", "stats", "--with-age"]);
+        match cli.command {
+            Some(Commands::Stats(args)) => assert!(args.with_age),
+            _ => panic!("Expected Stats command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_message_prefix() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--message-prefix", "[{repo}] "]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.message_prefix.as_deref(), Some("[{repo}] "))
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_list_files() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--list-files"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.list_files),
+            _ => panic!("Expected Scan command"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["todo-tree", "scan", "--list-files", "--stdin-filelist"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_stats_clusters() {
+        let cli = Cli::parse_from(["todo-tree", "stats", "--clusters", "5"]);
+        match cli.command {
+            Some(Commands::Stats(args)) => {
+                assert_eq!(args.clusters, Some(5));
+                assert_eq!(args.cluster_threshold, 0.5);
+            }
+            _ => panic!("Expected Stats command"),
+        }
+
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "stats",
+            "--clusters",
+            "3",
+            "--cluster-threshold",
+            "0.8",
+        ]);
+        match cli.command {
+            Some(Commands::Stats(args)) => assert_eq!(args.cluster_threshold, 0.8),
+            _ => panic!("Expected Stats command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_dot() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--dot"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.dot),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_sort_within_file() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--sort",
+            "file",
+            "--sort-within-file",
+            "priority",
+        ]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.sort, SortOrder::File);
+                assert_eq!(args.sort_within_file, Some(SortOrder::Priority));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_stream() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--stream", "--flat"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.stream && args.flat),
+            _ => panic!("Expected Scan command"),
+        }
+
+        assert!(Cli::try_parse_from(["todo-tree", "scan", "--stream", "--first", "5"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_scan_json_schema_version() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--json",
+            "--json-schema-version",
+            "1.0",
+        ]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.json_schema_version, JsonSchemaVersion::V1_0)
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        let cli = Cli::parse_from(["todo-tree", "scan"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.json_schema_version, JsonSchemaVersion::default())
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_truncate_paths() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--truncate-paths", "2"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert_eq!(args.truncate_paths, Some(2)),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_detect_encoding() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--detect-encoding"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.detect_encoding),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_emit_config() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--emit-config"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.emit_config),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_workspaces_from() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--workspaces-from",
+            "Cargo.toml",
+            "--workspace-filter",
+            "core,cli",
+        ]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.workspaces_from, Some(PathBuf::from("Cargo.toml")));
+                assert_eq!(
+                    args.workspace_filter,
+                    Some(vec!["core".to_string(), "cli".to_string()])
+                );
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        let result = Cli::try_parse_from(["todo-tree", "scan", "--workspace-filter", "core"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_scan_highlight_matches() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--highlight-matches"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.highlight_matches),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_timeout() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--timeout", "30"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert_eq!(args.timeout, Some(30)),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_benchmark() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--benchmark"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.benchmark),
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_markdown() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--markdown"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.markdown),
+            _ => panic!("Expected Scan command"),
+        }
+
+        for other in [
+            &["--json"][..],
+            &["--json-compact"],
+            &["--flat"],
+            &["--compact"],
+            &["--table"],
+            &["--dot"],
+            &["--format", "json"],
+        ] {
+            let mut argv = vec!["todo-tree", "scan", "--markdown"];
+            argv.extend_from_slice(other);
+            assert!(Cli::try_parse_from(argv).is_err(), "{:?}", other);
+        }
+    }
+
+    #[test]
+    fn test_parse_table_flag() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--table"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => assert!(args.table),
+            _ => panic!("Expected Scan command"),
+        }
+
+        let cli = Cli::parse_from(["todo-tree", "list", "--table"]);
+        match cli.command {
+            Some(Commands::List(args)) => assert!(args.table),
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_suppress_header() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--suppress-header", "--no-summary"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.suppress_header);
+                assert!(args.no_summary);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_parallel_files() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "scan",
+            "--threads",
+            "2",
+            "--parallel-files",
+            "8",
+        ]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.threads, Some(2));
+                assert_eq!(args.parallel_files, Some(8));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_ext() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--ext", "rs,toml", "--include", "*.md"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.ext, Some(vec!["rs".to_string(), "toml".to_string()]));
+                assert_eq!(args.include, Some(vec!["*.md".to_string()]));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_show_command() {
+        let cli = Cli::parse_from(["todo-tree", "show", "src/main.rs:42"]);
+
+        match cli.command {
+            Some(Commands::Show(args)) => {
+                assert_eq!(args.location, "src/main.rs:42");
+            }
+            _ => panic!("Expected Show command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_verify_command() {
+        let cli = Cli::parse_from([
+            "todo-tree",
+            "verify",
+            "--required-tags",
+            "FIXME,HACK",
+            "--ticket-regex",
+            "^JIRA-\\d+$",
+        ]);
+
+        match cli.command {
+            Some(Commands::Verify(args)) => {
+                assert_eq!(
+                    args.required_tags,
+                    Some(vec!["FIXME".to_string(), "HACK".to_string()])
+                );
+                assert_eq!(args.ticket_regex.as_deref(), Some("^JIRA-\\d+$"));
+                assert!(args.path.is_none());
+            }
+            _ => panic!("Expected Verify command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_schema_command() {
+        let cli = Cli::parse_from(["todo-tree", "schema", "--format", "yaml"]);
+        match cli.command {
+            Some(Commands::Schema(args)) => assert_eq!(args.format, SchemaFormat::YamlSchema),
+            _ => panic!("Expected Schema command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_config_upgrade_command() {
+        let cli = Cli::parse_from(["todo-tree", "config", "upgrade", ".todorc.json", "--write"]);
+        match cli.command {
+            Some(Commands::Config(ConfigArgs {
+                command: ConfigCommand::Upgrade(args),
+            })) => {
+                assert_eq!(args.file, Some(PathBuf::from(".todorc.json")));
+                assert!(args.write);
+            }
+            _ => panic!("Expected Config upgrade command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_interactive_command() {
+        let cli = Cli::parse_from(["todo-tree", "i", "src", "--tags", "TODO,FIXME", "-C", "5"]);
+        match cli.command {
+            Some(Commands::Interactive(args)) => {
+                assert_eq!(args.path, Some(PathBuf::from("src")));
+                assert_eq!(
+                    args.tags,
+                    Some(vec!["TODO".to_string(), "FIXME".to_string()])
+                );
+                assert_eq!(args.context, 5);
+                assert_eq!(args.sort, SortOrder::File);
+            }
+            _ => panic!("Expected Interactive command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_inline_counts() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--inline-counts"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(args.inline_counts);
+                assert!(!args.no_inline_counts);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        // The last flag wins
+        let cli = Cli::parse_from(["todo-tree", "scan", "--inline-counts", "--no-inline-counts"]);
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert!(!args.inline_counts);
+                assert!(args.no_inline_counts);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_scan_format() {
+        let cli = Cli::parse_from(["todo-tree", "scan", "--format", "delta"]);
+
+        match cli.command {
+            Some(Commands::Scan(args)) => {
+                assert_eq!(args.format, Some(OutputFormat::Delta));
+            }
+            _ => panic!("Expected Scan command"),
+        }
+    }
+
     #[test]
     fn test_sort_order_line() {
         let cli = Cli::parse_from(["todo-tree", "scan", "--sort", "line"]);
@@ -570,12 +2206,17 @@ mod tests {
         assert!(args.path.is_none());
         assert!(args.tags.is_none());
         assert!(args.include.is_none());
+        assert!(args.ext.is_none());
         assert!(args.exclude.is_none());
         assert!(!args.json);
         assert!(!args.flat);
         assert_eq!(args.depth, 0);
         assert!(!args.follow_links);
         assert!(!args.hidden);
+        assert!(!args.no_gitignore);
+        assert!(!args.respect_svnignore);
+        assert!(!args.respect_dockerignore);
+        assert!(!args.include_vcs_dirs);
         assert!(!args.case_sensitive);
         assert_eq!(args.sort, SortOrder::File);
     }
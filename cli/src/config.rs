@@ -1,13 +1,72 @@
+use crate::printer::ColorScheme;
 use anyhow::{Context, Result};
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use todo_tree_core::tags;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::Duration;
+use todo_tree_core::{Language, Priority, tags};
+
+/// Names of the per-project config files, in the order they are looked up
+const LOCAL_CONFIG_FILES: [&str; 4] = [".todorc", ".todorc.json", ".todorc.yaml", ".todorc.yml"];
+
+/// Quiet period after a config file change before it is reloaded
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
 
 /// Get default tags to search for if none are specified
 pub fn default_tags() -> Vec<String> {
     tags::default_tag_names()
 }
 
+/// File names of the global config, in order of preference
+const GLOBAL_CONFIG_NAMES: [&str; 3] = ["config.json", "config.yaml", "config.yml"];
+
+/// How a list field is combined when layering configurations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMode {
+    /// Replace the earlier value
+    Override,
+    /// Append new entries to the earlier value
+    Extend,
+}
+
+/// Per-field merge modes used by [`Config::layer_merge`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MergeModes {
+    pub tags: MergeMode,
+    pub include: MergeMode,
+    pub exclude: MergeMode,
+}
+
+impl Default for MergeModes {
+    fn default() -> Self {
+        Self {
+            tags: MergeMode::Extend,
+            include: MergeMode::Override,
+            exclude: MergeMode::Extend,
+        }
+    }
+}
+
+/// Schema document format produced by [`Config::export_schema`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SchemaFormat {
+    /// JSON Schema, for editors with JSON Schema support
+    #[default]
+    #[value(name = "json")]
+    JsonSchema,
+    /// The same JSON Schema written as TOML
+    #[value(name = "toml")]
+    TomlSchema,
+    /// The same JSON Schema written as YAML, e.g. for yaml-language-server
+    #[value(name = "yaml")]
+    YamlSchema,
+}
+
 /// Configuration for the todo-tree tool
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -18,6 +77,9 @@ pub struct Config {
     /// File patterns to include (glob patterns)
     pub include: Vec<String>,
 
+    /// File extensions to include (e.g., rs, toml), in addition to `include`
+    pub extensions: Vec<String>,
+
     /// File patterns to exclude (glob patterns)
     pub exclude: Vec<String>,
 
@@ -33,23 +95,99 @@ pub struct Config {
     /// Custom regex pattern for matching (advanced)
     pub custom_pattern: Option<String>,
 
+    /// Several custom regex patterns, tried in order (advanced)
+    pub custom_patterns: Vec<String>,
+
     /// Case sensitive matching
     pub case_sensitive: bool,
+
+    /// Number of directory walker threads (0 = auto)
+    pub threads: Option<usize>,
+
+    /// Number of files parsed in parallel (default: number of CPUs)
+    pub parallel_files: Option<usize>,
+
+    /// File containing additional exclude patterns, one glob per line
+    pub ignore_pattern_file: Option<PathBuf>,
+
+    /// Priority overrides for tags (e.g., SECURITY: critical)
+    pub tag_priorities: HashMap<String, Priority>,
+
+    /// Per-tag priorities by file type, e.g. `{"FIXME": {"rs": "critical", "_test.rs": "medium"}}`
+    pub extension_priorities: HashMap<String, HashMap<String, Priority>>,
+
+    /// Title printed as the first line of scan output
+    pub title: Option<String>,
+
+    /// Palette used for tag colors
+    pub color_scheme: Option<ColorScheme>,
+
+    /// Remove author names from all output
+    pub strip_author: bool,
+
+    /// How this config's lists combine with earlier layers
+    pub merge_modes: MergeModes,
+
+    /// Tags that `verify` requires to reference a ticket (default: FIXME, BUG)
+    pub required_ticket_tags: Vec<String>,
+
+    /// Regex a ticket reference in the author field must match for `verify`
+    pub ticket_regex: Option<String>,
 }
 
 impl Config {
     /// Create a new configuration with default values
     pub fn new() -> Self {
-        Self {
+        let mut config = Self {
             tags: default_tags(),
             include: Vec::new(),
+            extensions: Vec::new(),
             exclude: Vec::new(),
             json: false,
             flat: false,
             no_color: false,
             custom_pattern: None,
+            custom_patterns: Vec::new(),
             case_sensitive: false,
-        }
+            threads: None,
+            parallel_files: None,
+            ignore_pattern_file: None,
+            tag_priorities: HashMap::new(),
+            extension_priorities: HashMap::new(),
+            title: None,
+            color_scheme: None,
+            strip_author: false,
+            merge_modes: MergeModes::default(),
+            required_ticket_tags: Vec::new(),
+            ticket_regex: None,
+        };
+        config.sanitize();
+        config
+    }
+
+    /// Normalize the tag list: trimmed, no empty entries and no duplicates
+    ///
+    /// Tags are uppercased unless [`case_sensitive`](Self::case_sensitive) is
+    /// set, in which case only exact duplicates are dropped. The first
+    /// occurrence of each tag keeps its position. Called by
+    /// [`new`](Self::new), [`merge_with_cli`](Self::merge_with_cli) and
+    /// [`layer_merge`](Self::layer_merge), so `--tags todo,TODO` searches for
+    /// `TODO` once.
+    pub fn sanitize(&mut self) {
+        let mut seen = HashSet::new();
+        let case_sensitive = self.case_sensitive;
+        self.tags = std::mem::take(&mut self.tags)
+            .into_iter()
+            .map(|tag| {
+                let tag = tag.trim();
+                if case_sensitive {
+                    tag.to_string()
+                } else {
+                    tag.to_uppercase()
+                }
+            })
+            .filter(|tag| !tag.is_empty() && seen.insert(tag.clone()))
+            .collect();
     }
 
     /// Load configuration from a .todorc file
@@ -58,21 +196,22 @@ impl Config {
     /// 1. .todorc in the current directory
     /// 2. .todorc.json in the current directory
     /// 3. .todorc.yaml or .todorc.yml in the current directory
-    /// 4. ~/.config/todo-tree/config.json (global config)
+    /// 4. The `"todo-tree"` key of package.json in the current directory
+    ///
+    /// then the same in each parent directory, and finally:
+    /// 5. Global config: `$XDG_CONFIG_HOME` then `$XDG_CONFIG_DIRS` on Linux,
+    ///    the platform config directory elsewhere (see [`Config::from_xdg`])
     pub fn load(start_path: &Path) -> Result<Option<Self>> {
         // Try local config files first
-        let local_configs = [
-            start_path.join(".todorc"),
-            start_path.join(".todorc.json"),
-            start_path.join(".todorc.yaml"),
-            start_path.join(".todorc.yml"),
-        ];
-
-        for config_path in &local_configs {
+        for name in LOCAL_CONFIG_FILES {
+            let config_path = start_path.join(name);
             if config_path.exists() {
-                return Self::load_from_file(config_path).map(Some);
+                return Self::load_from_file(&config_path).map(Some);
             }
         }
+        if let Some(config) = Self::from_package_json(&start_path.join("package.json"))? {
+            return Ok(Some(config));
+        }
 
         // Try parent directories
         if let Some(parent) = start_path.parent()
@@ -83,16 +222,94 @@ impl Config {
         }
 
         // Try global config
-        if let Some(config_dir) = dirs::config_dir() {
-            let global_configs = [
-                config_dir.join("todo-tree").join("config.json"),
-                config_dir.join("todo-tree").join("config.yaml"),
-                config_dir.join("todo-tree").join("config.yml"),
-            ];
-
-            for config_path in &global_configs {
+        Self::load_global()
+    }
+
+    /// Load configuration from the `"todo-tree"` key of a package.json file
+    ///
+    /// Returns `None` if the file does not exist or has no such key, the way
+    /// `eslint` and `prettier` settings are optional there.
+    pub fn from_package_json(path: &Path) -> Result<Option<Self>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read package.json: {}", path.display()))?;
+        let mut package: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse package.json: {}", path.display()))?;
+        let Some(section) = package.get_mut("todo-tree").map(serde_json::Value::take) else {
+            return Ok(None);
+        };
+
+        let config: Self = serde_json::from_value(section).with_context(|| {
+            format!(
+                "Failed to parse the \"todo-tree\" key of {}",
+                path.display()
+            )
+        })?;
+        tracing::debug!(path = %path.display(), "loaded config from package.json");
+        Ok(Some(config))
+    }
+
+    /// Find the per-project config file that [`Config::load`] would read
+    ///
+    /// Searches `start_path` and its parents; the global config is not considered.
+    pub fn find_local_file(start_path: &Path) -> Option<PathBuf> {
+        start_path.ancestors().find_map(|dir| {
+            LOCAL_CONFIG_FILES
+                .iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.exists())
+        })
+    }
+
+    /// Load the global configuration following the XDG Base Directory specification
+    ///
+    /// Looks for `todo-tree/config.{json,yaml,yml}` in `$XDG_CONFIG_HOME` (default
+    /// `~/.config`) first, then in each directory of `$XDG_CONFIG_DIRS` (default
+    /// `/etc/xdg`). Returns `None` if no file exists or the home directory is unknown.
+    #[cfg(unix)]
+    pub fn from_xdg() -> Result<Option<Self>> {
+        let Ok(base_dirs) = xdg::BaseDirectories::with_prefix("todo-tree") else {
+            return Ok(None);
+        };
+
+        let mut dirs = vec![base_dirs.get_config_home()];
+        dirs.extend(base_dirs.get_config_dirs());
+        Self::load_first_global(&dirs)
+    }
+
+    /// Load the global configuration following the XDG Base Directory specification
+    ///
+    /// XDG directories only exist on Unix, so this always returns `None` here.
+    #[cfg(not(unix))]
+    pub fn from_xdg() -> Result<Option<Self>> {
+        Ok(None)
+    }
+
+    /// Load the global configuration for the current platform
+    #[cfg(target_os = "linux")]
+    fn load_global() -> Result<Option<Self>> {
+        Self::from_xdg()
+    }
+
+    /// Load the global configuration for the current platform
+    #[cfg(not(target_os = "linux"))]
+    fn load_global() -> Result<Option<Self>> {
+        match dirs::config_dir() {
+            Some(config_dir) => Self::load_first_global(&[config_dir.join("todo-tree")]),
+            None => Ok(None),
+        }
+    }
+
+    /// Load the first global config file found in the given directories
+    fn load_first_global(dirs: &[PathBuf]) -> Result<Option<Self>> {
+        for dir in dirs {
+            for name in GLOBAL_CONFIG_NAMES {
+                let config_path = dir.join(name);
                 if config_path.exists() {
-                    return Self::load_from_file(config_path).map(Some);
+                    return Self::load_from_file(&config_path).map(Some);
                 }
             }
         }
@@ -116,7 +333,336 @@ impl Config {
             serde_json::from_str(&content).or_else(|_| serde_yaml::from_str(&content))
         };
 
-        parse_result.with_context(|| format!("Failed to parse config: {}", path.display()))
+        let config: Self =
+            parse_result.with_context(|| format!("Failed to parse config: {}", path.display()))?;
+        tracing::debug!(path = %path.display(), "loaded config file");
+        Ok(config)
+    }
+
+    /// Migrate settings written for older versions to the current schema
+    ///
+    /// Applies each known migration in order and returns the changes made, so
+    /// an up-to-date config returns an empty list:
+    /// - a single `custom_pattern` moves to the front of `custom_patterns`,
+    ///   which is the only one the scanner reads
+    /// - `extensions` written as globs (`*.rs`) or with a dot (`.rs`) become `rs`
+    pub fn upgrade(&mut self) -> Vec<UpgradeDiff> {
+        let mut diffs = Vec::new();
+
+        if let Some(pattern) = self.custom_pattern.take() {
+            let before = format!("{:?}", self.custom_patterns);
+            if !self.custom_patterns.contains(&pattern) {
+                self.custom_patterns.insert(0, pattern.clone());
+            }
+            diffs.push(UpgradeDiff {
+                field: "custom_pattern".to_string(),
+                before: format!("{:?}", pattern),
+                after: "(removed)".to_string(),
+            });
+            diffs.push(UpgradeDiff {
+                field: "custom_patterns".to_string(),
+                before,
+                after: format!("{:?}", self.custom_patterns),
+            });
+        }
+
+        let extensions: Vec<String> = self
+            .extensions
+            .iter()
+            .map(|extension| {
+                extension
+                    .trim_start_matches("*.")
+                    .trim_start_matches('.')
+                    .to_string()
+            })
+            .collect();
+        if extensions != self.extensions {
+            diffs.push(UpgradeDiff {
+                field: "extensions".to_string(),
+                before: format!("{:?}", self.extensions),
+                after: format!("{:?}", extensions),
+            });
+            self.extensions = extensions;
+        }
+
+        diffs
+    }
+
+    /// Apply the [`upgrade`](Self::upgrade) migrations to a config file's raw document
+    ///
+    /// Only the migrated keys change: keys the file leaves out stay out, and the
+    /// others keep their values and order. JSON files are handled through the
+    /// same YAML data model, which keeps mapping order.
+    pub fn upgrade_document(document: &mut serde_yaml::Value) -> Result<Vec<UpgradeDiff>> {
+        let mut config: Config = serde_yaml::from_value(document.clone())?;
+        let diffs = config.upgrade();
+        let upgraded = serde_yaml::to_value(&config)?;
+
+        let Some(mapping) = document.as_mapping_mut() else {
+            return Ok(diffs);
+        };
+        for diff in &diffs {
+            match upgraded.get(diff.field.as_str()) {
+                Some(value) if !value.is_null() => {
+                    mapping.insert(diff.field.clone().into(), value.clone());
+                }
+                _ => {
+                    mapping.remove(diff.field.as_str());
+                }
+            }
+        }
+        Ok(diffs)
+    }
+
+    /// Upgrade a config file, rewriting it only when `write` is set
+    ///
+    /// See [`upgrade_document`](Self::upgrade_document). JSON files are written
+    /// back as pretty-printed JSON and YAML files as YAML; comments in YAML
+    /// files cannot be kept, which is logged as a warning.
+    pub fn upgrade_file(path: &Path, write: bool) -> Result<Vec<UpgradeDiff>> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let mut document: serde_yaml::Value = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse config: {}", path.display()))?;
+
+        let diffs = Self::upgrade_document(&mut document)
+            .with_context(|| format!("Failed to parse config: {}", path.display()))?;
+        if !write || diffs.is_empty() {
+            return Ok(diffs);
+        }
+
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        let is_json = extension != "yaml"
+            && extension != "yml"
+            && serde_json::from_str::<serde_json::Value>(&content).is_ok();
+        let upgraded = if is_json {
+            format!("{}\n", serde_json::to_string_pretty(&document)?)
+        } else {
+            if content
+                .lines()
+                .any(|line| line.trim_start().starts_with('#'))
+            {
+                tracing::warn!("comments in {} are not kept by the upgrade", path.display());
+            }
+            serde_yaml::to_string(&document)?
+        };
+
+        std::fs::write(path, upgraded)
+            .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+        Ok(diffs)
+    }
+
+    /// Watch a config file and yield the new configuration each time it changes
+    ///
+    /// Filesystem events are debounced by 200 ms, so an editor's save counts once.
+    /// The parent directory is watched, so a file replaced by a rename is still
+    /// seen. Versions that fail to load (e.g. half-written) are logged and
+    /// skipped; each `next` blocks until the next successful reload.
+    pub fn watch_and_reload(path: &Path) -> Result<impl Iterator<Item = Config> + use<>> {
+        let name = path
+            .file_name()
+            .with_context(|| format!("Not a config file: {}", path.display()))?;
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let path = dir
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path: {}", dir.display()))?
+            .join(name);
+
+        let (sender, events) = mpsc::channel();
+        let mut debouncer =
+            new_debouncer(RELOAD_DEBOUNCE, sender).context("Failed to start the config watcher")?;
+        debouncer
+            .watcher()
+            .watch(path.parent().unwrap_or(&path), RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch config file: {}", path.display()))?;
+
+        Ok(events.into_iter().filter_map(move |events| {
+            // Dropping the debouncer stops the watcher, so it lives as long as the iterator
+            let _watcher = &debouncer;
+            match events {
+                Ok(events) if events.iter().any(|event| event.path == path) => {
+                    match Config::load_from_file(&path) {
+                        Ok(config) => Some(config),
+                        Err(err) => {
+                            tracing::warn!("keeping the previous config: {:#}", err);
+                            None
+                        }
+                    }
+                }
+                Ok(_) => None,
+                Err(err) => {
+                    tracing::warn!(error = %err, "config watcher error");
+                    None
+                }
+            }
+        }))
+    }
+
+    /// Fill fields left at their zero value (e.g. an omitted `tags`) from [`Config::new`]
+    ///
+    /// Deserialization starts from `Config::default()`, which has no tags, so a
+    /// partial config file used on its own would search for nothing. Loaded
+    /// files are left partial so they can be layered; [`Config::effective`]
+    /// calls this after layering instead.
+    pub fn merge_defaults(&mut self) {
+        let defaults = Config::new();
+        if self.tags.is_empty() {
+            self.tags = defaults.tags;
+        }
+    }
+
+    /// Load settings from the `todo-tree` section of the repository's local git config
+    ///
+    /// Runs `git config --local`, so the global and system git configs are never
+    /// read. List keys (`tags`, `include`, `exclude`, ...) take comma-separated
+    /// values and may be repeated. Returns `None` when `repo_path` is not inside a
+    /// git repository, git is not installed, or no `todo-tree.*` key is set.
+    pub fn from_git_config(repo_path: &Path) -> Result<Option<Self>> {
+        let dir = if repo_path.is_file() {
+            repo_path.parent().unwrap_or(repo_path)
+        } else {
+            repo_path
+        };
+
+        let Ok(output) = Command::new("git")
+            .args([
+                "config",
+                "--local",
+                "--null",
+                "--get-regexp",
+                r"^todo-tree\.",
+            ])
+            .current_dir(dir)
+            .output()
+        else {
+            return Ok(None);
+        };
+
+        // Exit code 1 means no key matched; other failures mean there is no repository
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let mut config = Config::default();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for entry in stdout.split('\0').filter(|entry| !entry.is_empty()) {
+            // A key set without `=` is a boolean true
+            let (key, value) = entry.split_once('\n').unwrap_or((entry, "true"));
+            let key = key.trim_start_matches("todo-tree.");
+            config
+                .set_git_value(key, value)
+                .with_context(|| format!("Invalid value for git config todo-tree.{}", key))?;
+        }
+
+        Ok(Some(config))
+    }
+
+    /// Load settings from `TODO_TREE_*` environment variables
+    ///
+    /// The rest of the variable name is the config key, e.g. `TODO_TREE_TAGS` or
+    /// `TODO_TREE_NO_COLOR`, with values parsed as in the git config. Returns
+    /// `None` when no such variable is set.
+    pub fn from_env() -> Result<Option<Self>> {
+        let vars = std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)));
+        Self::from_vars(vars)
+    }
+
+    /// Load settings from `TODO_TREE_*` variables in a list of key-value pairs
+    ///
+    /// Lists set this way replace those of earlier layers instead of extending them.
+    fn from_vars<I>(vars: I) -> Result<Option<Self>>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut config = None;
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix("TODO_TREE_") else {
+                continue;
+            };
+            config
+                .get_or_insert_with(|| Config {
+                    merge_modes: MergeModes {
+                        tags: MergeMode::Override,
+                        include: MergeMode::Override,
+                        exclude: MergeMode::Override,
+                    },
+                    ..Default::default()
+                })
+                .set_git_value(key, &value)
+                .with_context(|| format!("Invalid value for {}", name))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Get the configuration that applies to a path, without any CLI options
+    ///
+    /// Layers, lowest priority first: the local git config, the config file found
+    /// by [`Config::load`], and `TODO_TREE_*` environment variables.
+    pub fn effective(start_path: &Path) -> Result<Config> {
+        let config = Self::load(start_path)?;
+        Self::resolve(start_path, config)
+    }
+
+    /// Layer the git config below and the environment above a loaded config file
+    pub(crate) fn resolve(start_path: &Path, file_config: Option<Config>) -> Result<Config> {
+        let layers: Vec<Config> = Self::from_git_config(start_path)?
+            .into_iter()
+            .chain(file_config)
+            .chain(Self::from_env()?)
+            .collect();
+
+        // Layers that set no tags, e.g. only `TODO_TREE_NO_COLOR`, keep the defaults
+        let mut config = Self::layer_merge(&layers);
+        config.merge_defaults();
+        Ok(config)
+    }
+
+    /// Apply one `todo-tree.<key>` git config value (or `TODO_TREE_<KEY>` variable)
+    ///
+    /// Git lowercases key names and does not allow `_`, so `no-color`, `nocolor`
+    /// and `no_color` are all accepted. Unknown keys are ignored.
+    fn set_git_value(&mut self, key: &str, value: &str) -> Result<()> {
+        let list = || {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(String::from)
+        };
+
+        match key.replace(['-', '_'], "").to_lowercase().as_str() {
+            "tags" => self.tags.extend(list()),
+            "include" => self.include.extend(list()),
+            "extensions" => self.extensions.extend(list()),
+            "exclude" => self.exclude.extend(list()),
+            "requiredtickettags" => self.required_ticket_tags.extend(list()),
+            // Regexes may contain commas, so each value is one pattern
+            "custompatterns" => self.custom_patterns.push(value.to_string()),
+            "json" => self.json = parse_git_bool(value)?,
+            "flat" => self.flat = parse_git_bool(value)?,
+            "nocolor" => self.no_color = parse_git_bool(value)?,
+            "casesensitive" => self.case_sensitive = parse_git_bool(value)?,
+            "stripauthor" => self.strip_author = parse_git_bool(value)?,
+            "threads" => self.threads = Some(value.trim().parse()?),
+            "parallelfiles" => self.parallel_files = Some(value.trim().parse()?),
+            "custompattern" => self.custom_pattern = Some(value.to_string()),
+            "ignorepatternfile" => self.ignore_pattern_file = Some(PathBuf::from(value)),
+            "title" => self.title = Some(value.to_string()),
+            "colorscheme" => {
+                self.color_scheme = Some(
+                    clap::ValueEnum::from_str(value.trim(), true).map_err(anyhow::Error::msg)?,
+                )
+            }
+            "ticketregex" => self.ticket_regex = Some(value.to_string()),
+            _ => {}
+        }
+
+        Ok(())
     }
 
     /// Merge CLI options with the loaded configuration
@@ -159,6 +705,263 @@ impl Config {
         if no_color {
             self.no_color = true;
         }
+
+        self.sanitize();
+    }
+
+    /// Get the include patterns, with each extension translated to a `*.ext` glob
+    pub fn include_patterns(&self) -> Vec<String> {
+        let mut patterns = self.include.clone();
+        for extension in &self.extensions {
+            let extension = extension.trim_start_matches("*.").trim_start_matches('.');
+            if extension.is_empty() {
+                continue;
+            }
+
+            let pattern = format!("*.{}", extension);
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+        patterns
+    }
+
+    /// Get the languages implied by the include patterns and extensions
+    ///
+    /// Only the extension of each pattern is looked at, so `*.py`,
+    /// `src/**/*.pyi` and `*.{ts,tsx}` all count. Sorted and without duplicates.
+    pub fn detect_language_from_include_patterns(&self) -> Vec<Language> {
+        let mut languages: Vec<Language> = self
+            .include_patterns()
+            .iter()
+            .filter_map(|pattern| pattern.rsplit('/').next()?.split_once('.'))
+            .flat_map(|(_, extension)| {
+                extension
+                    .trim_start_matches('{')
+                    .trim_end_matches('}')
+                    .split(',')
+                    .filter_map(Language::from_extension)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        languages.sort();
+        languages.dedup();
+        languages
+    }
+
+    /// Get the languages whose marker files (`Cargo.toml`, `package.json`, ...) are in `root`
+    pub fn infer_from_project_root(root: &Path) -> Vec<Language> {
+        Language::ALL
+            .into_iter()
+            .filter(|language| {
+                language
+                    .marker_files()
+                    .iter()
+                    .any(|file| root.join(file).is_file())
+            })
+            .collect()
+    }
+
+    /// Fold configs left-to-right into a single configuration
+    ///
+    /// Later layers win for scalar fields that they set (`true` flags and `Some`
+    /// values). Each layer's `merge_modes` decides whether its `tags`, `include`
+    /// and `exclude` replace or extend the earlier lists; empty lists are skipped.
+    /// Returns `Config::new()` when no configs are given.
+    pub fn layer_merge(configs: &[Config]) -> Config {
+        let Some((first, rest)) = configs.split_first() else {
+            return Config::new();
+        };
+
+        let mut merged = rest.iter().fold(first.clone(), |mut merged, layer| {
+            merged.merge_layer(layer);
+            merged
+        });
+        merged.sanitize();
+        merged
+    }
+
+    /// Merge a single later layer into this configuration
+    fn merge_layer(&mut self, layer: &Config) {
+        merge_list(&mut self.tags, &layer.tags, layer.merge_modes.tags);
+        merge_list(&mut self.include, &layer.include, layer.merge_modes.include);
+        merge_list(
+            &mut self.extensions,
+            &layer.extensions,
+            layer.merge_modes.include,
+        );
+        merge_list(&mut self.exclude, &layer.exclude, layer.merge_modes.exclude);
+
+        self.json |= layer.json;
+        self.flat |= layer.flat;
+        self.no_color |= layer.no_color;
+        self.case_sensitive |= layer.case_sensitive;
+        self.strip_author |= layer.strip_author;
+
+        if layer.custom_pattern.is_some() {
+            self.custom_pattern = layer.custom_pattern.clone();
+        }
+        if layer.threads.is_some() {
+            self.threads = layer.threads;
+        }
+        if layer.parallel_files.is_some() {
+            self.parallel_files = layer.parallel_files;
+        }
+        if !layer.custom_patterns.is_empty() {
+            self.custom_patterns = layer.custom_patterns.clone();
+        }
+        if layer.ignore_pattern_file.is_some() {
+            self.ignore_pattern_file = layer.ignore_pattern_file.clone();
+        }
+        if layer.title.is_some() {
+            self.title = layer.title.clone();
+        }
+        if layer.color_scheme.is_some() {
+            self.color_scheme = layer.color_scheme;
+        }
+        if !layer.required_ticket_tags.is_empty() {
+            self.required_ticket_tags = layer.required_ticket_tags.clone();
+        }
+        if layer.ticket_regex.is_some() {
+            self.ticket_regex = layer.ticket_regex.clone();
+        }
+
+        self.tag_priorities.extend(
+            layer
+                .tag_priorities
+                .iter()
+                .map(|(tag, priority)| (tag.clone(), *priority)),
+        );
+        for (tag, rules) in &layer.extension_priorities {
+            self.extension_priorities
+                .entry(tag.clone())
+                .or_default()
+                .extend(rules.iter().map(|(key, priority)| (key.clone(), *priority)));
+        }
+        self.merge_modes = layer.merge_modes;
+    }
+
+    /// Get the priority for a tag
+    ///
+    /// Looks up `tag_priorities` first (case-insensitive) and falls back to
+    /// the built-in mapping of `Priority::from_tag`, as the parser does.
+    pub fn priority_for_tag(&self, tag: &str) -> Priority {
+        crate::parser::priority_for_tag(&self.tag_priorities, tag)
+    }
+
+    /// Get the priority for a tag found in a file with the given extension or name
+    ///
+    /// `ext` is an extension (`rs`) or a file name (`parser_test.rs`). Rule keys
+    /// starting with `_` or containing a `.` match the end of the file name; other
+    /// keys match the extension. The longest matching key wins, and without a
+    /// match this is [`priority_for_tag`](Self::priority_for_tag).
+    pub fn priority_for_tag_in_extension(&self, tag: &str, ext: &str) -> Priority {
+        let extension = Path::new(ext)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or(ext);
+
+        self.extension_priorities
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(tag))
+            .flat_map(|(_, rules)| rules)
+            .filter(|(key, _)| {
+                if key.starts_with('_') || key.contains('.') {
+                    ext.ends_with(key.as_str())
+                } else {
+                    key.eq_ignore_ascii_case(extension)
+                }
+            })
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, priority)| *priority)
+            .unwrap_or_else(|| self.priority_for_tag(tag))
+    }
+
+    /// Append the patterns from `ignore_pattern_file` to the exclude list
+    ///
+    /// Relative paths are resolved against `root`.
+    pub fn apply_ignore_pattern_file(&mut self, root: &Path) -> Result<()> {
+        if let Some(file) = &self.ignore_pattern_file {
+            let patterns = read_ignore_pattern_file(&root.join(file))?;
+            self.exclude.extend(patterns);
+        }
+
+        Ok(())
+    }
+
+    /// Export a schema describing the configuration file
+    ///
+    /// The schema is hand-written and must be kept in sync with the fields of [`Config`].
+    pub fn export_schema(format: SchemaFormat) -> String {
+        let schema = config_schema();
+        match format {
+            SchemaFormat::JsonSchema => {
+                serde_json::to_string_pretty(&schema).expect("schema is valid JSON") + "\n"
+            }
+            SchemaFormat::TomlSchema => {
+                toml::to_string_pretty(&schema).expect("schema has no null values")
+            }
+            SchemaFormat::YamlSchema => {
+                serde_yaml::to_string(&schema).expect("schema is valid YAML")
+            }
+        }
+    }
+
+    /// Export the TODO conventions of this configuration as `.editorconfig` comments
+    ///
+    /// Everything is commented out, so editors ignore the result: it only documents
+    /// which files are scanned or excluded and which tags are expected in them,
+    /// e.g. for onboarding contributors.
+    pub fn export_to_editorconfig(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# TODO conventions, generated by todo-tree\n");
+        out.push_str("# Documentation only: these comments are not EditorConfig settings.\n");
+        out.push_str("#\n");
+        out.push_str(&format!("# Tags: {}\n", self.tags.join(", ")));
+        if self.case_sensitive {
+            out.push_str("# Tags are matched case-sensitively.\n");
+        }
+
+        let mut priorities: Vec<_> = self.tag_priorities.iter().collect();
+        priorities.sort();
+        for (tag, priority) in priorities {
+            out.push_str(&format!("# {} priority: {}\n", tag, priority));
+        }
+        if let Some(regex) = &self.ticket_regex {
+            out.push_str(&format!(
+                "# {} must reference a ticket matching `{}`\n",
+                self.required_ticket_tags.join(", "),
+                regex
+            ));
+        }
+
+        let includes = self.include_patterns();
+        if includes.is_empty() {
+            out.push_str("\n# [*]\n# todo-tree = scanned\n");
+        }
+        for pattern in &includes {
+            out.push_str(&format!("\n# [{}]\n# todo-tree = scanned\n", pattern));
+
+            let extension = pattern.rsplit_once("*.").map(|(_, ext)| ext);
+            let mut rules: Vec<_> = self
+                .extension_priorities
+                .iter()
+                .filter_map(|(tag, rules)| {
+                    let priority = rules.get(extension?)?;
+                    Some((tag, priority))
+                })
+                .collect();
+            rules.sort();
+            for (tag, priority) in rules {
+                out.push_str(&format!("# {} priority: {}\n", tag, priority));
+            }
+        }
+        for pattern in &self.exclude {
+            out.push_str(&format!("\n# [{}]\n# todo-tree = excluded\n", pattern));
+        }
+
+        out
     }
 
     /// Save the current configuration to a file
@@ -178,6 +981,178 @@ impl Config {
     }
 }
 
+/// A change made by [`Config::upgrade`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeDiff {
+    /// Name of the field in the config file
+    pub field: String,
+
+    /// Old value, as shown to the user
+    pub before: String,
+
+    /// New value, as shown to the user
+    pub after: String,
+}
+
+impl std::fmt::Display for UpgradeDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.before, self.after)
+    }
+}
+
+/// JSON Schema (draft-07) for the configuration file
+fn config_schema() -> serde_json::Value {
+    let string_list = |description: &str| {
+        serde_json::json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "description": description,
+        })
+    };
+    let merge_mode = serde_json::json!({
+        "type": "string",
+        "enum": ["override", "extend"],
+    });
+
+    serde_json::json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "todo-tree configuration",
+        "description": "Configuration for todo-tree (.todorc, .todorc.json, .todorc.yaml)",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "tags": string_list("Tags to search for (e.g., TODO, FIXME, BUG)"),
+            "include": string_list("File patterns to include (glob patterns)"),
+            "extensions": string_list("File extensions to include (e.g., rs, toml), in addition to `include`"),
+            "exclude": string_list("File patterns to exclude (glob patterns)"),
+            "json": {
+                "type": "boolean",
+                "description": "Whether to output in JSON format",
+            },
+            "flat": {
+                "type": "boolean",
+                "description": "Whether to output in flat format (no tree structure)",
+            },
+            "no_color": {
+                "type": "boolean",
+                "description": "Whether to disable colored output",
+            },
+            "custom_pattern": {
+                "type": ["string", "null"],
+                "description": "Custom regex pattern for matching (advanced)",
+            },
+            "custom_patterns": string_list("Several custom regex patterns, tried in order (advanced)"),
+            "case_sensitive": {
+                "type": "boolean",
+                "description": "Case sensitive matching",
+            },
+            "threads": {
+                "type": ["integer", "null"],
+                "minimum": 0,
+                "description": "Number of directory walker threads (0 = auto)",
+            },
+            "parallel_files": {
+                "type": ["integer", "null"],
+                "minimum": 0,
+                "description": "Number of files parsed in parallel (default: number of CPUs)",
+            },
+            "ignore_pattern_file": {
+                "type": ["string", "null"],
+                "description": "File containing additional exclude patterns, one glob per line",
+            },
+            "tag_priorities": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "string",
+                    "enum": ["low", "medium", "high", "critical", "Low", "Medium", "High", "Critical"],
+                },
+                "description": "Priority overrides for tags (e.g., SECURITY: critical)",
+            },
+            "extension_priorities": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "string",
+                        "enum": ["low", "medium", "high", "critical", "Low", "Medium", "High", "Critical"],
+                    },
+                },
+                "description": "Per-tag priorities by file extension (rs) or file name suffix (_test.rs)",
+            },
+            "title": {
+                "type": ["string", "null"],
+                "description": "Title printed as the first line of scan output",
+            },
+            "color_scheme": {
+                "type": "string",
+                "enum": ["default", "solarized", "nord", "gruvbox", "high-contrast", "colorblind"],
+                "description": "Palette used for tag colors",
+            },
+            "strip_author": {
+                "type": "boolean",
+                "description": "Remove author names from all output",
+            },
+            "merge_modes": {
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "tags": merge_mode,
+                    "include": merge_mode,
+                    "exclude": merge_mode,
+                },
+                "description": "How this config's lists combine with earlier layers",
+            },
+            "required_ticket_tags": string_list("Tags that `verify` requires to reference a ticket (default: FIXME, BUG)"),
+            "ticket_regex": {
+                "type": ["string", "null"],
+                "description": "Regex a ticket reference in the author field must match for `verify`",
+            },
+        },
+    })
+}
+
+/// Parse a git config boolean (`true`/`yes`/`on`/`1` or `false`/`no`/`off`/`0`)
+fn parse_git_bool(value: &str) -> Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" | "" => Ok(false),
+        other => anyhow::bail!("Expected a boolean, got '{}'", other),
+    }
+}
+
+/// Combine a list from a later config layer into the accumulated list
+fn merge_list(target: &mut Vec<String>, layer: &[String], mode: MergeMode) {
+    if layer.is_empty() {
+        return;
+    }
+
+    match mode {
+        MergeMode::Override => *target = layer.to_vec(),
+        MergeMode::Extend => {
+            for value in layer {
+                if !target.contains(value) {
+                    target.push(value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Read glob patterns from an ignore pattern file
+///
+/// Blank lines and lines starting with `#` are skipped.
+pub fn read_ignore_pattern_file(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read ignore pattern file: {}", path.display()))?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,6 +1233,261 @@ flat: true
         assert!(config.no_color);
     }
 
+    #[test]
+    fn test_sanitize() {
+        let mut config = Config::new();
+        config.tags = vec![
+            " todo".to_string(),
+            "FIXME".to_string(),
+            "".to_string(),
+            "TODO".to_string(),
+            "fixme ".to_string(),
+            "Bug".to_string(),
+        ];
+
+        config.sanitize();
+        assert_eq!(config.tags, vec!["TODO", "FIXME", "BUG"]);
+    }
+
+    #[test]
+    fn test_sanitize_case_sensitive() {
+        let mut config = Config {
+            case_sensitive: true,
+            tags: vec![
+                "note".to_string(),
+                " NOTE".to_string(),
+                "note ".to_string(),
+                "".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        config.sanitize();
+        assert_eq!(config.tags, vec!["note", "NOTE"]);
+    }
+
+    #[test]
+    fn test_merge_with_cli_sanitizes_tags() {
+        let mut config = Config::new();
+        config.merge_with_cli(
+            Some(vec![
+                "todo".to_string(),
+                "fixme".to_string(),
+                "TODO".to_string(),
+            ]),
+            None,
+            None,
+            false,
+            false,
+            false,
+        );
+
+        assert_eq!(config.tags, vec!["TODO", "FIXME"]);
+    }
+
+    #[test]
+    fn test_include_patterns_with_extensions() {
+        let mut config = Config::new();
+        config.include = vec!["*.md".to_string(), "*.rs".to_string()];
+        config.extensions = vec![
+            "rs".to_string(),
+            ".toml".to_string(),
+            "*.py".to_string(),
+            String::new(),
+        ];
+
+        assert_eq!(
+            config.include_patterns(),
+            vec!["*.md", "*.rs", "*.toml", "*.py"]
+        );
+        assert!(Config::new().include_patterns().is_empty());
+    }
+
+    #[test]
+    fn test_layer_merge() {
+        let mut workspace = Config::new();
+        workspace.tags = vec!["TODO".to_string(), "FIXME".to_string()];
+        workspace.include = vec!["*.rs".to_string()];
+        workspace.exclude = vec!["target/**".to_string()];
+
+        let mut package = Config::new();
+        package.tags = vec!["FIXME".to_string(), "SECURITY".to_string()];
+        package.include = vec!["src/**".to_string()];
+        package.exclude = vec!["generated/**".to_string()];
+        package.json = true;
+        package.custom_pattern = Some("//\\s*($TAGS):(.*)".to_string());
+
+        let mut user = Config::new();
+        user.tags = vec!["BUG".to_string()];
+        user.merge_modes.tags = MergeMode::Override;
+        user.tag_priorities
+            .insert("SECURITY".to_string(), Priority::Critical);
+
+        let merged = Config::layer_merge(&[workspace, package, user]);
+
+        assert_eq!(merged.tags, vec!["BUG"]);
+        assert_eq!(merged.include, vec!["src/**"]);
+        assert_eq!(merged.exclude, vec!["target/**", "generated/**"]);
+        assert!(merged.json);
+        assert!(merged.custom_pattern.is_some());
+        assert_eq!(merged.priority_for_tag("SECURITY"), Priority::Critical);
+    }
+
+    #[test]
+    fn test_layer_merge_extends_tags_by_default() {
+        let mut base = Config::new();
+        base.tags = vec!["TODO".to_string()];
+        let mut layer = Config::new();
+        layer.tags = vec!["TODO".to_string(), "SECURITY".to_string()];
+
+        let merged = Config::layer_merge(&[base, layer]);
+        assert_eq!(merged.tags, vec!["TODO", "SECURITY"]);
+    }
+
+    #[test]
+    fn test_layer_merge_empty() {
+        let merged = Config::layer_merge(&[]);
+        assert_eq!(merged.tags, default_tags());
+    }
+
+    #[test]
+    fn test_load_partial_config_uses_default_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".todorc.json");
+        std::fs::write(&config_path, r#"{"exclude": ["target/**"]}"#).unwrap();
+
+        // The file itself stays partial, so it does not add tags to other layers
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert!(config.tags.is_empty());
+        assert_eq!(config.exclude, vec!["target/**"]);
+
+        let git = Config {
+            tags: vec!["SECURITY".to_string()],
+            ..Default::default()
+        };
+        let merged = Config::layer_merge(&[git, config.clone()]);
+        assert_eq!(merged.tags, vec!["SECURITY"]);
+
+        // On its own it falls back to the default tags once resolved
+        let resolved = Config::resolve(temp_dir.path(), Some(config)).unwrap();
+        assert_eq!(resolved.tags, default_tags());
+        assert_eq!(resolved.exclude, vec!["target/**"]);
+    }
+
+    #[test]
+    fn test_merge_defaults_keeps_set_fields() {
+        let mut config = Config {
+            tags: vec!["CUSTOM".to_string()],
+            ..Default::default()
+        };
+        config.merge_defaults();
+        assert_eq!(config.tags, vec!["CUSTOM"]);
+        assert_eq!(config.merge_modes, MergeModes::default());
+    }
+
+    #[test]
+    fn test_load_merge_modes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".todorc.json");
+        std::fs::write(&config_path, r#"{"merge_modes": {"exclude": "override"}}"#).unwrap();
+
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.merge_modes.exclude, MergeMode::Override);
+        assert_eq!(config.merge_modes.tags, MergeMode::Extend);
+    }
+
+    #[test]
+    fn test_priority_for_tag() {
+        let mut config = Config::new();
+        config
+            .tag_priorities
+            .insert("SECURITY".to_string(), Priority::Critical);
+        config
+            .tag_priorities
+            .insert("TODO".to_string(), Priority::Low);
+
+        assert_eq!(config.priority_for_tag("SECURITY"), Priority::Critical);
+        assert_eq!(config.priority_for_tag("security"), Priority::Critical);
+        assert_eq!(config.priority_for_tag("TODO"), Priority::Low);
+        assert_eq!(config.priority_for_tag("BUG"), Priority::Critical);
+        assert_eq!(config.priority_for_tag("NOTE"), Priority::Low);
+    }
+
+    #[test]
+    fn test_priority_for_tag_in_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".todorc.json");
+        std::fs::write(
+            &config_path,
+            r#"{"extension_priorities": {"FIXME": {"rs": "critical", "_test.rs": "medium"}}}"#,
+        )
+        .unwrap();
+        let config = Config::load_from_file(&config_path).unwrap();
+
+        assert_eq!(
+            config.priority_for_tag_in_extension("FIXME", "rs"),
+            Priority::Critical
+        );
+        assert_eq!(
+            config.priority_for_tag_in_extension("fixme", "lib.rs"),
+            Priority::Critical
+        );
+        assert_eq!(
+            config.priority_for_tag_in_extension("FIXME", "parser_test.rs"),
+            Priority::Medium
+        );
+        assert_eq!(
+            config.priority_for_tag_in_extension("FIXME", "py"),
+            config.priority_for_tag("FIXME")
+        );
+        assert_eq!(
+            config.priority_for_tag_in_extension("TODO", "rs"),
+            Priority::Medium
+        );
+    }
+
+    #[test]
+    fn test_load_tag_priorities() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".todorc.yaml");
+        std::fs::write(
+            &config_path,
+            "tags:\n  - SECURITY\ntag_priorities:\n  SECURITY: critical\n  BREAKING: High\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.priority_for_tag("SECURITY"), Priority::Critical);
+        assert_eq!(config.priority_for_tag("BREAKING"), Priority::High);
+    }
+
+    #[test]
+    fn test_apply_ignore_pattern_file() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("ignore.txt"),
+            "# generated code\nbuild/**\n\n  vendor/**  \n",
+        )
+        .unwrap();
+
+        let mut config = Config::new();
+        config.exclude = vec!["target/**".to_string()];
+        config.ignore_pattern_file = Some(PathBuf::from("ignore.txt"));
+        config.apply_ignore_pattern_file(temp_dir.path()).unwrap();
+
+        assert_eq!(config.exclude, vec!["target/**", "build/**", "vendor/**"]);
+    }
+
+    #[test]
+    fn test_apply_ignore_pattern_file_missing() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let mut config = Config::new();
+        config.ignore_pattern_file = Some(PathBuf::from("missing.txt"));
+
+        assert!(config.apply_ignore_pattern_file(temp_dir.path()).is_err());
+    }
+
     #[test]
     fn test_save_and_load() {
         let temp_dir = TempDir::new().unwrap();
@@ -412,7 +1642,10 @@ flat: true
             "flat": true,
             "no_color": true,
             "custom_pattern": "PATTERN",
-            "case_sensitive": true
+            "custom_patterns": ["FIRST", "SECOND"],
+            "case_sensitive": true,
+            "threads": 2,
+            "parallel_files": 8
         }"#;
 
         std::fs::write(&config_path, config_content).unwrap();
@@ -425,7 +1658,240 @@ flat: true
         assert!(config.flat);
         assert!(config.no_color);
         assert_eq!(config.custom_pattern, Some("PATTERN".to_string()));
+        assert_eq!(config.custom_patterns, vec!["FIRST", "SECOND"]);
         assert!(config.case_sensitive);
+        assert_eq!(config.threads, Some(2));
+        assert_eq!(config.parallel_files, Some(8));
+    }
+
+    #[test]
+    fn test_export_schema_covers_all_fields() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&Config::export_schema(SchemaFormat::JsonSchema)).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+
+        let config = serde_json::to_value(Config::new()).unwrap();
+        for key in config.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "schema is missing `{}`", key);
+        }
+        assert_eq!(properties.len(), config.as_object().unwrap().len());
+    }
+
+    #[test]
+    fn test_export_schema_formats() {
+        let json: serde_json::Value =
+            serde_json::from_str(&Config::export_schema(SchemaFormat::JsonSchema)).unwrap();
+
+        let yaml: serde_json::Value =
+            serde_yaml::from_str(&Config::export_schema(SchemaFormat::YamlSchema)).unwrap();
+        assert_eq!(yaml, json);
+
+        let toml: serde_json::Value =
+            toml::from_str(&Config::export_schema(SchemaFormat::TomlSchema)).unwrap();
+        assert_eq!(toml, json);
+    }
+
+    #[test]
+    fn test_export_to_editorconfig() {
+        let mut config = Config::new();
+        config.tags = vec!["TODO".to_string(), "FIXME".to_string()];
+        config.extensions = vec!["rs".to_string()];
+        config.exclude = vec!["target/**".to_string()];
+        config
+            .tag_priorities
+            .insert("TODO".to_string(), Priority::High);
+        config.extension_priorities.insert(
+            "FIXME".to_string(),
+            HashMap::from([("rs".to_string(), Priority::Critical)]),
+        );
+
+        let output = config.export_to_editorconfig();
+        assert!(
+            output
+                .lines()
+                .all(|line| line.is_empty() || line.starts_with('#'))
+        );
+        assert!(output.contains("# Tags: TODO, FIXME\n"));
+        assert!(output.contains("# TODO priority: High\n"));
+        assert!(output.contains("# [*.rs]\n# todo-tree = scanned\n# FIXME priority: Critical\n"));
+        assert!(output.contains("# [target/**]\n# todo-tree = excluded\n"));
+    }
+
+    #[test]
+    fn test_set_git_value() {
+        let mut config = Config::default();
+        config.set_git_value("tags", "TODO, FIXME").unwrap();
+        config.set_git_value("tags", "HACK").unwrap();
+        config.set_git_value("no-color", "yes").unwrap();
+        config.set_git_value("casesensitive", "true").unwrap();
+        config.set_git_value("parallel-files", "2").unwrap();
+        config
+            .set_git_value("custom-patterns", "(//)\\s*($TAGS){1,2}")
+            .unwrap();
+        config.set_git_value("color-scheme", "nord").unwrap();
+        config.set_git_value("strip-author", "on").unwrap();
+        config.set_git_value("unknown", "ignored").unwrap();
+
+        assert_eq!(config.tags, vec!["TODO", "FIXME", "HACK"]);
+        assert!(config.no_color);
+        assert!(config.case_sensitive);
+        assert_eq!(config.parallel_files, Some(2));
+        assert_eq!(config.color_scheme, Some(ColorScheme::Nord));
+        assert!(config.strip_author);
+        assert_eq!(config.custom_patterns, vec!["(//)\\s*($TAGS){1,2}"]);
+
+        assert!(config.set_git_value("json", "maybe").is_err());
+        assert!(config.set_git_value("threads", "many").is_err());
+    }
+
+    #[test]
+    fn test_from_git_config() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(Config::from_git_config(temp_dir.path()).unwrap().is_none());
+
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        assert!(Config::from_git_config(temp_dir.path()).unwrap().is_none());
+
+        git(&["config", "todo-tree.tags", "SECURITY,TODO"]);
+        git(&["config", "todo-tree.exclude", "vendor/**"]);
+        git(&["config", "todo-tree.json", "true"]);
+
+        let config = Config::from_git_config(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(config.tags, vec!["SECURITY", "TODO"]);
+        assert_eq!(config.exclude, vec!["vendor/**"]);
+        assert!(config.json);
+    }
+
+    #[test]
+    fn test_detect_language_from_include_patterns() {
+        let config = Config {
+            include: vec![
+                "*.py".to_string(),
+                "stubs/**/*.pyi".to_string(),
+                "web/*.{ts,tsx,css}".to_string(),
+                "Makefile".to_string(),
+            ],
+            extensions: vec!["rs".to_string()],
+            ..Config::new()
+        };
+
+        assert_eq!(
+            config.detect_language_from_include_patterns(),
+            vec![Language::Rust, Language::Python, Language::TypeScript]
+        );
+        assert!(
+            Config::new()
+                .detect_language_from_include_patterns()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_infer_from_project_root() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(Config::infer_from_project_root(temp_dir.path()).is_empty());
+
+        std::fs::write(temp_dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(
+            Config::infer_from_project_root(temp_dir.path()),
+            vec![Language::Rust, Language::JavaScript]
+        );
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let vars = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(
+            Config::from_vars(vars(&[("PATH", "/bin")]))
+                .unwrap()
+                .is_none()
+        );
+
+        let config = Config::from_vars(vars(&[
+            ("TODO_TREE_TAGS", "BUG,FIXME"),
+            ("TODO_TREE_NO_COLOR", "1"),
+            ("HOME", "/root"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.tags, vec!["BUG", "FIXME"]);
+        assert!(config.no_color);
+        assert_eq!(config.merge_modes.tags, MergeMode::Override);
+        assert_eq!(config.merge_modes.exclude, MergeMode::Override);
+
+        let file = Config {
+            tags: vec!["LOCAL".to_string()],
+            exclude: vec!["vendor/**".to_string()],
+            ..Default::default()
+        };
+        let env = Config::from_vars(vars(&[
+            ("TODO_TREE_TAGS", "BUG"),
+            ("TODO_TREE_EXCLUDE", "target/**"),
+        ]))
+        .unwrap()
+        .unwrap();
+        let merged = Config::layer_merge(&[file, env]);
+        assert_eq!(merged.tags, vec!["BUG"]);
+        assert_eq!(merged.exclude, vec!["target/**"]);
+
+        let err = Config::from_vars(vars(&[("TODO_TREE_THREADS", "many")])).unwrap_err();
+        assert!(err.to_string().contains("TODO_TREE_THREADS"));
+    }
+
+    #[test]
+    fn test_effective() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(".todorc.json"),
+            r#"{"tags": ["LOCAL"], "threads": 3}"#,
+        )
+        .unwrap();
+
+        let config = Config::effective(temp_dir.path()).unwrap();
+        assert_eq!(config.tags, vec!["LOCAL"]);
+        assert_eq!(config.threads, Some(3));
+
+        std::fs::write(temp_dir.path().join(".todorc.json"), r#"{"threads": 3}"#).unwrap();
+        let config = Config::effective(temp_dir.path()).unwrap();
+        assert_eq!(config.tags, default_tags());
+    }
+
+    #[test]
+    fn test_load_first_global_order() {
+        let home = TempDir::new().unwrap();
+        let system = TempDir::new().unwrap();
+        std::fs::write(system.path().join("config.json"), r#"{"tags": ["SYSTEM"]}"#).unwrap();
+
+        let dirs = [home.path().to_path_buf(), system.path().to_path_buf()];
+        let config = Config::load_first_global(&dirs).unwrap().unwrap();
+        assert_eq!(config.tags, vec!["SYSTEM"]);
+
+        // The user directory wins, whatever the file format
+        std::fs::write(home.path().join("config.yml"), "tags:\n  - HOME\n").unwrap();
+        let config = Config::load_first_global(&dirs).unwrap().unwrap();
+        assert_eq!(config.tags, vec!["HOME"]);
+
+        let empty = TempDir::new().unwrap();
+        assert!(
+            Config::load_first_global(&[empty.path().to_path_buf()])
+                .unwrap()
+                .is_none()
+        );
     }
 
     #[test]
@@ -449,6 +1915,132 @@ flat: true
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_upgrade() {
+        let mut config = Config::new();
+        config.custom_pattern = Some("OLD".to_string());
+        config.custom_patterns = vec!["NEW".to_string()];
+        config.extensions = vec!["*.rs".to_string(), ".toml".to_string(), "py".to_string()];
+
+        let diffs = config.upgrade();
+        assert_eq!(config.custom_pattern, None);
+        assert_eq!(config.custom_patterns, vec!["OLD", "NEW"]);
+        assert_eq!(config.extensions, vec!["rs", "toml", "py"]);
+        assert_eq!(
+            diffs
+                .iter()
+                .map(|diff| diff.field.as_str())
+                .collect::<Vec<_>>(),
+            vec!["custom_pattern", "custom_patterns", "extensions"]
+        );
+        assert_eq!(
+            diffs[2].to_string(),
+            r#"extensions: ["*.rs", ".toml", "py"] -> ["rs", "toml", "py"]"#
+        );
+
+        assert!(config.upgrade().is_empty());
+    }
+
+    #[test]
+    fn test_upgrade_file_keeps_other_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_path = temp_dir.path().join(".todorc.json");
+        std::fs::write(
+            &json_path,
+            r#"{"exclude": ["vendor/**"], "extensions": ["*.rs"]}"#,
+        )
+        .unwrap();
+
+        let diffs = Config::upgrade_file(&json_path, true).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&json_path).unwrap(),
+            "{\n  \"exclude\": [\n    \"vendor/**\"\n  ],\n  \"extensions\": [\n    \"rs\"\n  ]\n}\n"
+        );
+
+        let yaml_path = temp_dir.path().join(".todorc.yaml");
+        std::fs::write(&yaml_path, "tags:\n- BUG\ncustom_pattern: OLD\n").unwrap();
+
+        let diffs = Config::upgrade_file(&yaml_path, false).unwrap();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(
+            std::fs::read_to_string(&yaml_path).unwrap(),
+            "tags:\n- BUG\ncustom_pattern: OLD\n"
+        );
+
+        Config::upgrade_file(&yaml_path, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&yaml_path).unwrap(),
+            "tags:\n- BUG\ncustom_patterns:\n- OLD\n"
+        );
+        assert!(Config::upgrade_file(&yaml_path, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_from_package_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_json = temp_dir.path().join("package.json");
+        assert!(Config::from_package_json(&package_json).unwrap().is_none());
+
+        std::fs::write(&package_json, r#"{"name": "app", "eslint": {}}"#).unwrap();
+        assert!(Config::from_package_json(&package_json).unwrap().is_none());
+        assert!(Config::load(temp_dir.path()).is_ok());
+
+        std::fs::write(
+            &package_json,
+            r#"{"name": "app", "todo-tree": {"exclude": ["dist/**"]}}"#,
+        )
+        .unwrap();
+        let config = Config::from_package_json(&package_json).unwrap().unwrap();
+        assert_eq!(config.exclude, vec!["dist/**"]);
+        assert!(config.tags.is_empty());
+
+        let nested = temp_dir.path().join("src");
+        std::fs::create_dir(&nested).unwrap();
+        let config = Config::load(&nested).unwrap().unwrap();
+        assert_eq!(config.exclude, vec!["dist/**"]);
+
+        std::fs::write(temp_dir.path().join(".todorc.json"), r#"{"exclude": []}"#).unwrap();
+        let config = Config::load(temp_dir.path()).unwrap().unwrap();
+        assert!(config.exclude.is_empty());
+
+        std::fs::write(&package_json, r#"{"todo-tree": {"tags": 1}}"#).unwrap();
+        assert!(Config::from_package_json(&package_json).is_err());
+    }
+
+    #[test]
+    fn test_find_local_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(Config::find_local_file(&nested), None);
+
+        let config_path = temp_dir.path().join(".todorc.yaml");
+        std::fs::write(&config_path, "tags: [TODO]\n").unwrap();
+        assert_eq!(Config::find_local_file(&nested), Some(config_path));
+    }
+
+    #[test]
+    fn test_watch_and_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".todorc.json");
+        std::fs::write(&config_path, r#"{"tags": ["TODO"]}"#).unwrap();
+
+        let mut configs = Config::watch_and_reload(&config_path).unwrap();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || sender.send(configs.next().map(|config| config.tags)));
+
+        std::fs::write(&config_path, "{{{{{{").unwrap();
+        std::thread::sleep(Duration::from_millis(500));
+        std::fs::write(&config_path, r#"{"tags": ["FIXME", "HACK"]}"#).unwrap();
+
+        let tags = receiver
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap()
+            .unwrap();
+        assert_eq!(tags, vec!["FIXME", "HACK"]);
+    }
+
     #[test]
     fn test_load_from_file_nonexistent() {
         let result = Config::load_from_file(std::path::Path::new("/nonexistent/config.json"));
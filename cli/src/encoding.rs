@@ -0,0 +1,84 @@
+use anyhow::{Result, bail};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+
+/// Bytes looked at when deciding whether a file is binary
+pub const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Guess the encoding of a file's bytes
+///
+/// A byte order mark wins and valid UTF-8 is UTF-8; anything else is guessed by
+/// `chardetng`, which covers the legacy encodings of the Web (Windows-125x,
+/// ISO-8859-x, Shift-JIS, EUC-JP, GBK, Big5, EUC-KR, ...).
+pub fn detect(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, false)
+}
+
+/// Decode a file's bytes with the detected encoding
+///
+/// Files with a NUL byte near the start (and no UTF-16 byte order mark) are
+/// treated as binary and rejected, as reading them as UTF-8 would.
+pub fn decode(bytes: &[u8]) -> Result<(String, &'static Encoding)> {
+    let encoding = detect(bytes);
+    if encoding != encoding_rs::UTF_16LE && encoding != encoding_rs::UTF_16BE && is_binary(bytes) {
+        bail!("binary content");
+    }
+
+    let (text, _, _) = encoding.decode(bytes);
+    Ok((text.into_owned(), encoding))
+}
+
+/// Whether the start of a file holds a NUL byte, which text files do not
+pub fn is_binary(head: &[u8]) -> bool {
+    head[..head.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{EUC_KR, GBK, SHIFT_JIS, WINDOWS_1251, WINDOWS_1252};
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect("// TODO: café".as_bytes()), UTF_8);
+        assert_eq!(detect(b"// TODO: caf\xe9"), WINDOWS_1252);
+
+        let (sjis, _, _) = SHIFT_JIS.encode("// TODO: 修正すること");
+        assert_eq!(detect(&sjis), SHIFT_JIS);
+
+        assert_eq!(detect(b"\xff\xfe/\x00/\x00"), encoding_rs::UTF_16LE);
+
+        for (encoding, text) in [
+            (GBK, "// TODO: 修复这个函数中的内存泄漏问题"),
+            (EUC_KR, "// TODO: 이 함수의 메모리 누수 문제를 수정하세요"),
+            (
+                WINDOWS_1251,
+                "// TODO: исправить утечку памяти в этой функции",
+            ),
+        ] {
+            let (bytes, _, _) = encoding.encode(text);
+            assert_eq!(detect(&bytes), encoding, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let (text, encoding) = decode(b"// FIXME: une id\xe9e na\xefve, \xe0 revoir").unwrap();
+        assert_eq!(text, "// FIXME: une idée naïve, à revoir");
+        assert_eq!(encoding, WINDOWS_1252);
+
+        let (sjis, _, _) = SHIFT_JIS.encode("// TODO: 修正すること\n");
+        assert_eq!(decode(&sjis).unwrap().0, "// TODO: 修正すること\n");
+
+        assert!(decode(b"\x89PNG\r\n\x1a\n\x00\x00").is_err());
+    }
+}
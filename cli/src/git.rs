@@ -0,0 +1,387 @@
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use todo_tree_core::ScanResult;
+
+/// Line numbers (1-based) added per file, keyed by absolute path
+pub type AddedLines = HashMap<PathBuf, HashSet<usize>>;
+
+/// Get the lines added in the working tree since a commit
+///
+/// Runs `git diff <commit> -U0` inside `dir`. Paths in the result are joined
+/// onto `dir`, so they match the paths produced by the scanner.
+pub fn added_lines_since(dir: &Path, commit: &str) -> Result<AddedLines> {
+    let output = Command::new("git")
+        .args([
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--relative",
+            "-U0",
+            commit,
+            "--",
+        ])
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git diff")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git diff {} failed: {}",
+            commit,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let diff = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_added_lines(&diff)
+        .into_iter()
+        .map(|(path, lines)| (dir.join(path), lines))
+        .collect())
+}
+
+/// Parse the added line numbers out of a unified diff
+///
+/// Paths are taken from the `+++ b/<path>` headers; deleted files are skipped.
+pub fn parse_added_lines(diff: &str) -> AddedLines {
+    let mut added = AddedLines::new();
+    let mut current: Option<PathBuf> = None;
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            current = path.strip_prefix("b/").map(PathBuf::from);
+        } else if let Some(hunk) = line.strip_prefix("@@ ")
+            && let Some(path) = &current
+            && let Some((start, count)) = parse_hunk_target(hunk)
+        {
+            added
+                .entry(path.clone())
+                .or_default()
+                .extend(start..start + count);
+        }
+    }
+
+    added
+}
+
+/// Parse the `+start[,count]` range of a hunk header (after the leading `@@ `)
+fn parse_hunk_target(hunk: &str) -> Option<(usize, usize)> {
+    let range = hunk.split_whitespace().find_map(|r| r.strip_prefix('+'))?;
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Keep only the items that sit on added lines
+pub fn filter_added_lines(result: &ScanResult, added: &AddedLines) -> ScanResult {
+    let root = result.root.clone().unwrap_or_else(|| PathBuf::from("."));
+    let mut filtered = ScanResult::new(root);
+
+    for (path, items) in &result.files_map {
+        let Some(lines) = added.get(path) else {
+            continue;
+        };
+
+        let items: Vec<_> = items
+            .iter()
+            .filter(|item| lines.contains(&item.line))
+            .cloned()
+            .collect();
+
+        if !items.is_empty() {
+            filtered.add_file(path.clone(), items);
+        }
+    }
+
+    filtered.summary.files_scanned = result.summary.files_scanned;
+    filtered.summary.truncated = result.summary.truncated;
+    filtered.summary.timed_out = result.summary.timed_out;
+    filtered
+}
+
+/// Get the author timestamp (seconds since the epoch) of the given lines of a file
+///
+/// Runs `git blame --line-porcelain` with one `-L` range per line. Fails when
+/// the file is not tracked or not inside a git repository. Uncommitted lines get
+/// the current time.
+pub fn blame_timestamps(path: &Path, lines: &[usize]) -> Result<HashMap<usize, i64>> {
+    let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+        anyhow::bail!("Not a file: {}", path.display());
+    };
+    if lines.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut command = Command::new("git");
+    command.args(["blame", "--line-porcelain"]);
+    for line in lines {
+        command.arg(format!("-L{},{}", line, line));
+    }
+    let output = command
+        .arg("--")
+        .arg(file_name)
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git blame")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git blame {} failed: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(parse_blame_timestamps(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse the `author-time` of each final line number out of `git blame --line-porcelain`
+pub fn parse_blame_timestamps(porcelain: &str) -> HashMap<usize, i64> {
+    let mut timestamps = HashMap::new();
+    let mut current: Option<usize> = None;
+
+    for line in porcelain.lines() {
+        if line.starts_with('\t') {
+            current = None;
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            if let (Some(line_number), Ok(time)) = (current, time.trim().parse()) {
+                timestamps.insert(line_number, time);
+            }
+        } else if current.is_none() {
+            // Header: <sha> <original line> <final line> [<group size>]
+            let mut fields = line.split_whitespace();
+            let is_sha = fields
+                .next()
+                .is_some_and(|sha| sha.len() >= 40 && sha.chars().all(|c| c.is_ascii_hexdigit()));
+            current = fields
+                .nth(1)
+                .and_then(|n| n.parse().ok())
+                .filter(|_| is_sha);
+        }
+    }
+
+    timestamps
+}
+
+/// Get the repository name from the `origin` remote of the repository containing `dir`
+pub fn origin_repo_name(dir: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .current_dir(dir)
+        .output()
+        .context("Failed to run git remote")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git remote get-url origin failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let url = String::from_utf8_lossy(&output.stdout);
+    repo_name_from_url(url.trim())
+        .with_context(|| format!("Cannot find a repository name in: {}", url.trim()))
+}
+
+/// Extract the repository name from a remote URL (`git@host:org/name.git` -> `name`)
+pub fn repo_name_from_url(url: &str) -> Option<String> {
+    let name = url
+        .trim_end_matches('/')
+        .rsplit(['/', ':'])
+        .next()?
+        .trim_end_matches(".git");
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+    use todo_tree_core::{Priority, TodoItem};
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn create_test_item(tag: &str, line: usize) -> TodoItem {
+        TodoItem {
+            tag: tag.to_string(),
+            message: "message".to_string(),
+            line,
+            column: 4,
+            line_content: None,
+            author: None,
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_added_lines() {
+        let diff = "diff --git a/src/main.rs b/src/main.rs\n\
+            --- a/src/main.rs\n\
+            +++ b/src/main.rs\n\
+            @@ -3,0 +4,2 @@ fn main() {\n\
+            +// TODO: one\n\
+            +// TODO: two\n\
+            @@ -10 +12 @@\n\
+            -old\n\
+            +new\n\
+            @@ -20,2 +21,0 @@\n\
+            diff --git a/gone.rs b/gone.rs\n\
+            --- a/gone.rs\n\
+            +++ /dev/null\n\
+            @@ -1 +0,0 @@\n";
+
+        let added = parse_added_lines(diff);
+        assert_eq!(added.len(), 1);
+
+        let mut lines: Vec<_> = added[Path::new("src/main.rs")].iter().copied().collect();
+        lines.sort();
+        assert_eq!(lines, vec![4, 5, 12]);
+    }
+
+    #[test]
+    fn test_filter_added_lines() {
+        let mut result = ScanResult::new(PathBuf::from("/project"));
+        result.add_file(
+            PathBuf::from("/project/a.rs"),
+            vec![create_test_item("TODO", 1), create_test_item("FIXME", 5)],
+        );
+        result.add_file(
+            PathBuf::from("/project/b.rs"),
+            vec![create_test_item("TODO", 2)],
+        );
+
+        let added = AddedLines::from([(PathBuf::from("/project/a.rs"), HashSet::from([5, 6]))]);
+        let filtered = filter_added_lines(&result, &added);
+
+        assert_eq!(filtered.summary.total_count, 1);
+        assert_eq!(filtered.summary.files_scanned, 2);
+        assert_eq!(
+            filtered.files_map[Path::new("/project/a.rs")][0].tag,
+            "FIXME"
+        );
+    }
+
+    #[test]
+    fn test_parse_blame_timestamps() {
+        let sha = "a".repeat(40);
+        let porcelain = format!(
+            "{sha} 1 3 1\n\
+             author alice\n\
+             author-time 1700000000\n\
+             filename main.rs\n\
+             \t// TODO: one\n\
+             {sha} 4 7\n\
+             author bob\n\
+             author-time 1600000000\n\
+             \t// TODO: two\n"
+        );
+
+        let timestamps = parse_blame_timestamps(&porcelain);
+        assert_eq!(
+            timestamps,
+            HashMap::from([(3, 1700000000), (7, 1600000000)])
+        );
+    }
+
+    #[test]
+    fn test_blame_timestamps() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        git(dir, &["init", "-q"]);
+        fs::write(dir.join("main.rs"), "// TODO: old\n").unwrap();
+        git(dir, &["add", "."]);
+        git(
+            dir,
+            &["commit", "-qm", "first", "--date=2020-01-01T00:00:00Z"],
+        );
+        fs::write(dir.join("main.rs"), "// TODO: old\n// TODO: new\n").unwrap();
+        git(
+            dir,
+            &["commit", "-qam", "second", "--date=2021-01-01T00:00:00Z"],
+        );
+
+        let timestamps = blame_timestamps(&dir.join("main.rs"), &[1, 2]).unwrap();
+        assert_eq!(timestamps[&1], 1577836800);
+        assert_eq!(timestamps[&2], 1609459200);
+
+        fs::write(dir.join("untracked.rs"), "// TODO: x\n").unwrap();
+        assert!(blame_timestamps(&dir.join("untracked.rs"), &[1]).is_err());
+    }
+
+    #[test]
+    fn test_repo_name_from_url() {
+        assert_eq!(
+            repo_name_from_url("https://github.com/yonasBSD/todo-tree.git").as_deref(),
+            Some("todo-tree")
+        );
+        assert_eq!(
+            repo_name_from_url("git@github.com:yonasBSD/todo-tree").as_deref(),
+            Some("todo-tree")
+        );
+        assert_eq!(
+            repo_name_from_url("/srv/git/project/").as_deref(),
+            Some("project")
+        );
+        assert_eq!(repo_name_from_url(""), None);
+    }
+
+    #[test]
+    fn test_origin_repo_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        git(dir, &["init", "-q"]);
+        assert!(origin_repo_name(dir).is_err());
+
+        git(
+            dir,
+            &[
+                "remote",
+                "add",
+                "origin",
+                "https://example.com/team/widgets.git",
+            ],
+        );
+        assert_eq!(origin_repo_name(dir).unwrap(), "widgets");
+    }
+
+    #[test]
+    fn test_added_lines_since() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        git(dir, &["init", "-q"]);
+        fs::write(dir.join("main.rs"), "// TODO: old\nfn main() {}\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-qm", "initial"]);
+
+        fs::write(
+            dir.join("main.rs"),
+            "// TODO: old\n// FIXME: new\nfn main() {}\n",
+        )
+        .unwrap();
+
+        let added = added_lines_since(dir, "HEAD").unwrap();
+        assert_eq!(added[&dir.join("main.rs")], HashSet::from([2]));
+
+        assert!(added_lines_since(dir, "no-such-commit").is_err());
+    }
+}
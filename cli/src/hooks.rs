@@ -0,0 +1,115 @@
+use todo_tree_core::{DEFAULT_TAGS, Priority};
+
+/// Template for the generated `pre-push` git hook
+///
+/// Placeholders:
+/// - `{{max_critical}}` - Maximum number of critical items allowed
+/// - `{{tags}}`         - Comma-separated list of tags counted as critical
+const PRE_PUSH_TEMPLATE: &str = r#"#!/bin/sh
+#
+# todo-tree pre-push hook
+#
+# Generated by `todo-tree init --pre-push`. Blocks the push when the number of
+# critical TODO items exceeds the configured threshold. When GITHUB_TOKEN is
+# set and the remote is hosted on GitHub, a comment is posted on the open pull
+# request for the current branch.
+
+url="$2"
+
+MAX_CRITICAL={{max_critical}}
+TAGS="{{tags}}"
+
+if command -v tt >/dev/null 2>&1; then
+    TT=tt
+elif command -v todo-tree >/dev/null 2>&1; then
+    TT=todo-tree
+else
+    echo "todo-tree: binary not found in PATH, skipping pre-push check" >&2
+    exit 0
+fi
+
+# Fail closed: a push is only allowed once the count is known
+if ! stats=$("$TT" --no-color stats --tags "$TAGS" --min-priority high --json); then
+    echo "todo-tree: counting critical TODO items failed, blocking push" >&2
+    exit 1
+fi
+count=$(printf '%s' "$stats" | grep -o '"total_items": *[0-9]*' | grep -o '[0-9]*$')
+if [ -z "$count" ]; then
+    echo "todo-tree: could not read the critical TODO count, blocking push" >&2
+    exit 1
+fi
+
+if [ "$count" -le "$MAX_CRITICAL" ]; then
+    exit 0
+fi
+
+message="todo-tree: found $count critical TODO items ($TAGS), maximum allowed is $MAX_CRITICAL"
+echo "$message" >&2
+
+if [ -n "$GITHUB_TOKEN" ]; then
+    repo=$(printf '%s' "$url" | sed -n 's#.*github\.com[:/]\([^/]*/[^/]*\)$#\1#p' | sed 's/\.git$//')
+    branch=$(git rev-parse --abbrev-ref HEAD)
+
+    if [ -n "$repo" ]; then
+        api="https://api.github.com/repos/$repo"
+        pr=$(curl -fsS \
+            -H "Authorization: Bearer $GITHUB_TOKEN" \
+            -H "Accept: application/vnd.github+json" \
+            "$api/pulls?head=${repo%%/*}:$branch&state=open" \
+            | grep -o '"number": *[0-9]*' | head -n 1 | grep -o '[0-9]*$')
+
+        if [ -n "$pr" ]; then
+            # Escape the message for a JSON string; tags come from user configuration
+            body=$(printf '%s' "$message" | tr -d '[:cntrl:]' | sed -e 's/\\/\\\\/g' -e 's/"/\\"/g')
+            curl -fsS -X POST \
+                -H "Authorization: Bearer $GITHUB_TOKEN" \
+                -H "Accept: application/vnd.github+json" \
+                -d "{\"body\": \"$body\"}" \
+                "$api/issues/$pr/comments" >/dev/null \
+                || echo "todo-tree: failed to post comment on pull request #$pr" >&2
+        fi
+    fi
+fi
+
+exit 1
+"#;
+
+/// Tags considered critical by the generated hooks
+pub fn critical_tags() -> Vec<String> {
+    DEFAULT_TAGS
+        .iter()
+        .filter(|t| t.priority == Priority::Critical)
+        .map(|t| t.name.to_string())
+        .collect()
+}
+
+/// Render the `pre-push` hook script for the given threshold and tags
+pub fn pre_push_script(max_critical: usize, tags: &[String]) -> String {
+    PRE_PUSH_TEMPLATE
+        .replace("{{max_critical}}", &max_critical.to_string())
+        .replace("{{tags}}", &tags.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_critical_tags() {
+        let tags = critical_tags();
+        assert!(tags.contains(&"BUG".to_string()));
+        assert!(tags.contains(&"FIXME".to_string()));
+        assert!(!tags.contains(&"TODO".to_string()));
+    }
+
+    #[test]
+    fn test_pre_push_script() {
+        let script = pre_push_script(3, &["BUG".to_string(), "FIXME".to_string()]);
+
+        assert!(script.starts_with("#!/bin/sh"));
+        assert!(script.contains("MAX_CRITICAL=3\n"));
+        assert!(script.contains("TAGS=\"BUG,FIXME\"\n"));
+        assert!(script.contains("stats --tags \"$TAGS\" --min-priority high --json"));
+        assert!(script.contains("GITHUB_TOKEN"));
+    }
+}
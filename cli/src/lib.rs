@@ -1,18 +1,31 @@
+pub mod archive;
 pub mod cli;
 pub mod config;
+pub mod encoding;
+pub mod git;
+pub mod hooks;
+pub mod lsp;
 pub mod parser;
 pub mod printer;
 pub mod scanner;
+pub mod snapshot;
+pub mod template;
+pub mod tui;
+pub mod verify;
+pub mod workspace;
 
-pub use todo_tree_core::{Priority, ScanResult, Summary, TodoItem};
+pub use todo_tree_core::{ItemFilter, Priority, ScanResult, Summary, TodoItem};
 
 use anyhow::Result;
 use cli::{Cli, Commands, ConfigFormat, ScanArgs, SortOrder};
 use config::Config;
 use parser::{TodoParser, priority_to_color};
-use printer::{OutputFormat, PrintOptions, Printer};
+use printer::{ColorMode, OutputFormat, Pagination, PrintOptions, Printer};
+use rayon::prelude::*;
 use scanner::{ScanOptions, Scanner};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Main entry point for the CLI application
 pub fn run() -> Result<()> {
@@ -23,81 +36,633 @@ pub fn run() -> Result<()> {
         colored::control::set_override(false);
     }
 
+    let command = cli.get_command();
+    let log_level = match &command {
+        Commands::Scan(args) if args.quiet => {
+            if cli.global.verbose || cli.global.debug || cli.global.log_level.is_some() {
+                // Logged before the quiet subscriber is installed, which would drop it
+                let subscriber = log_subscriber(cli::LogLevel::Warn, cli.global.no_color);
+                tracing::subscriber::with_default(subscriber, || {
+                    tracing::warn!("--quiet overrides --verbose");
+                });
+            }
+            cli::LogLevel::Error
+        }
+        _ => cli.global.effective_log_level(),
+    };
+    init_logging(log_level, cli.global.no_color);
+
     // Execute the command
-    match cli.get_command() {
+    match command {
         Commands::Scan(args) => cmd_scan(args, &cli.global),
         Commands::List(args) => cmd_list(args, &cli.global),
         Commands::Tags(args) => cmd_tags(args, &cli.global),
         Commands::Init(args) => cmd_init(args),
         Commands::Stats(args) => cmd_stats(args, &cli.global),
+        Commands::Lsp(args) => cmd_lsp(args, &cli.global),
+        Commands::Snapshot(args) => cmd_snapshot(args, &cli.global),
+        Commands::Parse(args) => cmd_parse(args, &cli.global),
+        Commands::Pattern(args) => cmd_pattern(args, &cli.global),
+        Commands::Show(args) => cmd_show(args, &cli.global),
+        Commands::Verify(args) => cmd_verify(args, &cli.global),
+        Commands::Schema(args) => cmd_schema(args),
+        Commands::Config(args) => cmd_config(args, &cli.global),
+        Commands::Interactive(args) => cmd_interactive(args, &cli.global),
     }
 }
 
+/// Send `tracing` output to stderr, so it never mixes with results on stdout
+fn init_logging(level: cli::LogLevel, no_color: bool) {
+    // Fails only if a subscriber is already set, e.g. when called twice in tests
+    let _ = tracing::subscriber::set_global_default(log_subscriber(level, no_color));
+}
+
+/// Subscriber writing `tracing` output at `level` and above to stderr
+fn log_subscriber(level: cli::LogLevel, no_color: bool) -> impl tracing::Subscriber {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(tracing::level_filters::LevelFilter::from(level))
+        .with_target(false)
+        .without_time()
+        .with_ansi(!no_color)
+        .finish()
+}
+
 /// Execute the scan command
 fn cmd_scan(args: ScanArgs, global: &cli::GlobalOptions) -> Result<()> {
-    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    if args.fail_above.is_some() && scan_output_format(&args) != OutputFormat::Tap {
+        anyhow::bail!("--fail-above only applies to TAP output; add --format tap");
+    }
+
+    let path = match &args.workspaces_from {
+        Some(manifest) => workspace::root(manifest).to_path_buf(),
+        None => args.path.clone().unwrap_or_else(|| PathBuf::from(".")),
+    };
     let path = path
         .canonicalize()
         .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
 
     // Load configuration
-    let mut config = load_config(&path, global.config.as_deref())?;
+    let mut config = load_global_config(&path, global)?;
+    merge_scan_args(&mut config, &args, global)?;
+
+    if args.write_config || args.write_config_only {
+        ensure_config_writable(global)?;
+        let mut file_config = load_file_config(&path, global)?;
+        merge_scan_args(&mut file_config, &args, global)?;
+        let written = save_config(&config_with_scan_args(&file_config, &args))?;
+        if !args.quiet {
+            eprintln!("Wrote configuration to {}", written.display());
+        }
+        if args.write_config_only {
+            return Ok(());
+        }
+    }
 
-    // Merge CLI options
-    config.merge_with_cli(
-        args.tags.clone(),
-        args.include.clone(),
-        args.exclude.clone(),
-        args.json,
-        args.flat,
-        global.no_color,
-    );
+    if args.emit_config {
+        eprintln!(
+            "{}",
+            serde_json::to_string_pretty(&config_with_scan_args(&config, &args))?
+        );
+    }
+
+    config.apply_ignore_pattern_file(&path)?;
 
     // Create parser
-    let parser = TodoParser::new(&config.tags, args.case_sensitive);
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
 
-    // Create scan options
-    let scan_options = ScanOptions {
-        include: config.include.clone(),
-        exclude: config.exclude.clone(),
-        max_depth: args.depth,
-        follow_links: args.follow_links,
-        hidden: args.hidden,
-        threads: 0, // Auto
-        respect_gitignore: true,
+    // Create scanner and scan
+    let scanner = Scanner::new(parser, scan_options(&args, &config));
+    if args.list_files {
+        let files = scanner.dry_run(&path)?;
+        return print_file_list(&files, &path, scan_output_format(&args));
+    }
+    if args.stream {
+        let title = scan_title(&args, &config);
+        let print_options = scan_print_options(&args, &config, global, &path, title, None);
+        let mut stdout = std::io::stdout().lock();
+        return stream_scan(&scanner, &args, &config, &path, print_options, &mut stdout);
+    }
+
+    let mut result = if args.stdin_filelist {
+        use std::io::BufRead;
+
+        let files = std::io::stdin()
+            .lock()
+            .lines()
+            .collect::<std::io::Result<Vec<_>>>()
+            .context("Failed to read file list from stdin")?;
+        let files = files
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from);
+        scanner.scan_files(&path, files)?
+    } else if let Some(manifest) = &args.workspaces_from {
+        let mut members = workspace::members(manifest)?;
+        if let Some(names) = &args.workspace_filter {
+            members = workspace::filter(members, names)?;
+        }
+        scan_workspace(&scanner, &path, &members)?
+    } else if let Some(seconds) = args.timeout {
+        scanner.scan_with_timeout(&path, Duration::from_secs(seconds))?
+    } else {
+        scanner.scan(&path)?
     };
 
-    // Create scanner and scan
-    let scanner = Scanner::new(parser, scan_options);
-    let mut result = scanner.scan(&path)?;
+    if let Some(commit) = &args.since_commit {
+        let dir = if path.is_file() {
+            path.parent().unwrap_or(&path)
+        } else {
+            &path
+        };
+        let added = git::added_lines_since(dir, commit)?;
+        result = git::filter_added_lines(&result, &added);
+    }
+
+    if args.strip_author || config.strip_author {
+        result.strip_authors();
+    }
+    apply_extension_priorities(&mut result, &config);
+
+    if result.summary.timed_out {
+        tracing::info!(
+            "stopped after {} files (--timeout), results are incomplete",
+            result.summary.files_scanned
+        );
+    } else if result.summary.truncated {
+        tracing::info!(
+            "stopped after {} files (--max-files), results are incomplete",
+            result.summary.files_scanned
+        );
+    }
 
     // Sort results if needed
-    sort_results(&mut result, args.sort);
+    sort_results(
+        &mut result,
+        args.sort,
+        args.sort_within_file,
+        args.sort_reverse,
+    );
+
+    // Coverage is judged on the whole result, not the page being shown
+    let coverage_summary = result.summary.clone();
+
+    let benchmark = args
+        .benchmark
+        .then(|| benchmark_first_file(&scanner, &result))
+        .transpose()?;
+
+    let pagination = if args.first.is_some() || args.last.is_some() || args.offset.is_some() {
+        let (page, pagination) = paginate(
+            &result,
+            args.offset.unwrap_or(0),
+            args.first,
+            args.last,
+            args.sort_reverse,
+        );
+        result = page;
+        Some(pagination)
+    } else {
+        None
+    };
+
+    // Render through a user template if requested
+    if let Some(template_file) = &args.output_template_file {
+        print!("{}", template::render_file(template_file, &result)?);
+        print_benchmark(benchmark.as_ref(), &path);
+        return check_coverage(&coverage_summary, args.coverage_threshold);
+    }
 
     // Print results
-    let print_options = PrintOptions {
-        format: if args.json {
-            OutputFormat::Json
-        } else if args.flat {
-            OutputFormat::Flat
-        } else {
-            OutputFormat::Tree
-        },
+    let format = scan_output_format(&args);
+    let title = scan_title(&args, &config);
+    let mut print_options = scan_print_options(&args, &config, global, &path, title, pagination);
+    if args.stats_after_scan && format == OutputFormat::Json {
+        print_options.statistics = Some(stats_json(&result.summary));
+    }
+
+    let printer = Printer::new(print_options);
+    printer.print(&result)?;
+
+    if args.stats_after_scan
+        && matches!(
+            format,
+            OutputFormat::Tree | OutputFormat::Flat | OutputFormat::Compact | OutputFormat::Table
+        )
+    {
+        println!();
+        print_stats(&result.summary, &config, global.no_color, None);
+    }
+    print_benchmark(benchmark.as_ref(), &path);
+
+    check_coverage(&coverage_summary, args.coverage_threshold)
+}
+
+/// Build the scanner options for the scan command
+fn scan_options(args: &ScanArgs, config: &Config) -> ScanOptions {
+    ScanOptions {
+        include: config.include_patterns(),
+        exclude: config.exclude.clone(),
+        max_depth: args.depth,
+        no_walk: args.no_walk,
+        follow_links: args.follow_links,
+        hidden: args.hidden,
+        threads: args.threads.or(config.threads).unwrap_or(0), // 0 = auto
+        parallel_files: args
+            .parallel_files
+            .or(config.parallel_files)
+            .unwrap_or_else(num_cpus::get),
+        respect_gitignore: !args.no_gitignore,
+        respect_svnignore: args.respect_svnignore,
+        respect_dockerignore: args.respect_dockerignore,
+        include_vcs_dirs: args.include_vcs_dirs,
+        max_files: args.max_files,
+        before_context: args.before_context,
+        after_context: args.after_context,
+        scan_archives: args.scan_archives,
+        encoding_detection: args.detect_encoding,
+        ..Default::default()
+    }
+}
+
+/// Build the print options for the scan command
+fn scan_print_options(
+    args: &ScanArgs,
+    config: &Config,
+    global: &cli::GlobalOptions,
+    path: &std::path::Path,
+    title: Option<String>,
+    pagination: Option<Pagination>,
+) -> PrintOptions {
+    let format = scan_output_format(args);
+    let message_prefix = args
+        .message_prefix
+        .as_deref()
+        .map(|prefix| expand_message_prefix(prefix, path));
+    PrintOptions {
+        format,
         colored: !global.no_color,
         show_line_numbers: true,
         full_paths: false,
-        clickable_links: !global.no_color,
-        base_path: Some(path),
-        show_summary: !args.json,
+        clickable_links: args.hyperlinks || (!args.no_hyperlinks && !global.no_color),
+        force_hyperlinks: args.hyperlinks,
+        base_path: Some(path.to_path_buf()),
+        show_summary: !matches!(
+            format,
+            OutputFormat::Json
+                | OutputFormat::Tap
+                | OutputFormat::Checkstyle
+                | OutputFormat::TeamCity
+                | OutputFormat::SonarQube
+                | OutputFormat::Ctags
+                | OutputFormat::CtagsEtags
+                | OutputFormat::LspDiagnostics
+                | OutputFormat::Prometheus
+                | OutputFormat::OpenMetrics
+                | OutputFormat::CsvWithHeader
+                | OutputFormat::TsvWithHeader
+                | OutputFormat::XUnit
+                | OutputFormat::Dot
+        ) && !args.no_summary
+            && !args.quiet,
         group_by_tag: args.group_by_tag,
+        group_by_author: args.group_by_author,
+        sort: args.sort,
+        sort_reverse: args.sort_reverse,
+        color_mode: if args.color_by_file {
+            ColorMode::ByFile
+        } else {
+            ColorMode::ByTag
+        },
+        color_scheme: args
+            .color_scheme
+            .or(config.color_scheme)
+            .unwrap_or_default(),
+        inline_counts: args.inline_counts && !args.no_inline_counts,
+        title,
+        suppress_header: args.suppress_header,
+        json_compact: args.json_compact,
+        json_schema_version: args.json_schema_version,
+        pagination,
+        fail_above: args.fail_above,
+        message_prefix,
+        before_context: args.before_context,
+        after_context: args.after_context,
+        truncate_paths: args.truncate_paths,
+        highlight_matches: args.highlight_matches,
+        strip_author: args.strip_author || config.strip_author,
+        ..Default::default()
+    }
+}
+
+/// Print each file's items as soon as the scanner reports them (`scan --stream`)
+///
+/// Items are sorted within their file, but files are printed in the order they
+/// are parsed. The tree holds back one file until the next arrives, so the last
+/// entry can be drawn with `└──`. The summary, if shown, comes after the last file.
+fn stream_scan<W: std::io::Write>(
+    scanner: &Scanner,
+    args: &ScanArgs,
+    config: &Config,
+    path: &std::path::Path,
+    print_options: PrintOptions,
+    writer: &mut W,
+) -> Result<()> {
+    use colored::Colorize;
+
+    if !matches!(
+        print_options.format,
+        OutputFormat::Tree | OutputFormat::Flat | OutputFormat::Compact
+    ) {
+        anyhow::bail!("--stream only supports the tree, flat and compact formats");
+    }
+
+    let show_summary = print_options.show_summary;
+    let tree_by_file = print_options.format == OutputFormat::Tree
+        && !print_options.group_by_tag
+        && !print_options.group_by_author;
+    let printer = Printer::new(print_options.clone());
+    let file_printer = Printer::new(PrintOptions {
+        show_summary: false,
+        title: None,
+        ..print_options
+    });
+
+    printer.print_title(writer)?;
+
+    let mut result = ScanResult::new(path.to_path_buf());
+    let mut pending: Option<(PathBuf, Vec<TodoItem>)> = None;
+    let mut write_result = Ok(());
+    let truncated = scanner.scan_with_callback(path, |file, items| {
+        result.add_file(file.clone(), items.clone());
+
+        if items.is_empty() || write_result.is_err() {
+            return;
+        }
+        let mut file_result = ScanResult::new(path.to_path_buf());
+        file_result.add_file(file, items);
+        if args.strip_author || config.strip_author {
+            file_result.strip_authors();
+        }
+        apply_extension_priorities(&mut file_result, config);
+        sort_results(
+            &mut file_result,
+            args.sort,
+            args.sort_within_file,
+            args.sort_reverse,
+        );
+
+        write_result = if tree_by_file {
+            let next = file_result.files_map.into_iter().next();
+            match std::mem::replace(&mut pending, next) {
+                Some((file, items)) => file_printer.print_tree_file(writer, &file, &items, false),
+                None => Ok(()),
+            }
+        } else {
+            file_printer.print_to(writer, &file_result)
+        }
+        .and_then(|()| writer.flush());
+    })?;
+    write_result?;
+
+    if let Some((file, items)) = pending {
+        file_printer.print_tree_file(writer, &file, &items, true)?;
+    }
+
+    if truncated {
+        tracing::info!(
+            "stopped after {} files (--max-files), results are incomplete",
+            result.summary.files_scanned
+        );
+    }
+
+    if result.is_empty() {
+        writeln!(writer, "{}", "No TODO items found.".dimmed())?;
+    } else if show_summary {
+        writeln!(writer)?;
+        printer.print_summary(writer, &result)?;
+    }
+
+    check_coverage(&result.summary, args.coverage_threshold)
+}
+
+/// Print the files of a dry run relative to the scan root, one per line or as a JSON array
+fn print_file_list(files: &[PathBuf], root: &std::path::Path, format: OutputFormat) -> Result<()> {
+    let files: Vec<String> = files
+        .iter()
+        .map(|file| {
+            file.strip_prefix(root)
+                .unwrap_or(file)
+                .display()
+                .to_string()
+        })
+        .collect();
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&files)?);
+    } else {
+        for file in files {
+            println!("{}", file);
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace `{repo}` in a message prefix with the origin remote's repository name
+///
+/// Falls back to the name of the scanned directory when there is no origin remote.
+fn expand_message_prefix(prefix: &str, path: &std::path::Path) -> String {
+    if !prefix.contains("{repo}") {
+        return prefix.to_string();
+    }
+
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
     };
+    let repo = git::origin_repo_name(dir).unwrap_or_else(|_| {
+        dir.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    prefix.replace("{repo}", &repo)
+}
 
-    let printer = Printer::new(print_options);
-    printer.print(&result)?;
+/// Time the scanner's parser on the first file (by path) that has TODOs
+fn benchmark_first_file(
+    scanner: &Scanner,
+    result: &ScanResult,
+) -> Result<Option<(PathBuf, parser::ParserBenchmark)>> {
+    let Some((path, _)) = result.sorted_files().into_iter().next() else {
+        return Ok(None);
+    };
+    let benchmark = scanner
+        .parser()
+        .benchmark(path)
+        .with_context(|| format!("Failed to benchmark parser on {}", path.display()))?;
+    Ok(Some((path.clone(), benchmark)))
+}
+
+/// Print a `--benchmark` result to stderr, after the scan output
+///
+/// `None` means no benchmark was requested.
+fn print_benchmark(
+    benchmark: Option<&Option<(PathBuf, parser::ParserBenchmark)>>,
+    root: &std::path::Path,
+) {
+    let Some(benchmark) = benchmark else {
+        return;
+    };
+    let Some((path, benchmark)) = benchmark else {
+        eprintln!("Parser benchmark: no file with TODOs to time");
+        return;
+    };
+    eprintln!(
+        "Parser benchmark: {} ({} lines x {} runs): {:.0} ns/line, {:.0} matches/s",
+        path.strip_prefix(root).unwrap_or(path).display(),
+        benchmark.total_lines,
+        parser::BENCHMARK_ITERATIONS,
+        benchmark.avg_ns_per_line,
+        benchmark.matches_per_second
+    );
+}
+
+/// Fail when fewer than `threshold` of the scanned files are TODO-free
+fn check_coverage(summary: &Summary, threshold: Option<f64>) -> Result<()> {
+    if let Some(threshold) = threshold
+        && summary.coverage_ratio() < threshold
+    {
+        anyhow::bail!(
+            "{:.1}% of scanned files are TODO-free, below the coverage threshold of {:.1}%",
+            summary.coverage_ratio() * 100.0,
+            threshold * 100.0
+        );
+    }
+
+    Ok(())
+}
+
+/// Scan the members of a Cargo workspace in parallel and merge the results
+///
+/// The result is rooted at the workspace directory, so paths are shown
+/// relative to it. A member nested in another (such as a root package) is
+/// covered by the outer scan and not scanned again.
+fn scan_workspace(
+    scanner: &Scanner,
+    root: &std::path::Path,
+    members: &[workspace::Member],
+) -> Result<ScanResult> {
+    let roots: Vec<&std::path::Path> = members
+        .iter()
+        .map(|member| member.path.as_path())
+        .filter(|path| {
+            !members
+                .iter()
+                .any(|other| other.path != *path && path.starts_with(&other.path))
+        })
+        .collect();
+
+    let results = roots
+        .par_iter()
+        .map(|path| scanner.scan(path))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut merged = ScanResult::new(root.to_path_buf());
+    for result in results {
+        merged.merge(result);
+    }
+    Ok(merged)
+}
+
+/// Merge the scan command's CLI options into the configuration
+fn merge_scan_args(
+    config: &mut Config,
+    args: &ScanArgs,
+    global: &cli::GlobalOptions,
+) -> Result<()> {
+    config.case_sensitive |= args.case_sensitive;
+    config.merge_with_cli(
+        args.tags.clone(),
+        args.include.clone(),
+        args.exclude.clone(),
+        args.json,
+        args.flat,
+        global.no_color,
+    );
+
+    if let Some(extensions) = &args.ext {
+        config.extensions.extend(extensions.iter().cloned());
+    }
 
+    // A pattern file given on the command line is resolved against the working directory
+    if let Some(file) = &args.ignore_pattern_file {
+        config.ignore_pattern_file = Some(std::env::current_dir()?.join(file));
+    }
     Ok(())
 }
 
+/// Fold the scan flags that have a config counterpart into the config
+///
+/// Used by `--write-config` and `--emit-config`, so the output reproduces the current run.
+fn config_with_scan_args(config: &Config, args: &ScanArgs) -> Config {
+    let mut config = config.clone();
+    config.case_sensitive |= args.case_sensitive;
+    config.strip_author |= args.strip_author;
+    config.threads = args.threads.or(config.threads);
+    config.parallel_files = args.parallel_files.or(config.parallel_files);
+    config.color_scheme = args.color_scheme.or(config.color_scheme);
+    if args.title.is_some() {
+        config.title = args.title.clone();
+    }
+    config
+}
+
+/// Determine the output title for the scan command
+///
+/// `--title` wins over the config title. With `--title-with-timestamp` the local
+/// scan time is appended, or used on its own when no title is set.
+fn scan_title(args: &ScanArgs, config: &Config) -> Option<String> {
+    let title = args.title.clone().or_else(|| config.title.clone());
+    if !args.title_with_timestamp {
+        return title;
+    }
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    Some(match title {
+        Some(title) => format!("{} ({})", title, timestamp),
+        None => timestamp,
+    })
+}
+
+/// Determine the output format for the scan command
+///
+/// An explicit `--format` wins over the `--json` (`--json-compact`), `--flat`, `--compact`, `--table` and `--dot` shorthands.
+fn scan_output_format(args: &ScanArgs) -> OutputFormat {
+    if let Some(format) = args.format {
+        format
+    } else if args.json || args.json_compact {
+        OutputFormat::Json
+    } else if args.flat {
+        OutputFormat::Flat
+    } else if args.compact {
+        OutputFormat::Compact
+    } else if args.table {
+        OutputFormat::Table
+    } else if args.dot {
+        OutputFormat::Dot
+    } else if args.markdown {
+        OutputFormat::Markdown
+    } else {
+        OutputFormat::Tree
+    }
+}
+
 /// Execute the list command
 fn cmd_list(args: cli::ListArgs, global: &cli::GlobalOptions) -> Result<()> {
     let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
@@ -106,7 +671,8 @@ fn cmd_list(args: cli::ListArgs, global: &cli::GlobalOptions) -> Result<()> {
         .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
 
     // Load configuration
-    let mut config = load_config(&path, global.config.as_deref())?;
+    let mut config = load_global_config(&path, global)?;
+    config.case_sensitive |= args.case_sensitive;
 
     // Merge CLI options
     config.merge_with_cli(
@@ -117,13 +683,17 @@ fn cmd_list(args: cli::ListArgs, global: &cli::GlobalOptions) -> Result<()> {
         true, // flat format for list
         global.no_color,
     );
+    config.apply_ignore_pattern_file(&path)?;
 
     // Create parser
-    let parser = TodoParser::new(&config.tags, args.case_sensitive);
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
 
     // Create scan options
     let scan_options = ScanOptions {
-        include: config.include.clone(),
+        include: config.include_patterns(),
         exclude: config.exclude.clone(),
         ..Default::default()
     };
@@ -133,16 +703,23 @@ fn cmd_list(args: cli::ListArgs, global: &cli::GlobalOptions) -> Result<()> {
     let result = scanner.scan(&path)?;
 
     // Filter by tag if specified
-    let result = if let Some(filter_tag) = &args.filter {
-        result.filter_by_tag(filter_tag)
+    let mut result = if let Some(filter_tag) = &args.filter {
+        result.filter(&ItemFilter::tag(filter_tag))
     } else {
         result
     };
 
+    if config.strip_author {
+        result.strip_authors();
+    }
+    apply_extension_priorities(&mut result, &config);
+
     // Print results
     let print_options = PrintOptions {
         format: if args.json {
             OutputFormat::Json
+        } else if args.table {
+            OutputFormat::Table
         } else {
             OutputFormat::Flat
         },
@@ -153,6 +730,7 @@ fn cmd_list(args: cli::ListArgs, global: &cli::GlobalOptions) -> Result<()> {
         base_path: Some(path),
         show_summary: !args.json,
         group_by_tag: false,
+        ..Default::default()
     };
 
     let printer = Printer::new(print_options);
@@ -161,12 +739,70 @@ fn cmd_list(args: cli::ListArgs, global: &cli::GlobalOptions) -> Result<()> {
     Ok(())
 }
 
+/// Execute the interactive command
+fn cmd_interactive(args: cli::InteractiveArgs, global: &cli::GlobalOptions) -> Result<()> {
+    use std::io::IsTerminal;
+
+    if !std::io::stdout().is_terminal() || !std::io::stdin().is_terminal() {
+        anyhow::bail!("interactive mode needs a terminal; use `tt scan` to write results");
+    }
+
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
+
+    let mut config = load_global_config(&path, global)?;
+    config.case_sensitive |= args.case_sensitive;
+    config.merge_with_cli(
+        args.tags.clone(),
+        args.include.clone(),
+        args.exclude.clone(),
+        false,
+        false,
+        global.no_color,
+    );
+    config.apply_ignore_pattern_file(&path)?;
+
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
+    let scan_options = ScanOptions {
+        include: config.include_patterns(),
+        exclude: config.exclude.clone(),
+        before_context: args.context,
+        after_context: args.context,
+        ..Default::default()
+    };
+
+    let mut result = Scanner::new(parser, scan_options).scan(&path)?;
+    if config.strip_author {
+        result.strip_authors();
+    }
+    apply_extension_priorities(&mut result, &config);
+    sort_results(&mut result, args.sort, None, false);
+
+    let printer = Printer::new(PrintOptions {
+        colored: !global.no_color,
+        base_path: Some(path),
+        ..Default::default()
+    });
+    printer.print_interactive(&result)?;
+    Ok(())
+}
+
 /// Execute the tags command
 fn cmd_tags(args: cli::TagsArgs, global: &cli::GlobalOptions) -> Result<()> {
     let current_dir = std::env::current_dir()?;
-    let mut config = load_config(&current_dir, global.config.as_deref())?;
 
     // Handle tag modifications
+    let mut config = if args.add.is_some() || args.remove.is_some() || args.reset {
+        ensure_config_writable(global)?;
+        load_file_config(&current_dir, global)?
+    } else {
+        load_global_config(&current_dir, global)?
+    };
     if let Some(new_tag) = &args.add {
         if !config.tags.iter().any(|t| t.eq_ignore_ascii_case(new_tag)) {
             config.tags.push(new_tag.to_uppercase());
@@ -211,7 +847,7 @@ fn cmd_tags(args: cli::TagsArgs, global: &cli::GlobalOptions) -> Result<()> {
             if global.no_color {
                 println!("  - {}", tag);
             } else {
-                let color = priority_to_color(Priority::from_tag(tag));
+                let color = priority_to_color(config.priority_for_tag(tag));
                 println!("  - {}", tag.color(color));
             }
         }
@@ -222,6 +858,10 @@ fn cmd_tags(args: cli::TagsArgs, global: &cli::GlobalOptions) -> Result<()> {
 
 /// Execute the init command
 fn cmd_init(args: cli::InitArgs) -> Result<()> {
+    if args.pre_push {
+        return install_pre_push_hook(&args);
+    }
+
     let filename = match args.format {
         ConfigFormat::Json => ".todorc.json",
         ConfigFormat::Yaml => ".todorc.yaml",
@@ -244,12 +884,49 @@ fn cmd_init(args: cli::InitArgs) -> Result<()> {
     println!("  - tags: List of tags to search for");
     println!("  - include: File patterns to include");
     println!("  - exclude: File patterns to exclude");
+    println!("  - ignore_pattern_file: File with additional exclude patterns");
     println!("  - json: Default to JSON output");
     println!("  - flat: Default to flat output");
 
     Ok(())
 }
 
+/// Install the pre-push hook into the current repository
+fn install_pre_push_hook(args: &cli::InitArgs) -> Result<()> {
+    let git_dir = PathBuf::from(".git");
+    if !git_dir.is_dir() {
+        anyhow::bail!("Not a git repository (no .git directory in the current directory)");
+    }
+
+    let hook_path = git_dir.join("hooks").join("pre-push");
+    if hook_path.exists() && !args.force {
+        anyhow::bail!(
+            "Hook {} already exists. Use --force to overwrite.",
+            hook_path.display()
+        );
+    }
+
+    let script = hooks::pre_push_script(args.max_critical, &hooks::critical_tags());
+
+    std::fs::create_dir_all(git_dir.join("hooks"))?;
+    std::fs::write(&hook_path, script)
+        .with_context(|| format!("Failed to write hook: {}", hook_path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook_path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    println!("Installed pre-push hook: {}", hook_path.display());
+    println!(
+        "Pushes are blocked when more than {} critical TODO items are found.",
+        args.max_critical
+    );
+
+    Ok(())
+}
+
 /// Execute the stats command
 fn cmd_stats(args: cli::StatsArgs, global: &cli::GlobalOptions) -> Result<()> {
     let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
@@ -258,93 +935,724 @@ fn cmd_stats(args: cli::StatsArgs, global: &cli::GlobalOptions) -> Result<()> {
         .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
 
     // Load configuration
-    let config = load_config(&path, global.config.as_deref())?;
+    let config = load_global_config(&path, global)?;
 
     // Get tags from CLI or config
     let tags = args.tags.clone().unwrap_or(config.tags.clone());
 
     // Create parser and scanner
-    let parser = TodoParser::new(&tags, false);
+    let parser = TodoParser::new(&tags, false)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
     let scanner = Scanner::new(parser, ScanOptions::default());
-    let result = scanner.scan(&path)?;
+
+    // The per-directory, age and cluster breakdowns and the priority filter need
+    // the items; plain statistics only need counts
+    if !args.group_by_dir
+        && !args.with_age
+        && args.clusters.is_none()
+        && args.min_priority.is_none()
+    {
+        let summary = scanner.count_only(&path)?;
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&stats_json(&summary))?);
+        } else {
+            print_stats(&summary, &config, global.no_color, args.coverage_threshold);
+        }
+        return check_coverage(&summary, args.coverage_threshold);
+    }
+
+    let mut result = scanner.scan(&path)?;
+    if let Some(min_priority) = args.min_priority {
+        result.retain_items(|item| item.priority >= min_priority);
+    }
+    if args.with_age {
+        annotate_created_at(&mut result);
+    }
 
     if args.json {
-        let stats = serde_json::json!({
-            "total_items": result.summary.total_count,
-            "files_with_todos": result.summary.files_with_todos,
-            "files_scanned": result.summary.files_scanned,
-            "tag_counts": result.summary.tag_counts,
-            "items_per_file": if result.summary.files_with_todos > 0 {
-                result.summary.total_count as f64 / result.summary.files_with_todos as f64
-            } else {
-                0.0
-            },
-        });
+        let mut stats = stats_json(&result.summary);
+        if args.group_by_dir {
+            stats["directories"] = directory_stats_json(&result);
+        }
+        if args.with_age {
+            stats["age"] = age_stats_json(&result);
+        }
+        if let Some(count) = args.clusters {
+            stats["clusters"] = cluster_stats_json(&result, args.cluster_threshold, count);
+        }
         println!("{}", serde_json::to_string_pretty(&stats)?);
     } else {
-        use colored::Colorize;
-
-        println!("{}", "TODO Statistics".bold().underline());
-        println!();
-        println!("  Total items:        {}", result.summary.total_count);
-        println!("  Files with TODOs:   {}", result.summary.files_with_todos);
-        println!("  Files scanned:      {}", result.summary.files_scanned);
-
-        if result.summary.files_with_todos > 0 {
-            let avg = result.summary.total_count as f64 / result.summary.files_with_todos as f64;
-            println!("  Avg items per file: {:.2}", avg);
+        print_stats(
+            &result.summary,
+            &config,
+            global.no_color,
+            args.coverage_threshold,
+        );
+        if args.group_by_dir {
+            print_directory_stats(&result);
         }
-
-        println!();
-        println!("{}", "By Tag:".bold());
-
-        let mut tags: Vec<_> = result.summary.tag_counts.iter().collect();
-        tags.sort_by(|a, b| b.1.cmp(a.1));
-
-        for (tag, count) in tags {
-            let percentage = if result.summary.total_count > 0 {
-                (*count as f64 / result.summary.total_count as f64) * 100.0
-            } else {
-                0.0
-            };
-
-            let bar_width = 20;
-            let filled = ((percentage / 100.0) * bar_width as f64) as usize;
-            let bar: String = "█".repeat(filled) + &"░".repeat(bar_width - filled);
-
-            if global.no_color {
-                println!("  {:<8} {:>4} ({:>5.1}%) {}", tag, count, percentage, bar);
-            } else {
-                let color = priority_to_color(Priority::from_tag(tag));
-                println!(
-                    "  {:<8} {:>4} ({:>5.1}%) {}",
-                    tag.color(color),
-                    count,
-                    percentage,
-                    bar.dimmed()
-                );
-            }
+        if args.with_age {
+            print_age_stats(&result, global.no_color);
+        }
+        if let Some(count) = args.clusters {
+            print_cluster_stats(&result, args.cluster_threshold, count);
         }
     }
 
-    Ok(())
+    check_coverage(&result.summary, args.coverage_threshold)
 }
 
-/// Load configuration from file or use defaults
-fn load_config(path: &std::path::Path, config_path: Option<&std::path::Path>) -> Result<Config> {
-    if let Some(config_path) = config_path {
-        return Config::load_from_file(config_path);
-    }
+/// Get per-directory results sorted by total count descending, then by path
+fn sorted_directories(result: &ScanResult) -> Vec<(PathBuf, ScanResult)> {
+    let mut directories: Vec<_> = result.by_directory().into_iter().collect();
+    directories.sort_by(|a, b| {
+        b.1.summary
+            .total_count
+            .cmp(&a.1.summary.total_count)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    directories
+}
 
-    match Config::load(path)? {
-        Some(config) => Ok(config),
-        None => Ok(Config::new()),
-    }
+/// Build the per-directory statistics JSON array for a scan result
+fn directory_stats_json(result: &ScanResult) -> serde_json::Value {
+    sorted_directories(result)
+        .into_iter()
+        .map(|(directory, result)| {
+            serde_json::json!({
+                "directory": directory.display().to_string(),
+                "total_items": result.summary.total_count,
+                "files_with_todos": result.summary.files_with_todos,
+                "tag_counts": result.summary.tag_counts,
+            })
+        })
+        .collect()
 }
 
-/// Save configuration to the default config file
-fn save_config(config: &Config) -> Result<()> {
-    let current_dir = std::env::current_dir()?;
+/// Print a per-directory breakdown table
+fn print_directory_stats(result: &ScanResult) {
+    use colored::Colorize;
+
+    let directories = sorted_directories(result);
+    let width = directories
+        .iter()
+        .map(|(directory, _)| directory.display().to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max("Directory".len());
+
+    println!();
+    println!("{}", "By Directory:".bold());
+    println!("  {:<width$} {:>6} {:>6}", "Directory", "Items", "Files");
+
+    for (directory, result) in directories {
+        println!(
+            "  {:<width$} {:>6} {:>6}",
+            directory.display().to_string(),
+            result.summary.total_count,
+            result.summary.files_with_todos
+        );
+    }
+}
+
+/// Age ranges shown by `stats --with-age`, as upper bounds with their labels
+const AGE_BUCKETS: [(u64, &str); 3] = [
+    (7, "<1 week"),
+    (30, "1 week - 1 month"),
+    (182, "1 month - 6 months"),
+];
+
+/// Label each age range of the result, followed by the "Unknown" count of unblamed items
+fn age_breakdown(result: &ScanResult) -> Vec<(&'static str, usize)> {
+    const DAY: u64 = 24 * 60 * 60;
+    let bounds: Vec<std::time::Duration> = AGE_BUCKETS
+        .iter()
+        .map(|(days, _)| std::time::Duration::from_secs(days * DAY))
+        .collect();
+
+    let labels = AGE_BUCKETS
+        .iter()
+        .map(|(_, label)| *label)
+        .chain([">6 months"]);
+    let mut breakdown: Vec<(&str, usize)> = labels
+        .zip(result.age_histogram(&bounds))
+        .map(|(label, (_, count))| (label, count))
+        .collect();
+
+    let known: usize = breakdown.iter().map(|(_, count)| count).sum();
+    breakdown.push(("Unknown", result.summary.total_count.saturating_sub(known)));
+    breakdown
+}
+
+/// Print how long the items have been in the codebase, as a bar chart
+fn print_age_stats(result: &ScanResult, no_color: bool) {
+    use colored::Colorize;
+
+    println!();
+    println!("{}", "By Age:".bold());
+
+    for (label, count) in age_breakdown(result) {
+        let percentage = if result.summary.total_count > 0 {
+            (count as f64 / result.summary.total_count as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let bar_width = 20;
+        let filled = ((percentage / 100.0) * bar_width as f64) as usize;
+        let bar: String = "█".repeat(filled) + &"░".repeat(bar_width - filled);
+
+        if no_color {
+            println!(
+                "  {:<18} {:>4} ({:>5.1}%) {}",
+                label, count, percentage, bar
+            );
+        } else {
+            println!(
+                "  {:<18} {:>4} ({:>5.1}%) {}",
+                label,
+                count,
+                percentage,
+                bar.dimmed()
+            );
+        }
+    }
+}
+
+/// Print the largest groups of items with similar messages
+fn print_cluster_stats(result: &ScanResult, threshold: f64, count: usize) {
+    use colored::Colorize;
+
+    println!();
+    println!("{}", "Similar Items:".bold());
+
+    let clusters = result.cluster_by_message_similarity(threshold);
+    if clusters.is_empty() {
+        println!("  {}", "No similar items found.".dimmed());
+        return;
+    }
+
+    let root = result.root.as_deref().unwrap_or(std::path::Path::new(""));
+    for cluster in clusters.iter().take(count) {
+        println!("  {:>4}x {}", cluster.len(), cluster.representative);
+        for (path, item) in &cluster.items {
+            let path = path.strip_prefix(root).unwrap_or(path);
+            println!(
+                "         {}",
+                format!("{}:{}", path.display(), item.line).dimmed()
+            );
+        }
+    }
+}
+
+/// Build the clusters JSON array for `stats --clusters --json`
+fn cluster_stats_json(result: &ScanResult, threshold: f64, count: usize) -> serde_json::Value {
+    let root = result.root.as_deref().unwrap_or(std::path::Path::new(""));
+    result
+        .cluster_by_message_similarity(threshold)
+        .into_iter()
+        .take(count)
+        .map(|cluster| {
+            let items: Vec<serde_json::Value> = cluster
+                .items
+                .iter()
+                .map(|(path, item)| {
+                    serde_json::json!({
+                        "path": path.strip_prefix(root).unwrap_or(path).display().to_string(),
+                        "line": item.line,
+                        "message": item.message,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "size": cluster.len(),
+                "message": cluster.representative,
+                "items": items,
+            })
+        })
+        .collect()
+}
+
+/// Build the age breakdown JSON array for `stats --with-age --json`
+fn age_stats_json(result: &ScanResult) -> serde_json::Value {
+    age_breakdown(result)
+        .into_iter()
+        .map(|(label, count)| serde_json::json!({ "age": label, "count": count }))
+        .collect()
+}
+
+/// Build the statistics JSON object for a scan summary
+fn stats_json(summary: &Summary) -> serde_json::Value {
+    serde_json::json!({
+        "total_items": summary.total_count,
+        "files_with_todos": summary.files_with_todos,
+        "files_scanned": summary.files_scanned,
+        "tag_counts": summary.tag_counts,
+        "items_per_file": if summary.files_with_todos > 0 {
+            summary.total_count as f64 / summary.files_with_todos as f64
+        } else {
+            0.0
+        },
+        "coverage_ratio": summary.coverage_ratio(),
+    })
+}
+
+/// Print the statistics block (totals and per-tag bar chart) for a scan summary
+///
+/// With a coverage threshold, the TODO-free file ratio is shown first.
+fn print_stats(
+    summary: &Summary,
+    config: &Config,
+    no_color: bool,
+    coverage_threshold: Option<f64>,
+) {
+    use colored::Colorize;
+
+    println!("{}", "TODO Statistics".bold().underline());
+    println!();
+
+    if let Some(threshold) = coverage_threshold {
+        let ratio = summary.coverage_ratio();
+        let line = format!(
+            "  TODO-free files:    {:.1}% (threshold {:.1}%)",
+            ratio * 100.0,
+            threshold * 100.0
+        );
+        if no_color {
+            println!("{}", line);
+        } else if ratio >= threshold {
+            println!("{}", line.green().bold());
+        } else {
+            println!("{}", line.red().bold());
+        }
+        println!();
+    }
+    println!("  Total items:        {}", summary.total_count);
+    println!("  Files with TODOs:   {}", summary.files_with_todos);
+    println!("  Files scanned:      {}", summary.files_scanned);
+
+    if summary.files_with_todos > 0 {
+        let avg = summary.total_count as f64 / summary.files_with_todos as f64;
+        println!("  Avg items per file: {:.2}", avg);
+    }
+
+    println!();
+    println!("{}", "By Tag:".bold());
+
+    let mut tags: Vec<_> = summary.tag_counts.iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(a.1));
+
+    for (tag, count) in tags {
+        let percentage = if summary.total_count > 0 {
+            (*count as f64 / summary.total_count as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let bar_width = 20;
+        let filled = ((percentage / 100.0) * bar_width as f64) as usize;
+        let bar: String = "█".repeat(filled) + &"░".repeat(bar_width - filled);
+
+        if no_color {
+            println!("  {:<8} {:>4} ({:>5.1}%) {}", tag, count, percentage, bar);
+        } else {
+            let color = priority_to_color(config.priority_for_tag(tag));
+            println!(
+                "  {:<8} {:>4} ({:>5.1}%) {}",
+                tag.color(color),
+                count,
+                percentage,
+                bar.dimmed()
+            );
+        }
+    }
+}
+
+/// Execute the lsp command
+fn cmd_lsp(args: cli::LspArgs, global: &cli::GlobalOptions) -> Result<()> {
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
+
+    lsp::run(path, args.tags, global)
+}
+
+/// Execute the parse command
+fn cmd_parse(args: cli::ParseArgs, global: &cli::GlobalOptions) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let mut config = load_global_config(&cwd, global)?;
+    config.case_sensitive |= args.case_sensitive;
+    config.merge_with_cli(args.tags, None, None, false, false, global.no_color);
+
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
+
+    if args.explain {
+        let explanation = parser.explain(&args.line);
+        let show = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+        println!("matched:        {}", explanation.matched);
+        println!("comment marker: {}", show(&explanation.comment_marker));
+        println!("tag:            {}", show(&explanation.tag_found));
+        println!("author:         {}", show(&explanation.author_found));
+        println!("message:        {}", show(&explanation.message_found));
+        if let Some(reason) = &explanation.reason_no_match {
+            println!("reason:         {}", reason);
+        }
+    } else {
+        match parser.parse_line(&args.line, 1) {
+            Some(item) => println!(
+                "{}{} ({}): {}",
+                item.tag,
+                item.format_author(),
+                item.priority,
+                item.message
+            ),
+            None => println!("No match"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Execute the pattern command
+fn cmd_pattern(args: cli::PatternArgs, global: &cli::GlobalOptions) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let mut config = load_global_config(&cwd, global)?;
+    config.case_sensitive |= args.case_sensitive;
+    config.merge_with_cli(args.tags, None, None, false, false, global.no_color);
+
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
+
+    let ripgrep = parser
+        .pattern_string_for_ripgrep()
+        .context("No tags configured")?;
+    if args.for_ripgrep {
+        println!("{}", ripgrep.command_line());
+    } else {
+        println!("{}", ripgrep.pattern);
+    }
+
+    Ok(())
+}
+
+/// Execute the show command
+fn cmd_show(args: cli::ShowArgs, global: &cli::GlobalOptions) -> Result<()> {
+    let (file, line) = args
+        .location
+        .rsplit_once(':')
+        .and_then(|(file, line)| Some((PathBuf::from(file), line.parse::<usize>().ok()?)))
+        .with_context(|| format!("Expected FILE:LINE, got: {}", args.location))?;
+
+    let cwd = std::env::current_dir()?;
+    let mut config = load_global_config(&cwd, global)?;
+    config.merge_with_cli(args.tags, None, None, false, false, global.no_color);
+
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
+
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Failed to read file: {}", file.display()))?;
+    let item = content
+        .lines()
+        .nth(line.saturating_sub(1))
+        .and_then(|text| parser.parse_line(text, line))
+        .with_context(|| format!("No TODO item at {}:{}", file.display(), line))?;
+
+    let printer = Printer::new(PrintOptions {
+        format: OutputFormat::Flat,
+        colored: !global.no_color,
+        clickable_links: !global.no_color,
+        base_path: Some(cwd),
+        ..Default::default()
+    });
+    printer.pprint_item(&mut std::io::stdout().lock(), &item, &file)?;
+
+    Ok(())
+}
+
+/// Execute the verify command
+fn cmd_verify(args: cli::VerifyArgs, global: &cli::GlobalOptions) -> Result<()> {
+    use colored::Colorize;
+
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
+
+    let mut config = load_global_config(&path, global)?;
+    config.merge_with_cli(args.tags, None, None, false, false, global.no_color);
+    config.apply_ignore_pattern_file(&path)?;
+
+    let required_tags = match args.required_tags {
+        Some(tags) => tags,
+        None if !config.required_ticket_tags.is_empty() => config.required_ticket_tags.clone(),
+        None => verify::DEFAULT_REQUIRED_TICKET_TAGS
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect(),
+    };
+    let ticket_regex = verify::ticket_regex(
+        args.ticket_regex
+            .as_deref()
+            .or(config.ticket_regex.as_deref()),
+    )?;
+
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
+    let scan_options = ScanOptions {
+        include: config.include_patterns(),
+        exclude: config.exclude.clone(),
+        ..Default::default()
+    };
+    let result = Scanner::new(parser, scan_options).scan(&path)?;
+
+    let violations = verify::verify(&result, &required_tags, &ticket_regex);
+    for violation in &violations {
+        let location = format!(
+            "{}:{}",
+            violation
+                .path
+                .strip_prefix(&path)
+                .unwrap_or(&violation.path)
+                .display(),
+            violation.item.line
+        );
+        let label = if global.no_color {
+            "error".normal()
+        } else {
+            "error".red().bold()
+        };
+        println!(
+            "{}: {}: [{}] {} (missing ticket reference)",
+            label, location, violation.item.tag, violation.item.message
+        );
+    }
+
+    if !violations.is_empty() {
+        anyhow::bail!(
+            "{} TODO item(s) missing a ticket reference matching {}",
+            violations.len(),
+            ticket_regex.as_str()
+        );
+    }
+
+    println!(
+        "All {} item(s) reference a ticket.",
+        result
+            .iter()
+            .filter(|(_, item)| {
+                required_tags
+                    .iter()
+                    .any(|tag| tag.eq_ignore_ascii_case(&item.tag))
+            })
+            .count()
+    );
+
+    Ok(())
+}
+
+/// Execute the schema command
+fn cmd_schema(args: cli::SchemaArgs) -> Result<()> {
+    print!("{}", Config::export_schema(args.format));
+    Ok(())
+}
+
+/// Execute the config command
+fn cmd_config(args: cli::ConfigArgs, global: &cli::GlobalOptions) -> Result<()> {
+    match args.command {
+        cli::ConfigCommand::Upgrade(args) => {
+            let file = match args.file.or_else(|| global.config.clone()) {
+                Some(file) => file,
+                None => Config::find_local_file(&std::env::current_dir()?)
+                    .context("No .todorc file found; pass the config file to upgrade")?,
+            };
+
+            let diffs = Config::upgrade_file(&file, args.write)?;
+            if diffs.is_empty() {
+                println!("{} is up to date.", file.display());
+                return Ok(());
+            }
+
+            for diff in &diffs {
+                println!("{}", diff);
+            }
+            if args.write {
+                println!("Wrote {}", file.display());
+            } else {
+                println!("Run again with --write to update {}.", file.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Execute the snapshot command
+fn cmd_snapshot(args: cli::SnapshotArgs, global: &cli::GlobalOptions) -> Result<()> {
+    use cli::SnapshotCommand;
+    use snapshot::Snapshot;
+
+    match args.command {
+        SnapshotCommand::Save(args) => {
+            let (path, result) = scan_project(&args.location, args.tags, global)?;
+            let dir = snapshot::snapshot_dir(&path, args.location.project_local)?;
+            let snapshot = Snapshot::new(&args.name, &path, &result);
+            let file = snapshot.save(&dir)?;
+
+            println!(
+                "Saved snapshot '{}' ({} TODO items) to {}",
+                args.name,
+                result.summary.total_count,
+                file.display()
+            );
+        }
+        SnapshotCommand::Diff(args) => {
+            let (path, result) = scan_project(&args.location, args.tags, global)?;
+            let dir = snapshot::snapshot_dir(&path, args.location.project_local)?;
+            let snapshot = Snapshot::load(&dir, &args.name)?;
+
+            let printer = Printer::new(PrintOptions {
+                colored: !global.no_color,
+                ..Default::default()
+            });
+            printer.print_diff(
+                &mut std::io::stdout().lock(),
+                &snapshot.scan_result(),
+                &result,
+            )?;
+        }
+        SnapshotCommand::List(args) => {
+            let path = resolve_snapshot_project(&args.location)?;
+            let dir = snapshot::snapshot_dir(&path, args.location.project_local)?;
+            let snapshots = Snapshot::list(&dir)?;
+
+            if snapshots.is_empty() {
+                println!("No snapshots found in {}", dir.display());
+            }
+            for snapshot in snapshots {
+                println!(
+                    "{:<24} {}  {:>5} items  {}",
+                    snapshot.name,
+                    snapshot.created_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                    snapshot.result.summary.total_count,
+                    snapshot.root.display()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve the project directory of a snapshot command
+fn resolve_snapshot_project(location: &cli::SnapshotLocation) -> Result<PathBuf> {
+    let path = location.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    path.canonicalize()
+        .with_context(|| format!("Failed to resolve path: {}", path.display()))
+}
+
+/// Scan the project directory of a snapshot command using its configuration
+fn scan_project(
+    location: &cli::SnapshotLocation,
+    tags: Option<Vec<String>>,
+    global: &cli::GlobalOptions,
+) -> Result<(PathBuf, ScanResult)> {
+    let path = resolve_snapshot_project(location)?;
+
+    let mut config = load_global_config(&path, global)?;
+    config.merge_with_cli(tags, None, None, false, false, global.no_color);
+    config.apply_ignore_pattern_file(&path)?;
+
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
+    let scan_options = ScanOptions {
+        include: config.include_patterns(),
+        exclude: config.exclude.clone(),
+        ..Default::default()
+    };
+
+    let result = Scanner::new(parser, scan_options).scan(&path)?;
+    Ok((path, result))
+}
+
+/// Load configuration honouring the global `--config`, `--no-config` and
+/// `--follow-config-from` options
+pub(crate) fn load_global_config(
+    path: &std::path::Path,
+    global: &cli::GlobalOptions,
+) -> Result<Config> {
+    if global.no_config {
+        return Ok(Config::new());
+    }
+
+    if let Some(dir) = &global.follow_config_from {
+        let dir = dir
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve config directory: {}", dir.display()))?;
+        return load_config(&dir, global.config.as_deref());
+    }
+
+    load_config(path, global.config.as_deref())
+}
+
+/// Load configuration from file or use defaults
+///
+/// `todo-tree.*` keys in the local git config are layered below the file and
+/// `TODO_TREE_*` environment variables above it, as in [`Config::effective`].
+fn load_config(path: &std::path::Path, config_path: Option<&std::path::Path>) -> Result<Config> {
+    match config_path {
+        Some(config_path) => Config::resolve(path, Some(Config::load_from_file(config_path)?)),
+        None => Config::effective(path),
+    }
+}
+
+/// Load only the config file that a command saving the configuration edits
+///
+/// Unlike [`load_global_config`], the git config and `TODO_TREE_*` layers are
+/// left out, so saving never copies them into the file. Without a file the
+/// defaults are used.
+fn load_file_config(path: &std::path::Path, global: &cli::GlobalOptions) -> Result<Config> {
+    let dir = global.follow_config_from.as_deref().unwrap_or(path);
+    let file = global
+        .config
+        .clone()
+        .or_else(|| Config::find_local_file(dir));
+
+    let mut config = match file {
+        Some(file) => Config::load_from_file(&file)?,
+        None => Config::new(),
+    };
+    config.merge_defaults();
+    Ok(config)
+}
+
+/// Refuse to save the configuration when config files were ignored
+///
+/// With `--no-config` the configuration starts from the defaults, so saving it
+/// would replace an existing `.todorc*` with the defaults plus one change.
+fn ensure_config_writable(global: &cli::GlobalOptions) -> Result<()> {
+    if global.no_config {
+        anyhow::bail!("Cannot write the config file with --no-config, which ignores its contents");
+    }
+    Ok(())
+}
+
+/// Save configuration to the default config file, returning its path
+fn save_config(config: &Config) -> Result<PathBuf> {
+    let current_dir = std::env::current_dir()?;
 
     // Try to find existing config file
     let config_files = [
@@ -354,39 +1662,134 @@ fn save_config(config: &Config) -> Result<()> {
         current_dir.join(".todorc.yml"),
     ];
 
-    for path in &config_files {
+    for path in config_files {
         if path.exists() {
-            return config.save(path);
+            config.save(&path)?;
+            return Ok(path);
         }
     }
 
     // Create new config file
     let path = current_dir.join(".todorc.json");
-    config.save(&path)
+    config.save(&path)?;
+    Ok(path)
 }
 
-/// Sort scan results based on the specified order
-fn sort_results(result: &mut ScanResult, sort: SortOrder) {
-    match sort {
-        SortOrder::File => {
-            // Already sorted by file path
+/// Keep the window of items selected by `--offset`, `--first` and `--last`
+///
+/// Items are counted in display order: files by path (last first with
+/// `reverse`), then the items of each file in their sorted order. `--offset`
+/// is applied first.
+fn paginate(
+    result: &ScanResult,
+    offset: usize,
+    first: Option<usize>,
+    last: Option<usize>,
+    reverse: bool,
+) -> (ScanResult, Pagination) {
+    let mut files = result.sorted_files();
+    if reverse {
+        files.reverse();
+    }
+    let items: Vec<_> = files
+        .into_iter()
+        .flat_map(|(path, items)| items.iter().map(move |item| (path, item)))
+        .collect();
+
+    let rest = &items[offset.min(items.len())..];
+    let window = match (first, last) {
+        (Some(n), _) => &rest[..n.min(rest.len())],
+        (None, Some(n)) => &rest[rest.len().saturating_sub(n)..],
+        (None, None) => rest,
+    };
+
+    let mut files: HashMap<PathBuf, Vec<TodoItem>> = HashMap::new();
+    for (path, item) in window {
+        files
+            .entry((*path).clone())
+            .or_default()
+            .push((*item).clone());
+    }
+
+    let root = result.root.clone().unwrap_or_else(|| PathBuf::from("."));
+    let mut page = ScanResult::new(root);
+    for (path, items) in files {
+        page.add_file(path, items);
+    }
+    page.summary.files_scanned = result.summary.files_scanned;
+    page.summary.truncated = result.summary.truncated;
+    page.summary.timed_out = result.summary.timed_out;
+
+    let pagination = Pagination {
+        offset: offset.min(items.len()),
+        shown: window.len(),
+        total: items.len(),
+        from_end: first.is_none() && last.is_some(),
+    };
+    (page, pagination)
+}
+
+/// Re-prioritize items whose tag has `extension_priorities` rules for their file
+fn apply_extension_priorities(result: &mut ScanResult, config: &Config) {
+    if config.extension_priorities.is_empty() {
+        return;
+    }
+
+    for (path, items) in result.files_map.iter_mut() {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for item in items.iter_mut() {
+            item.priority = config.priority_for_tag_in_extension(&item.tag, &file_name);
         }
+    }
+}
 
-        SortOrder::Line => {
-            // Sort items within each file by line number
-            for items in result.files_map.values_mut() {
-                items.sort_by_key(|item| item.line);
+/// Fill in `created_at` from `git blame`, one file per rayon task
+///
+/// Files git cannot blame (untracked, or outside a repository) are left as is.
+fn annotate_created_at(result: &mut ScanResult) {
+    result.files_map.par_iter_mut().for_each(|(path, items)| {
+        let lines: Vec<usize> = items.iter().map(|item| item.line).collect();
+        if let Ok(timestamps) = git::blame_timestamps(path, &lines) {
+            for item in items.iter_mut() {
+                item.created_at = timestamps.get(&item.line).copied();
             }
         }
-        SortOrder::Priority => {
-            // Sort items within each file by priority
-            for items in result.files_map.values_mut() {
-                items.sort_by_key(|item| std::cmp::Reverse(item.priority));
-            }
+    });
+}
+
+/// Sort scan results based on the specified order
+fn sort_results(
+    result: &mut ScanResult,
+    sort: SortOrder,
+    within_file: Option<SortOrder>,
+    reverse: bool,
+) {
+    sort_items(result, sort);
+    // The sorts are stable, so items that tie on `within_file` keep the `sort` order
+    if let Some(within_file) = within_file {
+        sort_items(result, within_file);
+    }
+
+    if reverse {
+        for items in result.files_map.values_mut() {
+            items.reverse();
         }
     }
 }
 
+/// Sort the items of each file by one order
+fn sort_items(result: &mut ScanResult, sort: SortOrder) {
+    if sort == SortOrder::CreatedAt {
+        annotate_created_at(result);
+    }
+    for items in result.files_map.values_mut() {
+        items.sort_by(|a, b| sort.compare(a, b));
+    }
+}
+
 use anyhow::Context;
 
 #[cfg(test)]
@@ -451,6 +1854,46 @@ fn temp_fix() {}
         assert!(result.summary.files_with_todos >= 2);
     }
 
+    #[test]
+    fn test_scan_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .unwrap();
+        for (name, message) in [("core", "TODO: core"), ("cli", "FIXME: cli")] {
+            let dir = root.join("crates").join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n", name),
+            )
+            .unwrap();
+            fs::write(dir.join("lib.rs"), format!("// {}\n", message)).unwrap();
+        }
+        fs::write(root.join("build.rs"), "// TODO: outside members\n").unwrap();
+
+        let tags: Vec<String> = config::default_tags();
+        let scanner = Scanner::new(TodoParser::new(&tags, false), ScanOptions::default());
+        let members = workspace::members(&root.join("Cargo.toml")).unwrap();
+
+        let result = scan_workspace(&scanner, &root, &members).unwrap();
+        assert_eq!(result.root, Some(root.clone()));
+        assert_eq!(result.summary.total_count, 2);
+        assert!(
+            result
+                .files_map
+                .contains_key(&root.join("crates/cli/lib.rs"))
+        );
+
+        let members = workspace::filter(members, &["core".to_string()]).unwrap();
+        let result = scan_workspace(&scanner, &root, &members).unwrap();
+        assert_eq!(result.summary.total_count, 1);
+        assert_eq!(result.summary.tag_counts.get("TODO"), Some(&1));
+    }
+
     #[test]
     fn test_config_loading() {
         let temp_dir = TempDir::new().unwrap();
@@ -490,7 +1933,7 @@ fn temp_fix() {}
         let scanner = Scanner::new(parser, ScanOptions::default());
 
         let mut result = scanner.scan(temp_dir.path()).unwrap();
-        sort_results(&mut result, SortOrder::Priority);
+        sort_results(&mut result, SortOrder::Priority, None, false);
 
         // Check that items are sorted by priority within files
         for items in result.files_map.values() {
@@ -500,6 +1943,53 @@ fn temp_fix() {}
         }
     }
 
+    #[test]
+    fn test_paginate() {
+        let item = |line| TodoItem {
+            tag: "TODO".to_string(),
+            message: format!("item {}", line),
+            line,
+            column: 1,
+            line_content: None,
+            author: None,
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        };
+        let mut result = ScanResult::new(PathBuf::from("/p"));
+        result.add_file(PathBuf::from("/p/b.rs"), vec![item(1), item(2)]);
+        result.add_file(PathBuf::from("/p/a.rs"), vec![item(3), item(4), item(5)]);
+
+        let lines = |page: &ScanResult| -> Vec<usize> {
+            page.sorted_files()
+                .into_iter()
+                .flat_map(|(_, items)| items.iter().map(|item| item.line))
+                .collect()
+        };
+
+        let (page, pagination) = paginate(&result, 0, Some(2), None, false);
+        assert_eq!(lines(&page), vec![3, 4]);
+        assert_eq!(pagination.describe(), "Showing first 2 of 5 total");
+        assert_eq!(page.summary.files_scanned, 2);
+
+        let (page, pagination) = paginate(&result, 0, None, Some(3), false);
+        assert_eq!(lines(&page), vec![5, 1, 2]);
+        assert_eq!(page.summary.files_with_todos, 2);
+        assert_eq!(pagination.describe(), "Showing last 3 of 5 total");
+
+        let (page, pagination) = paginate(&result, 2, Some(2), None, false);
+        assert_eq!(lines(&page), vec![5, 1]);
+        assert_eq!(pagination.describe(), "Showing items 3-4 of 5 total");
+
+        let (page, _) = paginate(&result, 10, None, None, false);
+        assert!(page.is_empty());
+
+        let (page, _) = paginate(&result, 0, Some(2), None, true);
+        assert_eq!(lines(&page), vec![1, 2]);
+    }
+
     #[test]
     fn test_sort_by_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -512,11 +2002,174 @@ fn temp_fix() {}
 
         let mut result = scanner.scan(temp_dir.path()).unwrap();
         // Sort by file should not panic
-        sort_results(&mut result, SortOrder::File);
+        sort_results(&mut result, SortOrder::File, None, false);
 
         assert!(result.summary.total_count >= 1);
     }
 
+    #[test]
+    fn test_sort_by_created_at() {
+        let temp_dir = TempDir::new().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        fs::write(temp_dir.path().join("test.rs"), "// TODO: First\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "first", "--date=2021-01-01T00:00:00Z"]);
+        fs::write(
+            temp_dir.path().join("test.rs"),
+            "// TODO: Newest\n// TODO: First\n",
+        )
+        .unwrap();
+        git(&["commit", "-qam", "second", "--date=2022-01-01T00:00:00Z"]);
+        fs::write(
+            temp_dir.path().join("untracked.rs"),
+            "// TODO: B\n// TODO: A\n",
+        )
+        .unwrap();
+
+        let tags: Vec<String> = config::default_tags();
+        let parser = TodoParser::new(&tags, false);
+        let scanner = Scanner::new(parser, ScanOptions::default());
+        let mut result = scanner.scan(temp_dir.path()).unwrap();
+
+        let messages = |result: &ScanResult, file: &str| -> Vec<String> {
+            let path = temp_dir.path().canonicalize().unwrap().join(file);
+            result.files_map[&path]
+                .iter()
+                .map(|item| item.message.clone())
+                .collect()
+        };
+
+        sort_results(&mut result, SortOrder::CreatedAt, None, false);
+        assert_eq!(messages(&result, "test.rs"), vec!["First", "Newest"]);
+        assert_eq!(messages(&result, "untracked.rs"), vec!["B", "A"]);
+
+        sort_results(&mut result, SortOrder::CreatedAt, None, true);
+        assert_eq!(messages(&result, "test.rs"), vec!["Newest", "First"]);
+        assert_eq!(messages(&result, "untracked.rs"), vec!["A", "B"]);
+    }
+
+    #[test]
+    fn test_sort_by_author() {
+        let item = |line, author: Option<&str>| TodoItem {
+            tag: "TODO".to_string(),
+            message: "message".to_string(),
+            line,
+            column: 1,
+            line_content: None,
+            author: author.map(String::from),
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        };
+        let mut result = ScanResult::new(PathBuf::from("/p"));
+        result.add_file(
+            PathBuf::from("/p/a.rs"),
+            vec![item(1, None), item(2, Some("zoe")), item(3, Some("bob"))],
+        );
+
+        sort_results(&mut result, SortOrder::Author, None, false);
+        let lines: Vec<usize> = result.files_map[&PathBuf::from("/p/a.rs")]
+            .iter()
+            .map(|item| item.line)
+            .collect();
+        assert_eq!(lines, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_sort_within_file() {
+        let item = |line, tag: &str, priority| TodoItem {
+            tag: tag.to_string(),
+            message: "message".to_string(),
+            line,
+            column: 1,
+            line_content: None,
+            author: None,
+            priority,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        };
+        let items = vec![
+            item(1, "TODO", Priority::Medium),
+            item(2, "FIXME", Priority::Critical),
+            item(3, "NOTE", Priority::Low),
+            item(4, "BUG", Priority::Critical),
+        ];
+        let lines = |result: &ScanResult| -> Vec<usize> {
+            result.files_map[&PathBuf::from("/p/a.rs")]
+                .iter()
+                .map(|item| item.line)
+                .collect()
+        };
+
+        let mut result = ScanResult::new(PathBuf::from("/p"));
+        result.add_file(PathBuf::from("/p/a.rs"), items.clone());
+        sort_results(
+            &mut result,
+            SortOrder::File,
+            Some(SortOrder::Priority),
+            false,
+        );
+        assert_eq!(lines(&result), vec![2, 4, 1, 3]);
+
+        let mut result = ScanResult::new(PathBuf::from("/p"));
+        result.add_file(PathBuf::from("/p/a.rs"), items);
+        sort_results(
+            &mut result,
+            SortOrder::Tag,
+            Some(SortOrder::Priority),
+            false,
+        );
+        assert_eq!(lines(&result), vec![4, 2, 1, 3]);
+    }
+
+    #[test]
+    fn test_sort_by_message() {
+        let item = |line, message: &str| TodoItem {
+            tag: "TODO".to_string(),
+            message: message.to_string(),
+            line,
+            column: 1,
+            line_content: None,
+            author: None,
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        };
+        let mut result = ScanResult::new(PathBuf::from("/p"));
+        result.add_file(
+            PathBuf::from("/p/a.rs"),
+            vec![
+                item(1, "handle errors"),
+                item(2, "Add logging"),
+                item(3, "Handle errors"),
+                item(4, "add logging"),
+            ],
+        );
+
+        sort_results(&mut result, SortOrder::Message, None, false);
+        let lines: Vec<usize> = result.files_map[&PathBuf::from("/p/a.rs")]
+            .iter()
+            .map(|item| item.line)
+            .collect();
+        assert_eq!(lines, vec![2, 4, 1, 3]);
+    }
+
     #[test]
     fn test_sort_by_line() {
         let temp_dir = TempDir::new().unwrap();
@@ -537,7 +2190,7 @@ fn main() {}
         let scanner = Scanner::new(parser, ScanOptions::default());
 
         let mut result = scanner.scan(temp_dir.path()).unwrap();
-        sort_results(&mut result, SortOrder::Line);
+        sort_results(&mut result, SortOrder::Line, None, false);
 
         // Check that items are sorted by line number within files
         for items in result.files_map.values() {
@@ -560,14 +2213,92 @@ fn main() {}
     }
 
     #[test]
-    fn test_load_config_no_file() {
+    fn test_load_config_layers_git_config() {
         let temp_dir = TempDir::new().unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "todo-tree.tags", "FROMGIT"],
+            &["config", "todo-tree.exclude", "vendor/**"],
+        ] {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
 
         let config = load_config(temp_dir.path(), None).unwrap();
-        // Should return default config
-        assert!(!config.tags.is_empty());
-        assert!(config.tags.contains(&"TODO".to_string()));
-    }
+        assert_eq!(config.tags, vec!["FROMGIT"]);
+
+        fs::write(
+            temp_dir.path().join(".todorc.json"),
+            r#"{"tags": ["LOCAL"], "exclude": ["dist/**"]}"#,
+        )
+        .unwrap();
+        let config = load_config(temp_dir.path(), None).unwrap();
+        assert_eq!(config.tags, vec!["FROMGIT", "LOCAL"]);
+        assert_eq!(config.exclude, vec!["vendor/**", "dist/**"]);
+    }
+
+    #[test]
+    fn test_load_global_config_no_config() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(".todorc.json"),
+            r#"{"tags": ["LOCAL"]}"#,
+        )
+        .unwrap();
+
+        let global = cli::GlobalOptions {
+            no_config: true,
+            ..Default::default()
+        };
+        let config = load_global_config(temp_dir.path(), &global).unwrap();
+        assert_eq!(config.tags, Config::new().tags);
+
+        let config = load_global_config(temp_dir.path(), &cli::GlobalOptions::default()).unwrap();
+        assert_eq!(config.tags, vec!["LOCAL"]);
+    }
+
+    #[test]
+    fn test_load_global_config_follow_config_from() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(".todorc.json"),
+            r#"{"tags": ["ROOT"]}"#,
+        )
+        .unwrap();
+        let package = temp_dir.path().join("packages").join("foo");
+        fs::create_dir_all(&package).unwrap();
+        fs::write(package.join(".todorc.json"), r#"{"tags": ["PACKAGE"]}"#).unwrap();
+
+        let config = load_global_config(&package, &cli::GlobalOptions::default()).unwrap();
+        assert_eq!(config.tags, vec!["PACKAGE"]);
+
+        let global = cli::GlobalOptions {
+            follow_config_from: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let config = load_global_config(&package, &global).unwrap();
+        assert_eq!(config.tags, vec!["ROOT"]);
+
+        let global = cli::GlobalOptions {
+            follow_config_from: Some(temp_dir.path().join("missing")),
+            ..Default::default()
+        };
+        assert!(load_global_config(&package, &global).is_err());
+    }
+
+    #[test]
+    fn test_load_config_no_file() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let config = load_config(temp_dir.path(), None).unwrap();
+        // Should return default config
+        assert!(!config.tags.is_empty());
+        assert!(config.tags.contains(&"TODO".to_string()));
+    }
 
     #[test]
     #[serial]
@@ -660,132 +2391,737 @@ fn main() {}
             case_sensitive: false,
             sort: cli::SortOrder::File,
             group_by_tag: false,
+            ..Default::default()
+        };
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_with_json_output() {
+        let temp_dir = create_test_project();
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            tags: Some(vec!["TODO".to_string()]),
+            include: Some(vec!["*.rs".to_string()]),
+            exclude: None,
+            json: true,
+            flat: false,
+            depth: 0,
+            follow_links: false,
+            hidden: false,
+            case_sensitive: true,
+            sort: cli::SortOrder::Priority,
+            group_by_tag: false,
+            ..Default::default()
+        };
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_with_flat_output() {
+        let temp_dir = create_test_project();
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            tags: None,
+            include: None,
+            exclude: Some(vec!["src/**".to_string()]),
+            json: false,
+            flat: true,
+            depth: 1,
+            follow_links: true,
+            hidden: true,
+            case_sensitive: false,
+            sort: cli::SortOrder::Line,
+            group_by_tag: false,
+            ..Default::default()
+        };
+
+        let global = cli::GlobalOptions {
+            no_color: false,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_stream() {
+        let temp_dir = create_test_project();
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: true,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            stream: true,
+            sort_within_file: Some(cli::SortOrder::Priority),
+            ..Default::default()
+        };
+        assert!(cmd_scan(args, &global).is_ok());
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            stream: true,
+            format: Some(OutputFormat::Json),
+            ..Default::default()
+        };
+        assert!(cmd_scan(args, &global).is_err());
+    }
+
+    #[test]
+    fn test_stream_scan_tree_connectors() {
+        colored::control::set_override(false);
+        let temp_dir = create_test_project();
+        let path = temp_dir.path().canonicalize().unwrap();
+        let args = cli::ScanArgs {
+            path: Some(path.clone()),
+            stream: true,
+            ..Default::default()
+        };
+        let config = Config::new();
+        let scanner = Scanner::new(
+            TodoParser::new(&config.tags, false),
+            scan_options(&args, &config),
+        );
+        let print_options = PrintOptions {
+            format: OutputFormat::Tree,
+            show_summary: false,
+            ..Default::default()
+        };
+
+        let mut output = Vec::new();
+        stream_scan(&scanner, &args, &config, &path, print_options, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let headers: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("├──") || line.starts_with("└──"))
+            .collect();
+        assert_eq!(headers.len(), 3);
+        assert!(headers[..2].iter().all(|line| line.starts_with("├──")));
+        assert!(headers[2].starts_with("└──"));
+    }
+
+    #[test]
+    fn test_cmd_scan_group_by_tag() {
+        let temp_dir = create_test_project();
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            tags: None,
+            include: None,
+            exclude: None,
+            json: false,
+            flat: false,
+            depth: 0,
+            follow_links: false,
+            hidden: false,
+            case_sensitive: false,
+            sort: cli::SortOrder::File,
+            group_by_tag: true,
+            ..Default::default()
+        };
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_group_by_tag_with_color() {
+        let temp_dir = create_test_project();
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            tags: None,
+            include: None,
+            exclude: None,
+            json: false,
+            flat: false,
+            depth: 0,
+            follow_links: false,
+            hidden: false,
+            case_sensitive: false,
+            sort: cli::SortOrder::File,
+            group_by_tag: true,
+            ..Default::default()
+        };
+
+        let global = cli::GlobalOptions {
+            no_color: false,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_no_gitignore() {
+        let temp_dir = create_test_project();
+        fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        fs::write(temp_dir.path().join(".gitignore"), "src/\n").unwrap();
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            no_gitignore: true,
+            ..Default::default()
+        };
+
+        // The gitignored file is scanned only with --no-gitignore
+        let tags = config::default_tags();
+        let scan = |args: &ScanArgs| {
+            let scanner = Scanner::new(
+                TodoParser::new(&tags, false),
+                scan_options(args, &Config::new()),
+            );
+            let result = scanner.scan(temp_dir.path()).unwrap();
+            result
+                .all_items()
+                .into_iter()
+                .any(|(_, item)| item.message == "Temporary workaround")
+        };
+        assert!(scan(&args));
+        assert!(!scan(&ScanArgs::default()));
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_fail_above_requires_tap() {
+        let temp_dir = create_test_project();
+        let args = |format| cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            format,
+            fail_above: Some(Priority::High),
+            ..Default::default()
+        };
+        let global = cli::GlobalOptions {
+            no_color: true,
+            ..Default::default()
+        };
+
+        assert!(cmd_scan(args(None), &global).is_err());
+        assert!(cmd_scan(args(Some(OutputFormat::Json)), &global).is_err());
+        assert!(cmd_scan(args(Some(OutputFormat::Tap)), &global).is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_ignore_pattern_file() {
+        let temp_dir = create_test_project();
+        let pattern_file = temp_dir.path().join("ignore.txt");
+        fs::write(&pattern_file, "# skip sources\nsrc/**\n").unwrap();
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            ignore_pattern_file: Some(pattern_file),
+            ..Default::default()
+        };
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cmd_scan_missing_ignore_pattern_file() {
+        let temp_dir = create_test_project();
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            ignore_pattern_file: Some(temp_dir.path().join("missing.txt")),
+            ..Default::default()
+        };
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let result = cmd_scan(args, &global);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_config_with_scan_args() {
+        let mut config = Config::new();
+        config.threads = Some(4);
+        config.title = Some("Audit".to_string());
+
+        let args = cli::ScanArgs {
+            case_sensitive: true,
+            parallel_files: Some(2),
+            color_scheme: Some(printer::ColorScheme::Nord),
+            ..Default::default()
+        };
+        let config = config_with_scan_args(&config, &args);
+
+        assert!(config.case_sensitive);
+        assert!(!config.strip_author);
+        assert_eq!(config.threads, Some(4));
+        assert_eq!(config.parallel_files, Some(2));
+        assert_eq!(config.color_scheme, Some(printer::ColorScheme::Nord));
+        assert_eq!(config.title, Some("Audit".to_string()));
+    }
+
+    #[test]
+    #[serial]
+    fn test_cmd_scan_write_config_only() {
+        let temp_dir = create_test_project();
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let args = cli::ScanArgs {
+            tags: Some(vec!["BUG".to_string(), "FIXME".to_string()]),
+            exclude: Some(vec!["target/**".to_string()]),
+            write_config_only: true,
+            quiet: true,
+            ..Default::default()
+        };
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+        let result = cmd_scan(args, &global);
+
+        std::env::set_current_dir(&original_dir).unwrap();
+        assert!(result.is_ok());
+
+        let saved = Config::load_from_file(&temp_dir.path().join(".todorc.json")).unwrap();
+        assert_eq!(saved.tags, vec!["BUG", "FIXME"]);
+        assert_eq!(saved.exclude, vec!["target/**"]);
+        assert!(saved.no_color);
+    }
+
+    #[test]
+    fn test_cmd_scan_stats_after_scan() {
+        let temp_dir = create_test_project();
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            stats_after_scan: true,
+            ..Default::default()
+        };
+        assert!(cmd_scan(args, &global).is_ok());
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            json: true,
+            stats_after_scan: true,
+            ..Default::default()
+        };
+        assert!(cmd_scan(args, &global).is_ok());
+    }
+
+    #[test]
+    fn test_expand_message_prefix() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("widgets");
+        fs::create_dir(&dir).unwrap();
+
+        assert_eq!(expand_message_prefix("[x] ", &dir), "[x] ");
+        assert_eq!(expand_message_prefix("[{repo}] ", &dir), "[widgets] ");
+    }
+
+    #[test]
+    fn test_cmd_scan_list_files() {
+        let temp_dir = create_test_project();
+        let global = cli::GlobalOptions {
+            no_color: true,
+            verbose: false,
+            config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
+        };
+
+        for json in [false, true] {
+            let args = cli::ScanArgs {
+                path: Some(temp_dir.path().to_path_buf()),
+                list_files: true,
+                json,
+                ..Default::default()
+            };
+            assert!(cmd_scan(args, &global).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_apply_extension_priorities() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "// FIXME: lib\n").unwrap();
+        fs::write(temp_dir.path().join("lib_test.rs"), "// FIXME: test\n").unwrap();
+
+        let tags = vec!["FIXME".to_string()];
+        let scanner = Scanner::new(TodoParser::new(&tags, false), ScanOptions::default());
+        let mut result = scanner.scan(temp_dir.path()).unwrap();
+
+        let mut config = Config::new();
+        config.extension_priorities.insert(
+            "FIXME".to_string(),
+            HashMap::from([
+                ("rs".to_string(), Priority::High),
+                ("_test.rs".to_string(), Priority::Low),
+            ]),
+        );
+        apply_extension_priorities(&mut result, &config);
+
+        for (path, item) in result.iter() {
+            let expected = if path.ends_with("lib_test.rs") {
+                Priority::Low
+            } else {
+                Priority::High
+            };
+            assert_eq!(item.priority, expected);
+        }
+    }
+
+    #[test]
+    fn test_cluster_stats_json() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("a.rs"),
+            "// TODO: handle the error\n// TODO: handle this error\n// TODO: other\n",
+        )
+        .unwrap();
+
+        let tags = vec!["TODO".to_string()];
+        let scanner = Scanner::new(TodoParser::new(&tags, false), ScanOptions::default());
+        let result = scanner.scan(temp_dir.path()).unwrap();
+
+        let clusters = cluster_stats_json(&result, 0.5, 10);
+        assert_eq!(clusters.as_array().unwrap().len(), 1);
+        assert_eq!(clusters[0]["size"], 2);
+        assert_eq!(clusters[0]["message"], "handle the error");
+        assert_eq!(clusters[0]["items"][1]["path"], "a.rs");
+        assert_eq!(clusters[0]["items"][1]["line"], 2);
+
+        assert_eq!(cluster_stats_json(&result, 0.5, 0), serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_check_coverage() {
+        let summary = Summary {
+            total_count: 2,
+            files_with_todos: 1,
+            files_scanned: 4,
+            tag_counts: HashMap::new(),
+            truncated: false,
+            timed_out: false,
+        };
+
+        assert!(check_coverage(&summary, None).is_ok());
+        assert!(check_coverage(&summary, Some(0.75)).is_ok());
+        let err = check_coverage(&summary, Some(0.8)).unwrap_err();
+        assert!(err.to_string().contains("75.0%"));
+    }
+
+    #[test]
+    fn test_stats_json() {
+        let temp_dir = create_test_project();
+        let tags = vec!["TODO".to_string(), "FIXME".to_string()];
+        let scanner = Scanner::new(TodoParser::new(&tags, false), ScanOptions::default());
+        let result = scanner.scan(temp_dir.path()).unwrap();
+
+        let stats = stats_json(&result.summary);
+        assert_eq!(stats["total_items"], result.summary.total_count);
+        assert_eq!(stats["files_with_todos"], result.summary.files_with_todos);
+        assert!(stats["tag_counts"].is_object());
+    }
+
+    #[test]
+    fn test_age_breakdown() {
+        let temp_dir = create_test_project();
+        let tags = vec!["TODO".to_string(), "FIXME".to_string()];
+        let scanner = Scanner::new(TodoParser::new(&tags, false), ScanOptions::default());
+        let mut result = scanner.scan(temp_dir.path()).unwrap();
+
+        // Outside a repository nothing can be blamed, so every item is unknown
+        annotate_created_at(&mut result);
+        let breakdown = age_breakdown(&result);
+        assert_eq!(breakdown.len(), 5);
+        assert_eq!(breakdown[4], ("Unknown", result.summary.total_count));
+
+        let stats = age_stats_json(&result);
+        assert_eq!(stats[0]["age"], "<1 week");
+        assert_eq!(stats[3]["age"], ">6 months");
+    }
+
+    #[test]
+    fn test_cmd_scan_output_template_file() {
+        let temp_dir = create_test_project();
+        let template_file = temp_dir.path().join("report.tera");
+        fs::write(&template_file, "{{ summary.total_count }} items\n").unwrap();
+
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
-        let result = cmd_scan(args, &global);
-        assert!(result.is_ok());
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            output_template_file: Some(template_file),
+            ..Default::default()
+        };
+        assert!(cmd_scan(args, &global).is_ok());
+
+        let args = cli::ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            output_template_file: Some(temp_dir.path().join("missing.tera")),
+            ..Default::default()
+        };
+        assert!(cmd_scan(args, &global).is_err());
     }
 
     #[test]
-    fn test_cmd_scan_with_json_output() {
+    fn test_cmd_snapshot_project_local() {
         let temp_dir = create_test_project();
-
-        let args = cli::ScanArgs {
+        let location = cli::SnapshotLocation {
             path: Some(temp_dir.path().to_path_buf()),
-            tags: Some(vec!["TODO".to_string()]),
-            include: Some(vec!["*.rs".to_string()]),
-            exclude: None,
-            json: true,
-            flat: false,
-            depth: 0,
-            follow_links: false,
-            hidden: false,
-            case_sensitive: true,
-            sort: cli::SortOrder::Priority,
-            group_by_tag: false,
+            project_local: true,
         };
-
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
-        let result = cmd_scan(args, &global);
-        assert!(result.is_ok());
-    }
+        let save = cli::SnapshotArgs {
+            command: cli::SnapshotCommand::Save(cli::SnapshotSaveArgs {
+                name: "baseline".to_string(),
+                tags: None,
+                location: location.clone(),
+            }),
+        };
+        assert!(cmd_snapshot(save, &global).is_ok());
+        assert!(
+            temp_dir
+                .path()
+                .join(".todo-tree/snapshots/baseline.json")
+                .exists()
+        );
 
-    #[test]
-    fn test_cmd_scan_with_flat_output() {
-        let temp_dir = create_test_project();
+        let diff = cli::SnapshotArgs {
+            command: cli::SnapshotCommand::Diff(cli::SnapshotDiffArgs {
+                name: "baseline".to_string(),
+                tags: None,
+                location: location.clone(),
+            }),
+        };
+        assert!(cmd_snapshot(diff, &global).is_ok());
 
-        let args = cli::ScanArgs {
-            path: Some(temp_dir.path().to_path_buf()),
-            tags: None,
-            include: None,
-            exclude: Some(vec!["src/**".to_string()]),
-            json: false,
-            flat: true,
-            depth: 1,
-            follow_links: true,
-            hidden: true,
-            case_sensitive: false,
-            sort: cli::SortOrder::Line,
-            group_by_tag: false,
+        let list = cli::SnapshotArgs {
+            command: cli::SnapshotCommand::List(cli::SnapshotListArgs {
+                location: location.clone(),
+            }),
+        };
+        assert!(cmd_snapshot(list, &global).is_ok());
+
+        let missing = cli::SnapshotArgs {
+            command: cli::SnapshotCommand::Diff(cli::SnapshotDiffArgs {
+                name: "missing".to_string(),
+                tags: None,
+                location,
+            }),
         };
+        assert!(cmd_snapshot(missing, &global).is_err());
+    }
 
+    #[test]
+    #[serial]
+    fn test_cmd_parse() {
         let global = cli::GlobalOptions {
-            no_color: false,
+            no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
-        let result = cmd_scan(args, &global);
-        assert!(result.is_ok());
+        for explain in [false, true] {
+            let args = cli::ParseArgs {
+                line: "// FIXME: fix this".to_string(),
+                explain,
+                ..Default::default()
+            };
+            assert!(cmd_parse(args, &global).is_ok());
+        }
     }
 
     #[test]
-    fn test_cmd_scan_group_by_tag() {
+    #[serial]
+    fn test_cmd_show() {
         let temp_dir = create_test_project();
-
-        let args = cli::ScanArgs {
-            path: Some(temp_dir.path().to_path_buf()),
-            tags: None,
-            include: None,
-            exclude: None,
-            json: false,
-            flat: false,
-            depth: 0,
-            follow_links: false,
-            hidden: false,
-            case_sensitive: false,
-            sort: cli::SortOrder::File,
-            group_by_tag: true,
-        };
+        let file = temp_dir.path().join("main.rs");
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
-        let result = cmd_scan(args, &global);
-        assert!(result.is_ok());
+        let args = cli::ShowArgs {
+            location: format!("{}:5", file.display()),
+            ..Default::default()
+        };
+        assert!(cmd_show(args, &global).is_ok());
+
+        let args = cli::ShowArgs {
+            location: format!("{}:1", file.display()),
+            ..Default::default()
+        };
+        assert!(cmd_show(args, &global).is_err());
+
+        let args = cli::ShowArgs {
+            location: file.display().to_string(),
+            ..Default::default()
+        };
+        assert!(cmd_show(args, &global).is_err());
     }
 
     #[test]
-    fn test_cmd_scan_group_by_tag_with_color() {
-        let temp_dir = create_test_project();
+    fn test_scan_output_format() {
+        let args = ScanArgs::default();
+        assert_eq!(scan_output_format(&args), OutputFormat::Tree);
 
-        let args = cli::ScanArgs {
-            path: Some(temp_dir.path().to_path_buf()),
-            tags: None,
-            include: None,
-            exclude: None,
-            json: false,
-            flat: false,
-            depth: 0,
-            follow_links: false,
-            hidden: false,
-            case_sensitive: false,
-            sort: cli::SortOrder::File,
-            group_by_tag: true,
+        let args = ScanArgs {
+            flat: true,
+            ..Default::default()
         };
+        assert_eq!(scan_output_format(&args), OutputFormat::Flat);
 
-        let global = cli::GlobalOptions {
-            no_color: false,
-            verbose: false,
-            config: None,
+        let args = ScanArgs {
+            table: true,
+            ..Default::default()
         };
+        assert_eq!(scan_output_format(&args), OutputFormat::Table);
 
-        let result = cmd_scan(args, &global);
-        assert!(result.is_ok());
+        let args = ScanArgs {
+            dot: true,
+            ..Default::default()
+        };
+        assert_eq!(scan_output_format(&args), OutputFormat::Dot);
+
+        let args = ScanArgs {
+            json_compact: true,
+            ..Default::default()
+        };
+        assert_eq!(scan_output_format(&args), OutputFormat::Json);
+
+        let args = ScanArgs {
+            json: true,
+            format: Some(OutputFormat::Delta),
+            ..Default::default()
+        };
+        assert_eq!(scan_output_format(&args), OutputFormat::Delta);
     }
 
     #[test]
@@ -798,6 +3134,7 @@ fn main() {}
             include: None,
             exclude: None,
             json: false,
+            table: false,
             filter: None,
             case_sensitive: false,
         };
@@ -806,6 +3143,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_list(args, &global);
@@ -822,6 +3163,7 @@ fn main() {}
             include: Some(vec!["*.rs".to_string()]),
             exclude: Some(vec!["src/**".to_string()]),
             json: false,
+            table: false,
             filter: Some("TODO".to_string()),
             case_sensitive: true,
         };
@@ -830,6 +3172,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_list(args, &global);
@@ -846,6 +3192,7 @@ fn main() {}
             include: None,
             exclude: None,
             json: true,
+            table: false,
             filter: None,
             case_sensitive: false,
         };
@@ -854,6 +3201,10 @@ fn main() {}
             no_color: false,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_list(args, &global);
@@ -886,6 +3237,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -920,6 +3275,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -954,6 +3313,10 @@ fn main() {}
             no_color: false,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -963,6 +3326,54 @@ fn main() {}
         assert!(result.is_ok());
     }
 
+    #[test]
+    #[serial]
+    fn test_cmd_tags_add_keeps_layers_out_of_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        git(&["config", "todo-tree.exclude", "vendor/**"]);
+
+        let path = temp_dir.path().join(".todorc.json");
+        let original = Config {
+            tags: vec!["TODO".to_string()],
+            ..Config::new()
+        };
+        original.save(&path).unwrap();
+
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+        // SAFETY: the test is serial and removes the variable again before returning
+        unsafe { std::env::set_var("TODO_TREE_TITLE", "secret") };
+        let args = cli::TagsArgs {
+            json: false,
+            add: Some("HACK".to_string()),
+            remove: None,
+            reset: false,
+        };
+        let result = cmd_tags(args, &cli::GlobalOptions::default());
+        unsafe { std::env::remove_var("TODO_TREE_TITLE") };
+        std::env::set_current_dir(original_dir).unwrap();
+        result.unwrap();
+
+        let expected = Config {
+            tags: vec!["TODO".to_string(), "HACK".to_string()],
+            ..original
+        };
+        assert_eq!(
+            serde_json::to_value(Config::load_from_file(&path).unwrap()).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
+    }
+
     #[test]
     #[serial]
     fn test_cmd_tags_add_new() {
@@ -988,6 +3399,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -997,6 +3412,57 @@ fn main() {}
         assert!(result.is_ok());
     }
 
+    #[test]
+    #[serial]
+    fn test_cmd_tags_no_config_keeps_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+        let config_path = temp_dir.path().join(".todorc.json");
+        let content = r#"{"tags": ["LOCAL"], "exclude": ["vendor/**"]}"#;
+        fs::write(&config_path, content).unwrap();
+
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let global = cli::GlobalOptions {
+            no_color: true,
+            no_config: true,
+            ..Default::default()
+        };
+        let add = cmd_tags(
+            cli::TagsArgs {
+                json: false,
+                add: Some("X".to_string()),
+                remove: None,
+                reset: false,
+            },
+            &global,
+        );
+        let reset = cmd_tags(
+            cli::TagsArgs {
+                json: false,
+                add: None,
+                remove: None,
+                reset: true,
+            },
+            &global,
+        );
+        let write_config = cmd_scan(
+            cli::ScanArgs {
+                write_config_only: true,
+                quiet: true,
+                ..Default::default()
+            },
+            &global,
+        );
+
+        std::env::set_current_dir(original_dir).unwrap();
+
+        assert!(add.is_err());
+        assert!(reset.is_err());
+        assert!(write_config.is_err());
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), content);
+    }
+
     #[test]
     #[serial]
     fn test_cmd_tags_add_existing() {
@@ -1022,6 +3488,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -1056,6 +3526,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -1090,6 +3564,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -1124,6 +3602,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_tags(args, &global);
@@ -1144,6 +3626,7 @@ fn main() {}
         let args = cli::InitArgs {
             format: cli::ConfigFormat::Json,
             force: false,
+            ..Default::default()
         };
 
         let result = cmd_init(args);
@@ -1165,6 +3648,7 @@ fn main() {}
         let args = cli::InitArgs {
             format: cli::ConfigFormat::Yaml,
             force: false,
+            ..Default::default()
         };
 
         let result = cmd_init(args);
@@ -1189,6 +3673,7 @@ fn main() {}
         let args = cli::InitArgs {
             format: cli::ConfigFormat::Json,
             force: false,
+            ..Default::default()
         };
 
         let result = cmd_init(args);
@@ -1212,6 +3697,30 @@ fn main() {}
         let args = cli::InitArgs {
             format: cli::ConfigFormat::Json,
             force: true,
+            ..Default::default()
+        };
+
+        let result = cmd_init(args);
+
+        std::env::set_current_dir(original_dir).unwrap();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test_cmd_init_pre_push() {
+        let temp_dir = TempDir::new().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+
+        fs::create_dir(temp_dir.path().join(".git")).unwrap();
+
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let args = cli::InitArgs {
+            pre_push: true,
+            max_critical: 5,
+            ..Default::default()
         };
 
         let result = cmd_init(args);
@@ -1219,6 +3728,137 @@ fn main() {}
         std::env::set_current_dir(original_dir).unwrap();
 
         assert!(result.is_ok());
+        let hook = fs::read_to_string(temp_dir.path().join(".git/hooks/pre-push")).unwrap();
+        assert!(hook.contains("MAX_CRITICAL=5"));
+        assert!(!temp_dir.path().join(".todorc.json").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test_cmd_init_pre_push_outside_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let args = cli::InitArgs {
+            pre_push: true,
+            ..Default::default()
+        };
+
+        let result = cmd_init(args);
+
+        std::env::set_current_dir(original_dir).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cmd_verify() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("main.rs"),
+            "// FIXME(PROJ-1): tracked\n// TODO: free-form\n",
+        )
+        .unwrap();
+
+        let args = cli::VerifyArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let global = cli::GlobalOptions {
+            no_color: true,
+            no_config: true,
+            ..Default::default()
+        };
+        assert!(cmd_verify(args, &global).is_ok());
+
+        fs::write(temp_dir.path().join("lib.rs"), "// BUG: untracked\n").unwrap();
+        let args = cli::VerifyArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let err = cmd_verify(args, &global).unwrap_err();
+        assert!(err.to_string().contains("1 TODO item(s) missing"));
+
+        let args = cli::VerifyArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            required_tags: Some(vec!["FIXME".to_string()]),
+            ..Default::default()
+        };
+        assert!(cmd_verify(args, &global).is_ok());
+    }
+
+    #[test]
+    fn test_cmd_config_upgrade() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".todorc.json");
+        fs::write(
+            &config_path,
+            r#"{"tags": ["TODO"], "custom_pattern": "PATTERN"}"#,
+        )
+        .unwrap();
+        let upgrade = |write| cli::ConfigArgs {
+            command: cli::ConfigCommand::Upgrade(cli::ConfigUpgradeArgs {
+                file: Some(config_path.clone()),
+                write,
+            }),
+        };
+        let global = cli::GlobalOptions::default();
+
+        assert!(cmd_config(upgrade(false), &global).is_ok());
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.custom_pattern, Some("PATTERN".to_string()));
+
+        assert!(cmd_config(upgrade(true), &global).is_ok());
+        let config = Config::load_from_file(&config_path).unwrap();
+        assert_eq!(config.custom_pattern, None);
+        assert_eq!(config.custom_patterns, vec!["PATTERN"]);
+
+        let written: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        let keys: Vec<_> = written.as_object().unwrap().keys().cloned().collect();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(written["tags"], serde_json::json!(["TODO"]));
+    }
+
+    #[test]
+    fn test_scan_title() {
+        let mut config = Config::new();
+        config.title = Some("From config".to_string());
+
+        let args = ScanArgs::default();
+        assert_eq!(scan_title(&args, &config).as_deref(), Some("From config"));
+        assert_eq!(scan_title(&args, &Config::new()), None);
+
+        let args = ScanArgs {
+            title: Some("Audit".to_string()),
+            title_with_timestamp: true,
+            ..Default::default()
+        };
+        let title = scan_title(&args, &config).unwrap();
+        assert!(title.starts_with("Audit ("));
+        assert!(title.ends_with(')'));
+    }
+
+    #[test]
+    fn test_cmd_scan_with_ext() {
+        let temp_dir = create_test_project();
+
+        let args = ScanArgs {
+            path: Some(temp_dir.path().to_path_buf()),
+            ext: Some(vec!["rs".to_string()]),
+            include: Some(vec!["*.py".to_string()]),
+            json: true,
+            ..Default::default()
+        };
+        let global = cli::GlobalOptions {
+            no_color: true,
+            no_config: true,
+            ..Default::default()
+        };
+
+        assert!(cmd_scan(args, &global).is_ok());
     }
 
     #[test]
@@ -1229,18 +3869,100 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: None,
             json: false,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_cmd_stats_min_priority() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("main.rs"), "// TODO: later\n").unwrap();
+
+        // Coverage is 0 with the TODO counted and 1 without it
+        let stats = |min_priority| {
+            let args = cli::StatsArgs {
+                path: Some(temp_dir.path().to_path_buf()),
+                coverage_threshold: Some(1.0),
+                min_priority,
+                ..Default::default()
+            };
+            cmd_stats(args, &cli::GlobalOptions::default())
+        };
+        assert!(stats(None).is_err());
+        assert!(stats(Some(Priority::High)).is_ok());
+        assert!(stats(Some(Priority::Medium)).is_err());
+    }
+
+    #[test]
+    fn test_directory_stats_json() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        let item = |line| TodoItem {
+            tag: "TODO".to_string(),
+            message: "Item".to_string(),
+            line,
+            column: 1,
+            line_content: None,
+            author: None,
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        };
+        result.add_file(PathBuf::from("/test/a/one.rs"), vec![item(1)]);
+        result.add_file(PathBuf::from("/test/b/two.rs"), vec![item(1), item(2)]);
+        result.add_file(PathBuf::from("/test/a/three.rs"), vec![item(1)]);
+        result.add_file(PathBuf::from("/test/c/four.rs"), vec![item(1)]);
+
+        let json = directory_stats_json(&result);
+        let directories: Vec<&str> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["directory"].as_str().unwrap())
+            .collect();
+        assert_eq!(directories, vec!["a", "b", "c"]);
+        assert_eq!(json[0]["total_items"], 2);
+        assert_eq!(json[0]["files_with_todos"], 2);
+    }
+
+    #[test]
+    fn test_cmd_stats_group_by_dir() {
+        let temp_dir = create_test_project();
+
+        for json in [false, true] {
+            let args = cli::StatsArgs {
+                path: Some(temp_dir.path().to_path_buf()),
+                json,
+                group_by_dir: true,
+                ..Default::default()
+            };
+            let global = cli::GlobalOptions {
+                no_color: true,
+                ..Default::default()
+            };
+            assert!(cmd_stats(args, &global).is_ok());
+        }
+    }
+
     #[test]
     fn test_cmd_stats_with_json() {
         let temp_dir = create_test_project();
@@ -1249,12 +3971,22 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: Some(vec!["TODO".to_string(), "FIXME".to_string()]),
             json: true,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
@@ -1269,12 +4001,22 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: None,
             json: false,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: false,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
@@ -1292,12 +4034,22 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: None,
             json: false,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
@@ -1315,12 +4067,22 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: None,
             json: true,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
@@ -1338,12 +4100,22 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: Some(vec!["NONEXISTENT".to_string()]),
             json: false,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
@@ -1361,12 +4133,22 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: Some(vec!["NONEXISTENT".to_string()]),
             json: false,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: false,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
@@ -1388,12 +4170,22 @@ fn main() {}
             path: Some(temp_dir.path().to_path_buf()),
             tags: None,
             json: false,
+            group_by_dir: false,
+            coverage_threshold: None,
+            with_age: false,
+            clusters: None,
+            cluster_threshold: 0.5,
+            min_priority: None,
         };
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: None,
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_stats(args, &global);
@@ -1421,12 +4213,17 @@ fn main() {}
             case_sensitive: false,
             sort: cli::SortOrder::File,
             group_by_tag: false,
+            ..Default::default()
         };
 
         let global = cli::GlobalOptions {
             no_color: true,
             verbose: false,
             config: Some(config_path),
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_scan(args, &global);
@@ -1447,6 +4244,7 @@ fn main() {}
             include: None,
             exclude: None,
             json: false,
+            table: false,
             filter: None,
             case_sensitive: false,
         };
@@ -1455,6 +4253,10 @@ fn main() {}
             no_color: true,
             verbose: false,
             config: Some(config_path),
+            no_config: false,
+            follow_config_from: None,
+            debug: false,
+            log_level: None,
         };
 
         let result = cmd_list(args, &global);
@@ -0,0 +1,693 @@
+use crate::cli::GlobalOptions;
+use crate::parser::TodoParser;
+use crate::scanner::{ScanOptions, Scanner};
+use anyhow::{Context, Result};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidChangeWatchedFiles, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{HoverRequest, RegisterCapability, Request as _};
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidChangeWatchedFilesParams,
+    DidChangeWatchedFilesRegistrationOptions, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    FileChangeType, FileSystemWatcher, GlobPattern, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, InitializeParams, MarkupContent, MarkupKind, NumberOrString, Position,
+    PublishDiagnosticsParams, Range, Registration, RegistrationParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Url,
+};
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use todo_tree_core::{Priority, ScanResult, TodoItem};
+
+/// Source name attached to every published diagnostic
+const DIAGNOSTIC_SOURCE: &str = "todo-tree";
+
+/// Map a TODO priority to an LSP diagnostic severity
+pub fn severity_for_priority(priority: Priority) -> DiagnosticSeverity {
+    match priority {
+        Priority::Critical => DiagnosticSeverity::ERROR,
+        Priority::High => DiagnosticSeverity::WARNING,
+        Priority::Medium => DiagnosticSeverity::INFORMATION,
+        Priority::Low => DiagnosticSeverity::HINT,
+    }
+}
+
+/// Convert a TODO item into an LSP diagnostic spanning its tag
+pub fn item_to_diagnostic(item: &TodoItem) -> Diagnostic {
+    let line = item.line.saturating_sub(1) as u32;
+    let byte_column = item.column.saturating_sub(1);
+
+    // LSP positions are UTF-16 offsets, while item columns are byte offsets
+    let start = item
+        .line_content
+        .as_deref()
+        .and_then(|content| content.get(..byte_column))
+        .map(|prefix| prefix.encode_utf16().count())
+        .unwrap_or(byte_column) as u32;
+    let end = start + item.tag.encode_utf16().count() as u32;
+
+    let message = if item.message.is_empty() {
+        item.tag.clone()
+    } else {
+        format!("{}: {}", item.tag, item.message)
+    };
+
+    Diagnostic {
+        range: Range::new(Position::new(line, start), Position::new(line, end)),
+        severity: Some(severity_for_priority(item.priority)),
+        code: Some(NumberOrString::String(item.tag.clone())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Convert a scan result into diagnostics grouped by document URI
+pub fn workspace_diagnostics(result: &ScanResult) -> Vec<(Url, Vec<Diagnostic>)> {
+    result
+        .sorted_files()
+        .into_iter()
+        .filter_map(|(path, items)| {
+            let uri = Url::from_file_path(path).ok()?;
+            Some((uri, items.iter().map(item_to_diagnostic).collect()))
+        })
+        .collect()
+}
+
+/// Describe TODO items for a hover, or `None` when there are none
+pub fn hover_for_items(items: &[&TodoItem]) -> Option<Hover> {
+    if items.is_empty() {
+        return None;
+    }
+
+    let value = items
+        .iter()
+        .map(|item| {
+            format!(
+                "**{}** ({}): {}",
+                item.tag,
+                item.priority.display_name(),
+                item.message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: None,
+    })
+}
+
+/// Run the language server over stdin/stdout until the client exits
+///
+/// `fallback_root` is used when the client does not announce a workspace folder.
+pub fn run(
+    fallback_root: PathBuf,
+    tags: Option<Vec<String>>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection, fallback_root, tags, global)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Serve LSP requests on an established connection
+pub fn serve(
+    connection: &Connection,
+    fallback_root: PathBuf,
+    tags: Option<Vec<String>>,
+    global: &GlobalOptions,
+) -> Result<()> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+
+    let params = connection
+        .initialize(serde_json::to_value(capabilities)?)
+        .context("LSP initialization failed")?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    let root = params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .and_then(|folder| folder.uri.to_file_path().ok())
+        .unwrap_or(fallback_root);
+
+    let mut config = crate::load_global_config(&root, global)?;
+    if let Some(tags) = tags {
+        config.tags = tags;
+    }
+    config.apply_ignore_pattern_file(&root)?;
+
+    let parser = TodoParser::new(&config.tags, config.case_sensitive)
+        .with_tag_priorities(config.tag_priorities.clone())
+        .with_multiple_patterns(&config.custom_patterns)
+        .context("Invalid custom pattern in config")?;
+    let scan_options = ScanOptions {
+        include: config.include_patterns(),
+        exclude: config.exclude.clone(),
+        ..Default::default()
+    };
+
+    let scanner = Scanner::new(parser.clone(), scan_options);
+    let mut server = Server {
+        connection,
+        parser,
+        files: scanner.dry_run(&root)?.into_iter().collect(),
+        items: ScanResult::new(root.clone()),
+        scanner,
+        root,
+        published: HashSet::new(),
+    };
+
+    let watch_supported = params
+        .capabilities
+        .workspace
+        .and_then(|workspace| workspace.did_change_watched_files)
+        .and_then(|watched| watched.dynamic_registration)
+        .unwrap_or(false);
+    if watch_supported {
+        server.register_file_watcher()?;
+    }
+
+    // Publish diagnostics for the whole workspace up front
+    let result = server.scanner.scan(&server.root)?;
+    for (uri, diagnostics) in workspace_diagnostics(&result) {
+        server.publish(uri, diagnostics)?;
+    }
+    server.items = result;
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                match request.method.as_str() {
+                    HoverRequest::METHOD => server.hover(request)?,
+                    _ => server.reject(request)?,
+                }
+            }
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// State of a running language server
+struct Server<'a> {
+    connection: &'a Connection,
+    parser: TodoParser,
+    scanner: Scanner,
+    root: PathBuf,
+
+    /// Files a scan of `root` parses, so include, exclude and ignore files apply
+    files: HashSet<PathBuf>,
+
+    /// Current items of each document, for hovers
+    items: ScanResult,
+
+    /// Documents that currently have diagnostics on the client
+    published: HashSet<Url>,
+}
+
+impl Server<'_> {
+    /// Ask the client to notify us about file changes in the workspace
+    fn register_file_watcher(&self) -> Result<()> {
+        let options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String("**/*".to_string()),
+                kind: None,
+            }],
+        };
+        let params = RegistrationParams {
+            registrations: vec![Registration {
+                id: "todo-tree-watcher".to_string(),
+                method: DidChangeWatchedFiles::METHOD.to_string(),
+                register_options: Some(serde_json::to_value(options)?),
+            }],
+        };
+
+        let request = Request::new(
+            RequestId::from("todo-tree/register-watcher".to_string()),
+            RegisterCapability::METHOD.to_string(),
+            params,
+        );
+        self.connection.sender.send(request.into())?;
+        Ok(())
+    }
+
+    /// Describe the TODO items on the hovered line
+    fn hover(&self, request: Request) -> Result<()> {
+        let response = match serde_json::from_value::<HoverParams>(request.params) {
+            Ok(params) => {
+                let position = params.text_document_position_params;
+                let hover = document_path(&position.text_document.uri).and_then(|path| {
+                    let line = position.position.line as usize + 1;
+                    hover_for_items(&self.items.items_near_line(&path, line, 0))
+                });
+                Response::new_ok(request.id, hover)
+            }
+            Err(err) => {
+                Response::new_err(request.id, ErrorCode::InvalidParams as i32, err.to_string())
+            }
+        };
+        self.connection.sender.send(response.into())?;
+        Ok(())
+    }
+
+    /// Reply to a request the server does not implement
+    fn reject(&self, request: Request) -> Result<()> {
+        let response = Response::new_err(
+            request.id,
+            ErrorCode::MethodNotFound as i32,
+            format!("Unsupported request: {}", request.method),
+        );
+        self.connection.sender.send(response.into())?;
+        Ok(())
+    }
+
+    /// Re-scan documents in response to editor and file system events
+    ///
+    /// Malformed notifications are logged and skipped rather than ending the server.
+    fn handle_notification(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                if let Some(params) = notification_params::<DidOpenTextDocumentParams>(notification)
+                {
+                    self.publish_content(params.text_document.uri, &params.text_document.text)?;
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                if let Some(params) =
+                    notification_params::<DidChangeTextDocumentParams>(notification)
+                    && let Some(change) = params.content_changes.last()
+                {
+                    self.publish_content(params.text_document.uri, &change.text)?;
+                }
+            }
+            DidSaveTextDocument::METHOD => {
+                if let Some(params) = notification_params::<DidSaveTextDocumentParams>(notification)
+                {
+                    match params.text {
+                        Some(text) => self.publish_content(params.text_document.uri, &text)?,
+                        None => self.publish_file(params.text_document.uri)?,
+                    }
+                }
+            }
+            DidChangeWatchedFiles::METHOD => {
+                if let Some(params) =
+                    notification_params::<DidChangeWatchedFilesParams>(notification)
+                {
+                    self.handle_file_changes(params)?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Update diagnostics for files created, changed or deleted on disk
+    ///
+    /// New files and edited ignore files can change which files a scan
+    /// parses, so the file list is walked again first.
+    fn handle_file_changes(&mut self, params: DidChangeWatchedFilesParams) -> Result<()> {
+        let rewalk = params.changes.iter().any(|change| {
+            change.typ == FileChangeType::CREATED
+                || change
+                    .uri
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    .is_some_and(is_ignore_file)
+        });
+        if rewalk {
+            self.files = self.scanner.dry_run(&self.root)?.into_iter().collect();
+        }
+
+        for change in params.changes {
+            if change.typ == FileChangeType::DELETED {
+                self.set_items(&change.uri, Vec::new());
+                self.publish(change.uri, Vec::new())?;
+            } else {
+                self.publish_file(change.uri)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a document read from disk and publish its diagnostics
+    fn publish_file(&mut self, uri: Url) -> Result<()> {
+        let Ok(path) = uri.to_file_path() else {
+            return Ok(());
+        };
+
+        // Skip directories and binary files, like the scanner does
+        match std::fs::read_to_string(&path) {
+            Ok(content) => self.publish_content(uri, &content),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Parse document content and publish its diagnostics
+    ///
+    /// Files a workspace scan would skip get no diagnostics.
+    fn publish_content(&mut self, uri: Url, content: &str) -> Result<()> {
+        let items = if self.is_scanned(&uri) {
+            self.parser.parse_content(content)
+        } else {
+            Vec::new()
+        };
+
+        let diagnostics = items.iter().map(item_to_diagnostic).collect();
+        self.set_items(&uri, items);
+        self.publish(uri, diagnostics)
+    }
+
+    /// Remember the current items of a document
+    fn set_items(&mut self, uri: &Url, items: Vec<TodoItem>) {
+        let Some(path) = document_path(uri) else {
+            return;
+        };
+        if items.is_empty() {
+            self.items.files_map.remove(&path);
+        } else {
+            self.items.files_map.insert(path, items);
+        }
+    }
+
+    /// Whether a workspace scan parses the document (documents not on disk always count)
+    fn is_scanned(&self, uri: &Url) -> bool {
+        match uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.canonicalize().ok())
+        {
+            Some(path) => self.files.contains(&path),
+            None => true,
+        }
+    }
+
+    /// Send diagnostics for a document, skipping redundant empty updates
+    fn publish(&mut self, uri: Url, diagnostics: Vec<Diagnostic>) -> Result<()> {
+        if diagnostics.is_empty() && !self.published.remove(&uri) {
+            return Ok(());
+        }
+        if !diagnostics.is_empty() {
+            self.published.insert(uri.clone());
+        }
+
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+}
+
+/// Deserialize the parameters of a notification, logging malformed ones
+fn notification_params<T: DeserializeOwned>(notification: Notification) -> Option<T> {
+    match serde_json::from_value(notification.params) {
+        Ok(params) => Some(params),
+        Err(err) => {
+            tracing::warn!(method = %notification.method, error = %err, "ignoring malformed notification");
+            None
+        }
+    }
+}
+
+/// Path of a document as the scanner reports it (canonical when it exists on disk)
+fn document_path(uri: &Url) -> Option<PathBuf> {
+    let path = uri.to_file_path().ok()?;
+    Some(path.canonicalize().unwrap_or(path))
+}
+
+/// Whether a file name is one the scanner reads ignore patterns from
+fn is_ignore_file(name: &str) -> bool {
+    matches!(
+        name,
+        ".gitignore" | ".ignore" | ".svnignore" | ".dockerignore"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn create_test_item(tag: &str, message: &str, priority: Priority) -> TodoItem {
+        TodoItem {
+            tag: tag.to_string(),
+            message: message.to_string(),
+            line: 3,
+            column: 4,
+            line_content: Some(format!("// {}: {}", tag, message)),
+            author: None,
+            priority,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        }
+    }
+
+    fn recv(client: &Connection) -> Message {
+        client
+            .receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Expected a message from the server")
+    }
+
+    #[test]
+    fn test_severity_for_priority() {
+        assert_eq!(
+            severity_for_priority(Priority::Critical),
+            DiagnosticSeverity::ERROR
+        );
+        assert_eq!(
+            severity_for_priority(Priority::High),
+            DiagnosticSeverity::WARNING
+        );
+        assert_eq!(
+            severity_for_priority(Priority::Medium),
+            DiagnosticSeverity::INFORMATION
+        );
+        assert_eq!(
+            severity_for_priority(Priority::Low),
+            DiagnosticSeverity::HINT
+        );
+    }
+
+    #[test]
+    fn test_item_to_diagnostic() {
+        let item = create_test_item("FIXME", "Fix this bug", Priority::Critical);
+        let diagnostic = item_to_diagnostic(&item);
+
+        assert_eq!(diagnostic.range.start, Position::new(2, 3));
+        assert_eq!(diagnostic.range.end, Position::new(2, 8));
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(
+            diagnostic.code,
+            Some(NumberOrString::String("FIXME".to_string()))
+        );
+        assert_eq!(diagnostic.source.as_deref(), Some("todo-tree"));
+        assert_eq!(diagnostic.message, "FIXME: Fix this bug");
+    }
+
+    #[test]
+    fn test_item_to_diagnostic_utf16_column() {
+        let mut item = create_test_item("TODO", "unicode", Priority::Medium);
+        item.line_content = Some("é // TODO: unicode".to_string());
+        item.column = 7;
+
+        let diagnostic = item_to_diagnostic(&item);
+        assert_eq!(diagnostic.range.start.character, 5);
+        assert_eq!(diagnostic.range.end.character, 9);
+    }
+
+    /// Start a server on `root` and complete the initialize handshake
+    fn start(root: &std::path::Path) -> (Connection, std::thread::JoinHandle<Result<()>>) {
+        let (server, client) = Connection::memory();
+        let server_root = root.to_path_buf();
+        let handle = std::thread::spawn(move || {
+            serve(
+                &server,
+                server_root,
+                Some(vec!["TODO".to_string()]),
+                &GlobalOptions::default(),
+            )
+        });
+
+        let params = InitializeParams::default();
+        client
+            .sender
+            .send(Request::new(RequestId::from(1), "initialize".to_string(), params).into())
+            .unwrap();
+        assert!(matches!(recv(&client), Message::Response(_)));
+        client
+            .sender
+            .send(Notification::new("initialized".to_string(), serde_json::json!({})).into())
+            .unwrap();
+
+        (client, handle)
+    }
+
+    fn shutdown(client: Connection, handle: std::thread::JoinHandle<Result<()>>) {
+        client
+            .sender
+            .send(Request::new(RequestId::from(2), "shutdown".to_string(), ()).into())
+            .unwrap();
+        assert!(matches!(recv(&client), Message::Response(_)));
+        client
+            .sender
+            .send(Notification::new("exit".to_string(), ()).into())
+            .unwrap();
+
+        handle.join().unwrap().unwrap();
+    }
+
+    fn recv_diagnostics(client: &Connection) -> PublishDiagnosticsParams {
+        let Message::Notification(notification) = recv(client) else {
+            panic!("Expected publishDiagnostics notification");
+        };
+        assert_eq!(notification.method, PublishDiagnostics::METHOD);
+        serde_json::from_value(notification.params).unwrap()
+    }
+
+    fn did_open(client: &Connection, path: &std::path::Path, text: &str) {
+        let params = DidOpenTextDocumentParams {
+            text_document: lsp_types::TextDocumentItem::new(
+                Url::from_file_path(path).unwrap(),
+                "rust".to_string(),
+                1,
+                text.to_string(),
+            ),
+        };
+        client
+            .sender
+            .send(Notification::new(DidOpenTextDocument::METHOD.to_string(), params).into())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_hover_for_items() {
+        assert!(hover_for_items(&[]).is_none());
+
+        let bug = create_test_item("BUG", "Crash on empty input", Priority::Critical);
+        let note = create_test_item("NOTE", "See the RFC", Priority::Low);
+        let hover = hover_for_items(&[&bug, &note]).unwrap();
+        let HoverContents::Markup(content) = hover.contents else {
+            panic!("Expected markup hover");
+        };
+        assert_eq!(content.kind, MarkupKind::Markdown);
+        assert_eq!(
+            content.value,
+            "**BUG** (Critical): Crash on empty input\n\n**NOTE** (Low): See the RFC"
+        );
+    }
+
+    #[test]
+    fn test_serve_publishes_workspace_diagnostics() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(root.join("main.rs"), "// TODO: first\nfn main() {}\n").unwrap();
+
+        let (client, handle) = start(&root);
+
+        let params = recv_diagnostics(&client);
+        assert_eq!(
+            params.uri,
+            Url::from_file_path(root.join("main.rs")).unwrap()
+        );
+        assert_eq!(params.diagnostics.len(), 1);
+        assert_eq!(params.diagnostics[0].message, "TODO: first");
+
+        // Hovering the TODO line describes the item, other lines have nothing
+        let uri = Url::from_file_path(root.join("main.rs")).unwrap();
+        for (id, line) in [(3, 0), (4, 1)] {
+            let params = HoverParams {
+                text_document_position_params: lsp_types::TextDocumentPositionParams::new(
+                    lsp_types::TextDocumentIdentifier::new(uri.clone()),
+                    Position::new(line, 0),
+                ),
+                work_done_progress_params: Default::default(),
+            };
+            client
+                .sender
+                .send(
+                    Request::new(
+                        RequestId::from(id),
+                        HoverRequest::METHOD.to_string(),
+                        params,
+                    )
+                    .into(),
+                )
+                .unwrap();
+            let Message::Response(response) = recv(&client) else {
+                panic!("Expected hover response");
+            };
+            let hover: Option<Hover> = serde_json::from_value(response.result.unwrap()).unwrap();
+            assert_eq!(hover.is_some(), line == 0);
+        }
+
+        shutdown(client, handle);
+    }
+
+    #[test]
+    fn test_serve_skips_ignored_files_and_bad_notifications() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::write(root.join(".ignore"), "skipped.rs\n").unwrap();
+        fs::write(root.join("main.rs"), "// TODO: first\n").unwrap();
+        fs::write(root.join("skipped.rs"), "// TODO: hidden\n").unwrap();
+
+        let (client, handle) = start(&root);
+        assert_eq!(
+            recv_diagnostics(&client).uri,
+            Url::from_file_path(root.join("main.rs")).unwrap()
+        );
+
+        // Neither a malformed notification nor an ignored file produces a message
+        client
+            .sender
+            .send(
+                Notification::new(
+                    DidOpenTextDocument::METHOD.to_string(),
+                    serde_json::json!({}),
+                )
+                .into(),
+            )
+            .unwrap();
+        did_open(&client, &root.join("skipped.rs"), "// TODO: hidden\n");
+        did_open(&client, &root.join("main.rs"), "// TODO: a\n// TODO: b\n");
+
+        let params = recv_diagnostics(&client);
+        assert_eq!(
+            params.uri,
+            Url::from_file_path(root.join("main.rs")).unwrap()
+        );
+        assert_eq!(params.diagnostics.len(), 2);
+
+        shutdown(client, handle);
+    }
+}
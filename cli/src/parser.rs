@@ -1,6 +1,9 @@
 use colored::Color;
 use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, Read};
 use std::path::Path;
+use std::time::Instant;
 use todo_tree_core::{Priority, TodoItem};
 
 /// Get the color associated with a priority level
@@ -13,6 +16,18 @@ pub fn priority_to_color(priority: Priority) -> Color {
     }
 }
 
+/// Resolve the priority for a tag, honoring configured overrides
+///
+/// `tag_priorities` is matched case-insensitively; other tags get the built-in
+/// mapping of [`Priority::from_tag`].
+pub fn priority_for_tag(tag_priorities: &HashMap<String, Priority>, tag: &str) -> Priority {
+    tag_priorities
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(tag))
+        .map(|(_, priority)| *priority)
+        .unwrap_or_else(|| Priority::from_tag(tag))
+}
+
 #[cfg(not(doctest))]
 /// Default regex pattern for matching TODO-style tags in comments.
 ///
@@ -43,11 +58,73 @@ pub fn priority_to_color(priority: Priority) -> Color {
 pub const DEFAULT_REGEX: &str =
     r#"(//|#|<!--|;|/\*|\*|--|%|"""|'''|REM\s|::)\s*($TAGS)(?:\(([^)]+)\))?[:\s]+(.*)"#;
 
+/// Comment markers recognized by [`DEFAULT_REGEX`] (its first capture group)
+const COMMENT_MARKERS: &str = r#"(//|#|<!--|;|/\*|\*|--|%|"""|'''|REM\s|::)"#;
+
+/// Explanation of how the parser handled a single line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParserExplanation {
+    /// Whether the line produced a TODO item
+    pub matched: bool,
+
+    /// Comment marker found in the line (e.g. `//`)
+    pub comment_marker: Option<String>,
+
+    /// Configured tag found in the line
+    pub tag_found: Option<String>,
+
+    /// Author found in parentheses after the tag
+    pub author_found: Option<String>,
+
+    /// Message following the tag
+    pub message_found: Option<String>,
+
+    /// Why the line did not match (only set when `matched` is false)
+    pub reason_no_match: Option<String>,
+}
+
+/// A ripgrep invocation that matches the same lines as a parser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RipgrepArgs {
+    /// Regex to pass to `rg -e`
+    pub pattern: String,
+
+    /// Recommended `rg` flags, e.g. `--ignore-case` for case-insensitive parsers
+    pub flags: Vec<String>,
+}
+
+impl RipgrepArgs {
+    /// Format a shell command line ready to copy-paste, e.g. `rg --column -e '...'`
+    pub fn command_line(&self) -> String {
+        let mut parts = vec!["rg".to_string()];
+        parts.extend(self.flags.iter().cloned());
+        parts.push("-e".to_string());
+        parts.push(format!("'{}'", self.pattern.replace('\'', r"'\''")));
+        parts.join(" ")
+    }
+}
+
+/// Times a sample file is parsed by [`TodoParser::benchmark`]
+pub const BENCHMARK_ITERATIONS: usize = 1000;
+
+/// Timing of a parser over a sample file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParserBenchmark {
+    /// Average time to parse one line, in nanoseconds
+    pub avg_ns_per_line: f64,
+
+    /// Lines in the sample file
+    pub total_lines: usize,
+
+    /// Matching lines found per second of parsing
+    pub matches_per_second: f64,
+}
+
 /// Parser for detecting TODO-style tags in source code
 #[derive(Debug, Clone)]
 pub struct TodoParser {
-    /// Compiled regex pattern for matching tags (None if no tags to search for)
-    pattern: Option<Regex>,
+    /// Compiled regex patterns, tried in order (empty if no tags to search for)
+    patterns: Vec<Regex>,
 
     /// Tags being searched for
     tags: Vec<String>,
@@ -57,6 +134,9 @@ pub struct TodoParser {
 
     /// The regex pattern string (for ripgrep integration)
     pattern_string: Option<String>,
+
+    /// Priority overrides consulted before `Priority::from_tag`
+    tag_priorities: HashMap<String, Priority>,
 }
 
 impl TodoParser {
@@ -73,14 +153,59 @@ impl TodoParser {
     /// If no custom pattern is provided, the default pattern is used.
     pub fn with_regex(tags: &[String], case_sensitive: bool, custom_regex: Option<&str>) -> Self {
         let (pattern, pattern_string) = Self::build_pattern(tags, case_sensitive, custom_regex);
+        tracing::trace!(
+            pattern = pattern_string.as_deref().unwrap_or(""),
+            "built tag pattern"
+        );
         Self {
-            pattern,
+            patterns: pattern.into_iter().collect(),
             tags: tags.to_vec(),
             case_sensitive,
             pattern_string,
+            tag_priorities: HashMap::new(),
         }
     }
 
+    /// Replace the pattern with several patterns, tried in order
+    ///
+    /// Each pattern uses the same `$TAGS` placeholder and capture groups as
+    /// [`with_regex`](Self::with_regex); the first one that matches a line wins.
+    /// An empty list keeps the current pattern.
+    pub fn with_multiple_patterns(mut self, patterns: &[String]) -> Result<Self, regex::Error> {
+        if patterns.is_empty() || self.tags.is_empty() {
+            return Ok(self);
+        }
+
+        let tags_alternation = Self::tags_alternation(&self.tags);
+        let pattern_strings: Vec<String> = patterns
+            .iter()
+            .map(|pattern| pattern.replace("$TAGS", &tags_alternation))
+            .collect();
+
+        self.patterns = pattern_strings
+            .iter()
+            .map(|pattern| Self::compile(pattern, self.case_sensitive))
+            .collect::<Result<_, _>>()?;
+        self.pattern_string = Some(
+            pattern_strings
+                .iter()
+                .map(|pattern| format!("(?:{})", pattern))
+                .collect::<Vec<_>>()
+                .join("|"),
+        );
+
+        Ok(self)
+    }
+
+    /// Set priority overrides for tags
+    ///
+    /// Tags found in the map (case-insensitive) use the given priority instead
+    /// of the built-in mapping.
+    pub fn with_tag_priorities(mut self, tag_priorities: HashMap<String, Priority>) -> Self {
+        self.tag_priorities = tag_priorities;
+        self
+    }
+
     /// Build the regex pattern for matching tags
     ///
     /// Returns both the compiled regex and the pattern string (for ripgrep integration).
@@ -93,23 +218,30 @@ impl TodoParser {
             return (None, None);
         }
 
-        // Escape special regex characters in tags
-        let escaped_tags: Vec<String> = tags.iter().map(|t| regex::escape(t)).collect();
-        let tags_alternation = escaped_tags.join("|");
-
         // Use custom regex or default
         let base_pattern = custom_regex.unwrap_or(DEFAULT_REGEX);
 
         // Replace $TAGS placeholder with the actual tags alternation
-        let pattern_string = base_pattern.replace("$TAGS", &tags_alternation);
+        let pattern_string = base_pattern.replace("$TAGS", &Self::tags_alternation(tags));
 
-        let regex = RegexBuilder::new(&pattern_string)
+        let regex =
+            Self::compile(&pattern_string, case_sensitive).expect("Failed to build regex pattern");
+
+        (Some(regex), Some(pattern_string))
+    }
+
+    /// Build the alternation of tags, escaping special regex characters
+    fn tags_alternation(tags: &[String]) -> String {
+        let escaped_tags: Vec<String> = tags.iter().map(|t| regex::escape(t)).collect();
+        escaped_tags.join("|")
+    }
+
+    /// Compile a pattern string with the parser's matching options
+    fn compile(pattern: &str, case_sensitive: bool) -> Result<Regex, regex::Error> {
+        RegexBuilder::new(pattern)
             .case_insensitive(!case_sensitive)
             .multi_line(true)
             .build()
-            .expect("Failed to build regex pattern");
-
-        (Some(regex), Some(pattern_string))
     }
 
     /// Get the regex pattern string for ripgrep integration
@@ -117,10 +249,53 @@ impl TodoParser {
         self.pattern_string.as_deref()
     }
 
+    /// Get the pattern with the `rg` flags needed to match like this parser
+    ///
+    /// ripgrep uses the same regex syntax, so the pattern is passed unchanged;
+    /// the flags reproduce case-insensitivity and report line and column like
+    /// `TodoItem`. Returns `None` when there are no tags to search for.
+    pub fn pattern_string_for_ripgrep(&self) -> Option<RipgrepArgs> {
+        let pattern = self.pattern_string.clone()?;
+
+        let mut flags = vec!["--line-number".to_string(), "--column".to_string()];
+        if !self.case_sensitive {
+            flags.push("--ignore-case".to_string());
+        }
+
+        Some(RipgrepArgs { pattern, flags })
+    }
+
     /// Parse a single line for TODO items
     pub fn parse_line(&self, line: &str, line_number: usize) -> Option<TodoItem> {
-        let pattern = self.pattern.as_ref()?;
+        self.patterns
+            .iter()
+            .find_map(|pattern| self.parse_line_with(pattern, line, line_number))
+    }
 
+    /// Parse a single line, returning the item only if `col` falls on its tag
+    ///
+    /// `col` is a 0-based byte offset into `line`, as used for LSP hover
+    /// requests. Lets a hover show the full item without parsing the whole file.
+    pub fn parse_line_at_column(
+        &self,
+        line: &str,
+        col: usize,
+        line_num: usize,
+    ) -> Option<TodoItem> {
+        let (pattern, tag_range) = self.patterns.iter().find_map(|pattern| {
+            let tag_match = pattern.captures(line)?.get(2)?;
+            Some((pattern, tag_match.start()..tag_match.end()))
+        })?;
+
+        if !tag_range.contains(&col) {
+            return None;
+        }
+
+        self.parse_line_with(pattern, line, line_num)
+    }
+
+    /// Parse a single line with one of the parser's patterns
+    fn parse_line_with(&self, pattern: &Regex, line: &str, line_number: usize) -> Option<TodoItem> {
         // Try to match the pattern
         if let Some(captures) = pattern.captures(line) {
             // Default pattern capture groups:
@@ -152,7 +327,7 @@ impl TodoParser {
                     .unwrap_or(tag)
             };
 
-            let priority = Priority::from_tag(&normalized_tag);
+            let priority = priority_for_tag(&self.tag_priorities, &normalized_tag);
 
             return Some(TodoItem {
                 tag: normalized_tag,
@@ -162,12 +337,80 @@ impl TodoParser {
                 line_content: Some(line.to_string()),
                 author,
                 priority,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
             });
         }
 
         None
     }
 
+    /// Explain why a line did or did not match
+    ///
+    /// Intended for debugging tag detection; use [`parse_line`](Self::parse_line)
+    /// for actual parsing.
+    pub fn explain(&self, line: &str) -> ParserExplanation {
+        if self.patterns.is_empty() {
+            return ParserExplanation {
+                reason_no_match: Some("no tags configured".to_string()),
+                ..Default::default()
+            };
+        }
+
+        if let Some(item) = self.parse_line(line, 1) {
+            let comment_marker = self
+                .patterns
+                .iter()
+                .find_map(|pattern| pattern.captures(line))
+                .and_then(|captures| captures.get(1))
+                .map(|m| m.as_str().trim().to_string());
+
+            return ParserExplanation {
+                matched: true,
+                comment_marker,
+                tag_found: Some(item.tag),
+                author_found: item.author,
+                message_found: Some(item.message),
+                reason_no_match: None,
+            };
+        }
+
+        let comment_marker = Regex::new(COMMENT_MARKERS)
+            .ok()
+            .and_then(|markers| markers.find(line))
+            .map(|m| m.as_str().trim().to_string());
+        let tag_found = self
+            .tags
+            .iter()
+            .find(|tag| {
+                if self.case_sensitive {
+                    line.contains(tag.as_str())
+                } else {
+                    line.to_lowercase().contains(&tag.to_lowercase())
+                }
+            })
+            .cloned();
+
+        let reason = if tag_found.is_none() {
+            "no configured tag found"
+        } else if comment_marker.is_none() {
+            "no comment marker found"
+        } else {
+            "tag must directly follow a comment marker and be followed by ':' or whitespace"
+        };
+
+        ParserExplanation {
+            matched: false,
+            comment_marker,
+            tag_found,
+            author_found: None,
+            message_found: None,
+            reason_no_match: Some(reason.to_string()),
+        }
+    }
+
     /// Parse content (multiple lines) for TODO items
     pub fn parse_content(&self, content: &str) -> Vec<TodoItem> {
         content
@@ -177,18 +420,182 @@ impl TodoParser {
             .collect()
     }
 
+    /// Parse content, keeping up to `before` and `after` surrounding lines for each item
+    pub fn parse_content_with_context(
+        &self,
+        content: &str,
+        before: usize,
+        after: usize,
+    ) -> Vec<TodoItem> {
+        self.parse_lines_with_context(content.lines(), before, after)
+    }
+
+    /// Parse a sequence of lines, keeping up to `before` and `after` surrounding lines
+    ///
+    /// Only `before` lines are buffered, so this also works on streamed input.
+    /// Context may contain other TODO lines, as with `grep -A`/`-B`.
+    pub fn parse_lines_with_context<I, S>(
+        &self,
+        lines: I,
+        before: usize,
+        after: usize,
+    ) -> Vec<TodoItem>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut items: Vec<TodoItem> = Vec::new();
+        let mut previous: VecDeque<String> = VecDeque::with_capacity(before);
+        let mut awaiting_after: Vec<usize> = Vec::new();
+
+        for (idx, line) in lines.into_iter().enumerate() {
+            let line = line.as_ref();
+
+            awaiting_after.retain(|&i| {
+                items[i].context_after.push(line.to_string());
+                items[i].context_after.len() < after
+            });
+
+            if let Some(mut item) = self.parse_line(line, idx + 1) {
+                item.context_before = previous.iter().cloned().collect();
+                if after > 0 {
+                    awaiting_after.push(items.len());
+                }
+                items.push(item);
+            }
+
+            if before > 0 {
+                if previous.len() == before {
+                    previous.pop_front();
+                }
+                previous.push_back(line.to_string());
+            }
+        }
+
+        items
+    }
+
+    /// Lazily parse content from a reader, one line at a time
+    ///
+    /// Unlike [`parse_content`](Self::parse_content), the input is never held in
+    /// memory as a whole. Lines are read with [`lossy_lines`], so invalid UTF-8
+    /// does not hide the items after it.
+    pub fn parse_content_streaming<R: BufRead>(&self, reader: R) -> impl Iterator<Item = TodoItem> {
+        lossy_lines(reader)
+            .enumerate()
+            .filter_map(|(idx, line)| self.parse_line(&line, idx + 1))
+    }
+
     /// Parse a file for TODO items
     pub fn parse_file(&self, path: &Path) -> std::io::Result<Vec<TodoItem>> {
         let content = std::fs::read_to_string(path)?;
         Ok(self.parse_content(&content))
     }
 
+    /// Time the parser over every line of a sample file
+    ///
+    /// The file is read once and parsed [`BENCHMARK_ITERATIONS`] times, so a
+    /// custom pattern can be compared with the default one. Slow patterns
+    /// (e.g. with heavy backtracking) show up as a high `avg_ns_per_line`.
+    pub fn benchmark(&self, sample_file: &Path) -> std::io::Result<ParserBenchmark> {
+        let content = std::fs::read_to_string(sample_file)?;
+        let lines: Vec<&str> = content.lines().collect();
+
+        let mut matches = 0;
+        let start = Instant::now();
+        for _ in 0..BENCHMARK_ITERATIONS {
+            for (idx, line) in lines.iter().enumerate() {
+                if std::hint::black_box(self.parse_line(line, idx + 1)).is_some() {
+                    matches += 1;
+                }
+            }
+        }
+        let elapsed = start.elapsed();
+
+        let parsed_lines = lines.len() * BENCHMARK_ITERATIONS;
+        Ok(ParserBenchmark {
+            avg_ns_per_line: if parsed_lines > 0 {
+                elapsed.as_nanos() as f64 / parsed_lines as f64
+            } else {
+                0.0
+            },
+            total_lines: lines.len(),
+            matches_per_second: if elapsed.is_zero() {
+                0.0
+            } else {
+                matches as f64 / elapsed.as_secs_f64()
+            },
+        })
+    }
+
     /// Get the tags being searched for
     pub fn tags(&self) -> &[String] {
         &self.tags
     }
 }
 
+/// Longest line kept by [`lossy_lines`]; the rest of a longer line is dropped
+pub const MAX_LINE_LEN: usize = 1024 * 1024;
+
+/// Iterate over the lines of a reader, replacing invalid UTF-8 with U+FFFD
+///
+/// Like [`BufRead::lines`], the `\n` or `\r\n` terminator is removed, but a line
+/// that is not valid UTF-8 does not end the iteration. Lines are cut at
+/// [`MAX_LINE_LEN`] bytes, so input without newlines is never buffered whole.
+/// Stops at the first I/O error.
+pub fn lossy_lines<R: BufRead>(mut reader: R) -> impl Iterator<Item = String> {
+    let mut buf = Vec::new();
+    std::iter::from_fn(move || {
+        buf.clear();
+        let read = reader
+            .by_ref()
+            .take(MAX_LINE_LEN as u64)
+            .read_until(b'\n', &mut buf)
+            .and_then(|read| {
+                if read == MAX_LINE_LEN && !buf.ends_with(b"\n") {
+                    skip_line(&mut reader)?;
+                }
+                Ok(read)
+            });
+        match read {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.ends_with(b"\n") {
+                    buf.pop();
+                    if buf.ends_with(b"\r") {
+                        buf.pop();
+                    }
+                }
+                Some(String::from_utf8_lossy(&buf).into_owned())
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "stopped reading input");
+                None
+            }
+        }
+    })
+}
+
+/// Discard input up to and including the next `\n`
+fn skip_line<R: BufRead>(reader: &mut R) -> std::io::Result<()> {
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            return Ok(());
+        }
+        match available.iter().position(|&byte| byte == b'\n') {
+            Some(end) => {
+                reader.consume(end + 1);
+                return Ok(());
+            }
+            None => {
+                let len = available.len();
+                reader.consume(len);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +634,53 @@ mod tests {
         assert_eq!(item.message, "Implement this");
     }
 
+    #[test]
+    fn test_parse_line_at_column() {
+        let parser = TodoParser::new(&default_tags(), false);
+        let line = "let x = 1; // FIXME(jane): Overflow";
+
+        // FIXME spans bytes 14..19
+        let item = parser.parse_line_at_column(line, 14, 7).unwrap();
+        assert_eq!(item.tag, "FIXME");
+        assert_eq!(item.line, 7);
+        assert_eq!(item.author, Some("jane".to_string()));
+        assert!(parser.parse_line_at_column(line, 18, 7).is_some());
+
+        assert!(parser.parse_line_at_column(line, 13, 7).is_none());
+        assert!(parser.parse_line_at_column(line, 19, 7).is_none());
+        assert!(parser.parse_line_at_column("let x = 1;", 0, 7).is_none());
+    }
+
+    #[test]
+    fn test_pattern_string_for_ripgrep() {
+        let parser = TodoParser::new(&["TODO".to_string()], false);
+        let args = parser.pattern_string_for_ripgrep().unwrap();
+        assert_eq!(args.pattern, parser.pattern_string().unwrap());
+        assert_eq!(
+            args.flags,
+            vec!["--line-number", "--column", "--ignore-case"]
+        );
+
+        let parser = TodoParser::new(&["TODO".to_string()], true);
+        let args = parser.pattern_string_for_ripgrep().unwrap();
+        assert!(!args.flags.contains(&"--ignore-case".to_string()));
+
+        assert!(
+            TodoParser::new(&[], false)
+                .pattern_string_for_ripgrep()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_ripgrep_command_line() {
+        let args = RipgrepArgs {
+            pattern: "(')\\s*(TODO)".to_string(),
+            flags: vec!["--column".to_string()],
+        };
+        assert_eq!(args.command_line(), r"rg --column -e '('\'')\s*(TODO)'");
+    }
+
     #[test]
     fn test_parse_hash_comment() {
         let parser = TodoParser::new(&default_tags(), false);
@@ -326,6 +780,20 @@ fn main() {}
         assert_eq!(priority_to_color(Priority::Low), Color::Green);
     }
 
+    #[test]
+    fn test_parse_with_tag_priorities() {
+        let tags = vec!["SECURITY".to_string(), "TODO".to_string()];
+        let mut priorities = HashMap::new();
+        priorities.insert("security".to_string(), Priority::Critical);
+        let parser = TodoParser::new(&tags, false).with_tag_priorities(priorities);
+
+        let item = parser.parse_line("// SECURITY: validate input", 1).unwrap();
+        assert_eq!(item.priority, Priority::Critical);
+
+        let item = parser.parse_line("// TODO: fallback mapping", 2).unwrap();
+        assert_eq!(item.priority, Priority::Medium);
+    }
+
     #[test]
     fn test_priority_from_unknown_tag() {
         // Unknown tags should default to Medium priority
@@ -348,6 +816,28 @@ fn main() {}
         assert_eq!(Priority::from_tag("IDEA"), Priority::Low);
     }
 
+    #[test]
+    fn test_benchmark() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.rs");
+        std::fs::write(&file_path, "// TODO: one\nfn main() {}\n// FIXME: two\n").unwrap();
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let benchmark = parser.benchmark(&file_path).unwrap();
+
+        assert_eq!(benchmark.total_lines, 3);
+        assert!(benchmark.avg_ns_per_line > 0.0);
+        assert!(benchmark.matches_per_second > 0.0);
+
+        assert!(
+            parser
+                .benchmark(&temp_dir.path().join("missing.rs"))
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_parse_file() {
         use tempfile::TempDir;
@@ -374,6 +864,171 @@ fn main() {
         assert_eq!(items[1].tag, "FIXME");
     }
 
+    #[test]
+    fn test_parse_content_with_context() {
+        let parser = TodoParser::new(&default_tags(), false);
+        let content = "fn a() {}\nfn b() {}\n// TODO: first\nlet x = 1;\n// FIXME: second\n";
+
+        let items = parser.parse_content_with_context(content, 1, 2);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].context_before, vec!["fn b() {}"]);
+        assert_eq!(
+            items[0].context_after,
+            vec!["let x = 1;", "// FIXME: second"]
+        );
+        assert_eq!(items[1].context_before, vec!["let x = 1;"]);
+        assert!(items[1].context_after.is_empty());
+
+        let items = parser.parse_content_with_context(content, 0, 0);
+        assert!(items[0].context_before.is_empty());
+        assert!(items[0].context_after.is_empty());
+    }
+
+    #[test]
+    fn test_parse_content_streaming() {
+        let parser = TodoParser::new(&default_tags(), false);
+        let content = "fn main() {}\n// TODO: first\r\n// FIXME: second\n";
+
+        let streamed: Vec<TodoItem> = parser
+            .parse_content_streaming(std::io::Cursor::new(content.as_bytes().to_vec()))
+            .collect();
+
+        assert_eq!(streamed, parser.parse_content(content));
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(streamed[0].line, 2);
+        assert_eq!(streamed[1].message, "second");
+    }
+
+    #[test]
+    fn test_parse_content_streaming_invalid_utf8() {
+        let parser = TodoParser::new(&default_tags(), false);
+        let mut content = b"// TODO: before\n".to_vec();
+        content.extend_from_slice(&[0xff, 0xfe, b'\n']);
+        content.extend_from_slice(b"// TODO: after\n");
+
+        let items: Vec<TodoItem> = parser
+            .parse_content_streaming(std::io::Cursor::new(content))
+            .collect();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].message, "before");
+        assert_eq!(items[1].message, "after");
+        assert_eq!(items[1].line, 3);
+    }
+
+    #[test]
+    fn test_lossy_lines() {
+        let lines: Vec<String> =
+            lossy_lines(std::io::Cursor::new(b"a\r\nb\xff\n\nc".to_vec())).collect();
+        assert_eq!(lines, vec!["a", "b\u{fffd}", "", "c"]);
+
+        let mut long = vec![b'x'; MAX_LINE_LEN + 10];
+        long.extend_from_slice(b"\nnext");
+        let lines: Vec<String> = lossy_lines(std::io::Cursor::new(long)).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), MAX_LINE_LEN);
+        assert_eq!(lines[1], "next");
+    }
+
+    #[test]
+    fn test_comment_markers_match_default_regex() {
+        assert!(DEFAULT_REGEX.starts_with(COMMENT_MARKERS));
+    }
+
+    #[test]
+    fn test_explain_match() {
+        let parser = TodoParser::new(&default_tags(), false);
+        let explanation = parser.explain("    // fixme(alice): fix this");
+
+        assert_eq!(
+            explanation,
+            ParserExplanation {
+                matched: true,
+                comment_marker: Some("//".to_string()),
+                tag_found: Some("FIXME".to_string()),
+                author_found: Some("alice".to_string()),
+                message_found: Some("fix this".to_string()),
+                reason_no_match: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_explain_no_match_reasons() {
+        let parser = TodoParser::new(&default_tags(), false);
+
+        let explanation = parser.explain("// nothing to see here");
+        assert!(!explanation.matched);
+        assert_eq!(explanation.comment_marker.as_deref(), Some("//"));
+        assert_eq!(
+            explanation.reason_no_match.as_deref(),
+            Some("no configured tag found")
+        );
+
+        let explanation = parser.explain("let value = TODO");
+        assert_eq!(explanation.tag_found.as_deref(), Some("TODO"));
+        assert_eq!(
+            explanation.reason_no_match.as_deref(),
+            Some("no comment marker found")
+        );
+
+        let explanation = parser.explain("// see the TODO list");
+        assert_eq!(explanation.tag_found.as_deref(), Some("TODO"));
+        assert!(
+            explanation
+                .reason_no_match
+                .unwrap()
+                .starts_with("tag must directly follow")
+        );
+    }
+
+    #[test]
+    fn test_explain_no_tags() {
+        let parser = TodoParser::new(&[], false);
+        let explanation = parser.explain("// TODO: something");
+
+        assert!(!explanation.matched);
+        assert_eq!(
+            explanation.reason_no_match.as_deref(),
+            Some("no tags configured")
+        );
+    }
+
+    #[test]
+    fn test_with_multiple_patterns() {
+        let parser = TodoParser::new(&default_tags(), false)
+            .with_multiple_patterns(&[
+                r"(//)\s*($TAGS)(?:\(([^)]+)\))?:\s*(.*)".to_string(),
+                r"(<!--)\s*($TAGS)(?:\(([^)]+)\))?:\s*(.*?)\s*-->".to_string(),
+            ])
+            .unwrap();
+
+        let item = parser.parse_line("// TODO(bob): code", 1).unwrap();
+        assert_eq!(item.tag, "TODO");
+        assert_eq!(item.author, Some("bob".to_string()));
+        assert_eq!(item.message, "code");
+
+        let item = parser.parse_line("<!-- FIXME: markup -->", 2).unwrap();
+        assert_eq!(item.tag, "FIXME");
+        assert_eq!(item.message, "markup");
+
+        // The default pattern is no longer used
+        assert!(parser.parse_line("# TODO: hash comment", 3).is_none());
+        assert!(parser.pattern_string().unwrap().starts_with("(?:(//)"));
+    }
+
+    #[test]
+    fn test_with_multiple_patterns_empty_and_invalid() {
+        let parser = TodoParser::new(&default_tags(), false)
+            .with_multiple_patterns(&[])
+            .unwrap();
+        assert!(parser.parse_line("# TODO: hash comment", 1).is_some());
+
+        let result =
+            TodoParser::new(&default_tags(), false).with_multiple_patterns(&["($TAGS".to_string()]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_file_nonexistent() {
         let parser = TodoParser::new(&default_tags(), false);
@@ -410,6 +1065,10 @@ fn main() {
             line_content: Some("// TODO: Test".to_string()),
             author: None,
             priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
         };
 
         let item2 = TodoItem {
@@ -420,6 +1079,10 @@ fn main() {
             line_content: Some("// TODO: Test".to_string()),
             author: None,
             priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
         };
 
         assert_eq!(item1, item2);
@@ -1,13 +1,16 @@
+use crate::cli::SortOrder;
+use crate::lsp;
 use crate::parser::priority_to_color;
-use colored::Colorize;
-use serde::Serialize;
-use std::collections::HashMap;
+use crate::tui;
+use colored::{Color, Colorize};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use todo_tree_core::{Priority, ScanResult, TodoItem};
 
 /// Output format for printing results
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum OutputFormat {
     /// Tree view grouped by file
     Tree,
@@ -15,6 +18,272 @@ pub enum OutputFormat {
     Flat,
     /// JSON output
     Json,
+    /// Markdown with a heading per file and a bullet per item, e.g. for wikis
+    Markdown,
+    /// One abbreviated line per item
+    Compact,
+    /// Synthetic unified diff for the `delta` diff renderer
+    Delta,
+    /// Aligned table with one row per item
+    Table,
+    /// Emacs Org-mode outline with one heading per file and item
+    #[value(name = "org")]
+    OrgMode,
+    /// Test Anything Protocol stream with one test point per item
+    Tap,
+    /// Checkstyle XML report for Java build tools (Maven, Gradle, IntelliJ)
+    Checkstyle,
+    /// JetBrains TeamCity service messages
+    #[value(name = "teamcity")]
+    TeamCity,
+    /// SonarQube Generic Issue Import JSON
+    #[value(name = "sonarqube")]
+    SonarQube,
+    /// Universal Ctags tags file, sorted by tag name
+    Ctags,
+    /// Emacs TAGS file (etags format)
+    CtagsEtags,
+    /// JSON array of LSP `PublishDiagnosticsParams`, one per file
+    LspDiagnostics,
+    /// Prometheus text exposition format (e.g. for the node_exporter textfile collector)
+    Prometheus,
+    /// OpenMetrics text exposition format, with the scan time and an `# EOF` marker
+    OpenMetrics,
+    /// xUnit.net v2 XML report with one test collection per file
+    #[value(name = "xunit")]
+    XUnit,
+    /// Graphviz DOT graph of the issues (`#123`) that items reference
+    Dot,
+    /// Confluence / Bitbucket wiki markup with a table per file
+    Atlassian,
+    /// Comma-separated values with a header row, for spreadsheets and databases
+    CsvWithHeader,
+    /// Tab-separated values with a header row
+    TsvWithHeader,
+}
+
+/// Layout of the JSON output, so consumers can pin the fields they rely on
+///
+/// `1.0` is the original layout: `files` (path, tag, message, line, column,
+/// line_content, author, priority) and `summary` (counts only). `1.1` adds
+/// `schema_version`, `title`, `paginated`, `summary.truncated`,
+/// `summary.timed_out` and the item
+/// fields `context_before`, `context_after`, `created_at` and `file_extension`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum JsonSchemaVersion {
+    #[value(name = "1.0")]
+    V1_0,
+    #[default]
+    #[value(name = "1.1")]
+    V1_1,
+}
+
+impl JsonSchemaVersion {
+    /// Version string, as written to the `schema_version` field
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JsonSchemaVersion::V1_0 => "1.0",
+            JsonSchemaVersion::V1_1 => "1.1",
+        }
+    }
+
+    /// Reduce JSON scan output to this version's layout
+    ///
+    /// For `1.0` every newer field is dropped; later versions record
+    /// themselves in `schema_version`.
+    pub fn apply(self, json: &mut serde_json::Value) {
+        match self {
+            JsonSchemaVersion::V1_0 => {
+                retain_keys(json, &["files", "summary"]);
+                retain_keys(
+                    &mut json["summary"],
+                    &[
+                        "total_count",
+                        "files_with_todos",
+                        "files_scanned",
+                        "tag_counts",
+                    ],
+                );
+                for file in json["files"].as_array_mut().into_iter().flatten() {
+                    retain_keys(file, &["path", "items"]);
+                    for item in file["items"].as_array_mut().into_iter().flatten() {
+                        retain_keys(
+                            item,
+                            &[
+                                "tag",
+                                "message",
+                                "line",
+                                "column",
+                                "line_content",
+                                "author",
+                                "priority",
+                            ],
+                        );
+                    }
+                }
+            }
+            JsonSchemaVersion::V1_1 => {
+                json["schema_version"] = serde_json::Value::from(self.as_str());
+            }
+        }
+    }
+}
+
+/// Latest-schema JSON output written straight from a [`ScanResult`]
+#[derive(Serialize)]
+struct IncrementalJsonOutput<T: Serialize> {
+    #[serde(flatten)]
+    result: T,
+    schema_version: &'static str,
+}
+
+/// Remove every key of a JSON object except `keys`
+fn retain_keys(json: &mut serde_json::Value, keys: &[&str]) {
+    if let Some(object) = json.as_object_mut() {
+        object.retain(|key, _| keys.contains(&key.as_str()));
+    }
+}
+
+/// The slice of the results being printed, set by `--first`, `--last` and `--offset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pagination {
+    /// Number of items skipped from the start
+    pub offset: usize,
+    /// Number of items shown
+    pub shown: usize,
+    /// Number of items before pagination
+    pub total: usize,
+    /// Whether the items were taken from the end (`--last`)
+    pub from_end: bool,
+}
+
+impl Pagination {
+    /// Describe the slice, e.g. `Showing first 10 of 250 total`
+    pub fn describe(&self) -> String {
+        if self.from_end {
+            format!("Showing last {} of {} total", self.shown, self.total)
+        } else if self.offset == 0 {
+            format!("Showing first {} of {} total", self.shown, self.total)
+        } else if self.shown == 0 {
+            format!("Showing 0 of {} total", self.total)
+        } else {
+            format!(
+                "Showing items {}-{} of {} total",
+                self.offset + 1,
+                self.offset + self.shown,
+                self.total
+            )
+        }
+    }
+}
+
+/// How colors are assigned in the output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Color tags by priority (file paths are bold)
+    #[default]
+    ByTag,
+    /// Additionally give each file path its own color from a fixed palette
+    ByFile,
+}
+
+/// Named palette for tag colors, from Critical to Low priority
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorScheme {
+    /// Red, yellow, cyan and green terminal colors
+    #[default]
+    Default,
+    /// Solarized accent colors
+    Solarized,
+    /// Nord aurora and frost colors
+    Nord,
+    /// Gruvbox bright colors
+    Gruvbox,
+    /// Bright terminal colors that stand out on dark and light backgrounds
+    HighContrast,
+    /// Okabe-Ito colors, distinguishable with the common color vision deficiencies
+    Colorblind,
+}
+
+impl ColorScheme {
+    /// Tag colors for Critical, High, Medium and Low priority
+    pub fn tag_colors(self) -> [Color; 4] {
+        let rgb = |r, g, b| Color::TrueColor { r, g, b };
+        match self {
+            ColorScheme::Default => [
+                Priority::Critical,
+                Priority::High,
+                Priority::Medium,
+                Priority::Low,
+            ]
+            .map(priority_to_color),
+            ColorScheme::Solarized => [
+                rgb(0xdc, 0x32, 0x2f),
+                rgb(0xb5, 0x89, 0x00),
+                rgb(0x2a, 0xa1, 0x98),
+                rgb(0x85, 0x99, 0x00),
+            ],
+            ColorScheme::Nord => [
+                rgb(0xbf, 0x61, 0x6a),
+                rgb(0xeb, 0xcb, 0x8b),
+                rgb(0x88, 0xc0, 0xd0),
+                rgb(0xa3, 0xbe, 0x8c),
+            ],
+            ColorScheme::Gruvbox => [
+                rgb(0xfb, 0x49, 0x34),
+                rgb(0xfa, 0xbd, 0x2f),
+                rgb(0x83, 0xa5, 0x98),
+                rgb(0xb8, 0xbb, 0x26),
+            ],
+            ColorScheme::HighContrast => [
+                Color::BrightRed,
+                Color::BrightMagenta,
+                Color::BrightBlue,
+                Color::BrightGreen,
+            ],
+            ColorScheme::Colorblind => [
+                rgb(0xd5, 0x5e, 0x00),
+                rgb(0xf0, 0xe4, 0x42),
+                rgb(0x56, 0xb4, 0xe9),
+                rgb(0x00, 0x9e, 0x73),
+            ],
+        }
+    }
+
+    /// Tag color for a priority level
+    pub fn color_for(self, priority: Priority) -> Color {
+        let [critical, high, medium, low] = self.tag_colors();
+        match priority {
+            Priority::Critical => critical,
+            Priority::High => high,
+            Priority::Medium => medium,
+            Priority::Low => low,
+        }
+    }
+}
+
+/// Palette cycled through when coloring file paths
+const FILE_PALETTE: [Color; 10] = [
+    Color::Blue,
+    Color::Magenta,
+    Color::Green,
+    Color::Yellow,
+    Color::Cyan,
+    Color::Red,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightGreen,
+    Color::BrightYellow,
+];
+
+/// Pick a palette color for a path, stable across runs
+pub fn file_color(path: &str) -> Color {
+    // FNV-1a keeps the mapping deterministic regardless of the std hasher
+    let hash = path.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    FILE_PALETTE[(hash % FILE_PALETTE.len() as u64) as usize]
 }
 
 /// Options for printing
@@ -35,6 +304,9 @@ pub struct PrintOptions {
     /// Whether to show clickable links (OSC 8)
     pub clickable_links: bool,
 
+    /// Emit clickable links without checking whether the terminal supports them
+    pub force_hyperlinks: bool,
+
     /// Base path for relative path display
     pub base_path: Option<PathBuf>,
 
@@ -43,6 +315,66 @@ pub struct PrintOptions {
 
     /// Group by tag instead of file
     pub group_by_tag: bool,
+
+    /// Whether to group by author instead of by file
+    pub group_by_author: bool,
+
+    /// Order of the items across files in flat, compact and table output
+    pub sort: SortOrder,
+
+    /// Reverse the file order, and the item order in flat, compact and table output
+    pub sort_reverse: bool,
+
+    /// Maximum message length in compact mode before truncation
+    pub compact_message_max: usize,
+
+    /// How colors are assigned
+    pub color_mode: ColorMode,
+
+    /// Palette used for tag colors
+    pub color_scheme: ColorScheme,
+
+    /// Show per-tag counts in tree file headers instead of the total
+    pub inline_counts: bool,
+
+    /// Title printed as the first line (a `title` field in JSON)
+    pub title: Option<String>,
+
+    /// Omit the file (or tag) header lines in tree mode
+    pub suppress_header: bool,
+
+    /// Write JSON on a single line instead of pretty-printing it
+    pub json_compact: bool,
+
+    /// Layout of the JSON output
+    pub json_schema_version: JsonSchemaVersion,
+
+    /// Set when only a slice of the results is printed (`"paginated": true` in JSON)
+    pub pagination: Option<Pagination>,
+
+    /// Items with a priority above this are `not ok` in TAP output
+    pub fail_above: Option<Priority>,
+
+    /// Prepended to every displayed message (not ctags, etags or diff output)
+    pub message_prefix: Option<String>,
+
+    /// Lines of context shown before each item in tree and flat output
+    pub before_context: usize,
+
+    /// Lines of context shown after each item in tree and flat output
+    pub after_context: usize,
+
+    /// Show only the last N path components in tree, flat and table output
+    pub truncate_paths: Option<usize>,
+
+    /// Highlight the tag wherever it appears in the message (tree and flat output)
+    pub highlight_matches: bool,
+
+    /// Authors were stripped, so CSV and TSV output leave out the author column
+    pub strip_author: bool,
+
+    /// Added to JSON output as a `statistics` object (`scan --stats-after-scan`)
+    pub statistics: Option<serde_json::Value>,
 }
 
 impl Default for PrintOptions {
@@ -53,9 +385,30 @@ impl Default for PrintOptions {
             show_line_numbers: true,
             full_paths: false,
             clickable_links: true,
+            force_hyperlinks: false,
             base_path: None,
             show_summary: true,
             group_by_tag: false,
+            group_by_author: false,
+            sort: SortOrder::File,
+            sort_reverse: false,
+            compact_message_max: 40,
+            color_mode: ColorMode::ByTag,
+            color_scheme: ColorScheme::Default,
+            inline_counts: false,
+            title: None,
+            suppress_header: false,
+            json_compact: false,
+            json_schema_version: JsonSchemaVersion::default(),
+            pagination: None,
+            fail_above: None,
+            message_prefix: None,
+            before_context: 0,
+            after_context: 0,
+            truncate_paths: None,
+            highlight_matches: false,
+            strip_author: false,
+            statistics: None,
         }
     }
 }
@@ -83,17 +436,57 @@ impl Printer {
         self.print_to(&mut handle, result)
     }
 
+    /// Browse scan results in a fullscreen terminal UI until the user quits
+    ///
+    /// Files are listed in path order, with the paths shown as in tree output.
+    pub fn print_interactive(&self, result: &ScanResult) -> io::Result<()> {
+        let entries = result
+            .sorted_files()
+            .into_iter()
+            .flat_map(|(path, items)| {
+                items.iter().map(move |item| tui::Entry {
+                    path: path.clone(),
+                    display_path: self.display_path(path),
+                    item: item.clone(),
+                    resolved: false,
+                })
+            })
+            .collect();
+        tui::run(tui::Browser::new(entries))
+    }
+
     /// Print scan results to a writer
     pub fn print_to<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
         match self.options.format {
             OutputFormat::Tree => self.print_tree(writer, result),
             OutputFormat::Flat => self.print_flat(writer, result),
             OutputFormat::Json => self.print_json(writer, result),
+            OutputFormat::Markdown => self.print_markdown(writer, result),
+            OutputFormat::Compact => self.print_compact(writer, result),
+            OutputFormat::Delta => self.print_delta(writer, result),
+            OutputFormat::Table => self.print_table(writer, result),
+            OutputFormat::OrgMode => self.print_org_mode(writer, result),
+            OutputFormat::Tap => self.print_tap(writer, result),
+            OutputFormat::Checkstyle => self.print_checkstyle(writer, result),
+            OutputFormat::TeamCity => self.print_teamcity(writer, result),
+            OutputFormat::SonarQube => self.print_sonarqube(writer, result),
+            OutputFormat::Ctags => self.print_ctags(writer, result),
+            OutputFormat::CtagsEtags => self.print_etags(writer, result),
+            OutputFormat::LspDiagnostics => self.print_lsp_diagnostics(writer, result),
+            OutputFormat::Prometheus => self.print_prometheus(writer, result),
+            OutputFormat::OpenMetrics => self.print_open_metrics(writer, result),
+            OutputFormat::CsvWithHeader => self.print_csv(writer, result),
+            OutputFormat::TsvWithHeader => self.print_tsv(writer, result),
+            OutputFormat::XUnit => self.print_xunit(writer, result),
+            OutputFormat::Dot => write!(writer, "{}", result.to_dot_graph()),
+            OutputFormat::Atlassian => self.print_atlassian(writer, result),
         }
     }
 
     /// Print results in tree format
     fn print_tree<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        self.print_title(writer)?;
+
         if result.is_empty() {
             writeln!(writer, "{}", "No TODO items found.".dimmed())?;
             return Ok(());
@@ -101,6 +494,8 @@ impl Printer {
 
         if self.options.group_by_tag {
             self.print_tree_by_tag(writer, result)?;
+        } else if self.options.group_by_author {
+            self.print_tree_by_author(writer, result)?;
         } else {
             self.print_tree_by_file(writer, result)?;
         }
@@ -115,21 +510,34 @@ impl Printer {
 
     /// Print tree grouped by file
     fn print_tree_by_file<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
-        let sorted_files = result.sorted_files();
+        let sorted_files = self.ordered_files(result);
         let total_files = sorted_files.len();
 
         for (idx, (path, items)) in sorted_files.iter().enumerate() {
-            let is_last_file = idx == total_files - 1;
+            self.print_tree_file(writer, path, items, idx == total_files - 1)?;
+        }
 
-            // Print file header
-            self.print_file_header(writer, path, items.len(), is_last_file)?;
+        Ok(())
+    }
 
-            // Print items
-            let total_items = items.len();
-            for (item_idx, item) in items.iter().enumerate() {
-                let is_last_item = item_idx == total_items - 1;
-                self.print_tree_item(writer, item, is_last_file, is_last_item, path)?;
-            }
+    /// Print one file of the tree grouped by file, with its header and items
+    pub fn print_tree_file<W: Write>(
+        &self,
+        writer: &mut W,
+        path: &Path,
+        items: &[TodoItem],
+        is_last_file: bool,
+    ) -> io::Result<()> {
+        // Print file header
+        if !self.options.suppress_header {
+            self.print_file_header(writer, path, items, is_last_file)?;
+        }
+
+        // Print items
+        let total_items = items.len();
+        for (item_idx, item) in items.iter().enumerate() {
+            let is_last_item = item_idx == total_items - 1;
+            self.print_tree_item(writer, item, is_last_file, is_last_item, path)?;
         }
 
         Ok(())
@@ -164,8 +572,11 @@ impl Printer {
             } else {
                 "├──"
             };
-            let colored_tag = self.colorize_tag(tag);
-            writeln!(writer, "{} {} ({})", prefix, colored_tag, items.len())?;
+            if !self.options.suppress_header {
+                let priority = tag_priority(items.iter().map(|(_, item)| item), tag);
+                let colored_tag = self.colorize_tag(tag, priority);
+                writeln!(writer, "{} {} ({})", prefix, colored_tag, items.len())?;
+            }
 
             // Print items under this tag
             let total_items = items.len();
@@ -178,7 +589,7 @@ impl Printer {
                     "├──"
                 };
 
-                let display_path = self.format_path(path);
+                let display_path = self.display_path(path);
                 let link = self.make_clickable_link(path, item.line);
 
                 writeln!(
@@ -188,7 +599,75 @@ impl Printer {
                     item_prefix,
                     link.unwrap_or_else(|| display_path.to_string()),
                     item.line.to_string().cyan(),
-                    item.message.dimmed()
+                    self.display_message(&item.message).dimmed()
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print tree grouped by author
+    ///
+    /// Authors are listed alphabetically, followed by an `(unassigned)` group for
+    /// items without an author. Items keep the file order within each group.
+    fn print_tree_by_author<W: Write>(
+        &self,
+        writer: &mut W,
+        result: &ScanResult,
+    ) -> io::Result<()> {
+        let mut by_author: BTreeMap<Option<&str>, Vec<(&PathBuf, &TodoItem)>> = BTreeMap::new();
+        for (path, items) in result.sorted_files() {
+            for item in items {
+                by_author
+                    .entry(item.author.as_deref())
+                    .or_default()
+                    .push((path, item));
+            }
+        }
+
+        // `None` sorts first in a BTreeMap, but the unassigned group goes last
+        let mut groups: Vec<_> = by_author.into_iter().collect();
+        if groups.first().is_some_and(|(author, _)| author.is_none()) {
+            groups.rotate_left(1);
+        }
+
+        let total_groups = groups.len();
+        for (idx, (author, items)) in groups.iter().enumerate() {
+            let is_last_group = idx == total_groups - 1;
+
+            let prefix = if is_last_group {
+                "└──"
+            } else {
+                "├──"
+            };
+            if !self.options.suppress_header {
+                let name = author.unwrap_or("(unassigned)");
+                writeln!(writer, "{} {} ({})", prefix, name.bold(), items.len())?;
+            }
+
+            let total_items = items.len();
+            for (item_idx, (path, item)) in items.iter().enumerate() {
+                let is_last_item = item_idx == total_items - 1;
+                let tree_prefix = if is_last_group { "    " } else { "│   " };
+                let item_prefix = if is_last_item {
+                    "└──"
+                } else {
+                    "├──"
+                };
+
+                let display_path = self.display_path(path);
+                let link = self.make_clickable_link(path, item.line);
+
+                writeln!(
+                    writer,
+                    "{}{} {}:{} [{}] {}",
+                    tree_prefix,
+                    item_prefix,
+                    link.unwrap_or_else(|| display_path.to_string()),
+                    item.line.to_string().cyan(),
+                    self.colorize_tag(&item.tag, item.priority),
+                    self.display_message(&item.message).dimmed()
                 )?;
             }
         }
@@ -201,26 +680,40 @@ impl Printer {
         &self,
         writer: &mut W,
         path: &Path,
-        item_count: usize,
+        items: &[TodoItem],
         is_last: bool,
     ) -> io::Result<()> {
         let prefix = if is_last { "└──" } else { "├──" };
-        let display_path = self.format_path(path);
+        let display_path = self.display_path(path);
         let link = self.make_clickable_link(path, 1);
 
-        let path_str = link.unwrap_or_else(|| {
-            if self.options.colored {
-                display_path.bold().to_string()
-            } else {
-                display_path.to_string()
+        let path_str = link.unwrap_or_else(|| self.colorize_path(&display_path));
+
+        let count_display = if self.options.inline_counts {
+            let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+            for item in items {
+                *tag_counts.entry(item.tag.as_str()).or_insert(0) += 1;
             }
-        });
 
-        let count_str = format!("({})", item_count);
-        let count_display = if self.options.colored {
-            count_str.dimmed().to_string()
+            // Most frequent tags first, ties broken by name
+            let mut counts: Vec<_> = tag_counts.into_iter().collect();
+            counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+            let tokens: Vec<String> = counts
+                .iter()
+                .map(|(tag, count)| {
+                    let priority = tag_priority(items, tag);
+                    format!("{}:{}", self.colorize_tag(tag, priority), count)
+                })
+                .collect();
+            format!("({})", tokens.join(" "))
         } else {
-            count_str
+            let count_str = format!("({})", items.len());
+            if self.options.colored {
+                count_str.dimmed().to_string()
+            } else {
+                count_str
+            }
         };
 
         writeln!(writer, "{} {} {}", prefix, path_str, count_display)?;
@@ -244,14 +737,18 @@ impl Printer {
             "├──"
         };
 
-        let tag = self.colorize_tag(&item.tag);
+        let (before, after) = visible_context(item, &self.options);
+        let context_prefix = format!("{}│   ", tree_prefix);
+        self.print_context_lines(writer, &context_prefix, item.line - before.len(), before)?;
+
+        let tag = self.colorize_tag(&item.tag, item.priority);
         let line_num = if self.options.colored {
             format!("L{}", item.line).cyan().to_string()
         } else {
             format!("L{}", item.line)
         };
 
-        let message = item.message.clone();
+        let message = self.colorize_message(&self.display_message(&item.message), &item.tag);
 
         // Add clickable link to line number if supported
         let line_display = if self.options.clickable_links {
@@ -287,21 +784,48 @@ impl Printer {
             )?;
         }
 
+        let context_prefix = format!(
+            "{}{}",
+            tree_prefix,
+            if is_last_item { "    " } else { "│   " }
+        );
+        self.print_context_lines(writer, &context_prefix, item.line + 1, after)?;
+
+        Ok(())
+    }
+
+    /// Print context lines as `prefix<line>- <text>`, numbered from `first_line`
+    fn print_context_lines<W: Write>(
+        &self,
+        writer: &mut W,
+        prefix: &str,
+        first_line: usize,
+        lines: &[String],
+    ) -> io::Result<()> {
+        for (offset, text) in lines.iter().enumerate() {
+            let line = format!("{}- {}", first_line + offset, text);
+            if self.options.colored {
+                writeln!(writer, "{}{}", prefix, line.dimmed())?;
+            } else {
+                writeln!(writer, "{}{}", prefix, line)?;
+            }
+        }
         Ok(())
     }
 
     /// Print results in flat format
     fn print_flat<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        self.print_title(writer)?;
+
         if result.is_empty() {
             writeln!(writer, "{}", "No TODO items found.".dimmed())?;
             return Ok(());
         }
 
-        let mut all_items = result.all_items();
-        all_items.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.line.cmp(&b.1.line)));
+        let all_items = self.sorted_items(result);
 
         for (path, item) in all_items {
-            self.print_flat_item(writer, &path, &item)?;
+            self.print_flat_item(writer, path, item)?;
         }
 
         if self.options.show_summary {
@@ -312,6 +836,37 @@ impl Printer {
         Ok(())
     }
 
+    /// Files by path, or last first with [`PrintOptions::sort_reverse`]
+    fn ordered_files<'a>(&self, result: &'a ScanResult) -> Vec<(&'a PathBuf, &'a Vec<TodoItem>)> {
+        let mut files = result.sorted_files();
+        if self.options.sort_reverse {
+            files.reverse();
+        }
+        files
+    }
+
+    /// All items in the order of the one-line-per-item outputs
+    ///
+    /// Files come as in [`ordered_files`](Self::ordered_files) with their items as
+    /// sorted, then the items of all files are sorted by [`PrintOptions::sort`]
+    /// (descending with `sort_reverse`). The sort is stable, so ties keep that order.
+    fn sorted_items<'a>(&self, result: &'a ScanResult) -> Vec<(&'a PathBuf, &'a TodoItem)> {
+        let mut items: Vec<_> = self
+            .ordered_files(result)
+            .into_iter()
+            .flat_map(|(path, items)| items.iter().map(move |item| (path, item)))
+            .collect();
+        items.sort_by(|a, b| {
+            let order = self.options.sort.compare(a.1, b.1);
+            if self.options.sort_reverse {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        items
+    }
+
     /// Print a single item in flat format
     fn print_flat_item<W: Write>(
         &self,
@@ -319,342 +874,2697 @@ impl Printer {
         path: &Path,
         item: &TodoItem,
     ) -> io::Result<()> {
-        let display_path = self.format_path(path);
+        let (before, after) = visible_context(item, &self.options);
+        let prefix = format!("{}-", self.display_path(path));
+
+        self.print_context_lines(writer, &prefix, item.line - before.len(), before)?;
+        self.pprint_item(writer, item, path)?;
+        self.print_context_lines(writer, &prefix, item.line + 1, after)
+    }
+
+    /// Pretty-print a single item on one line (`path:line:col [TAG] message`)
+    ///
+    /// Uses the same format as the flat output and honors the color, link and
+    /// line number options, so callers can format items without a `ScanResult`.
+    pub fn pprint_item<W: Write>(
+        &self,
+        writer: &mut W,
+        item: &TodoItem,
+        path: &Path,
+    ) -> io::Result<()> {
+        let display_path = self.display_path(path);
         let link = self.make_clickable_link(path, item.line);
 
-        let path_str = link.unwrap_or_else(|| {
-            if self.options.colored {
-                display_path.bold().to_string()
-            } else {
-                display_path.to_string()
-            }
-        });
+        let path_str = link.unwrap_or_else(|| self.colorize_path(&display_path));
 
-        let line_col = format!(":{}:{}", item.line, item.column);
+        let line_col = if self.options.show_line_numbers {
+            format!(":{}:{}", item.line, item.column)
+        } else {
+            String::new()
+        };
         let line_col_display = if self.options.colored {
             line_col.cyan().to_string()
         } else {
             line_col
         };
 
-        let tag = self.colorize_tag(&item.tag);
+        let tag = self.colorize_tag(&item.tag, item.priority);
 
         writeln!(
             writer,
             "{}{} [{}] {}",
-            path_str, line_col_display, tag, item.message
+            path_str,
+            line_col_display,
+            tag,
+            self.colorize_message(&self.display_message(&item.message), &item.tag)
         )?;
 
         Ok(())
     }
 
-    /// Print results in JSON format
-    fn print_json<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
-        let json_result = result.to_json_format();
-        let json_str = serde_json::to_string_pretty(&json_result).map_err(io::Error::other)?;
+    /// Print results in compact format, one abbreviated line per item
+    pub fn print_compact<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        if result.is_empty() {
+            writeln!(writer, "{}", "No TODO items found.".dimmed())?;
+            return Ok(());
+        }
 
-        writeln!(writer, "{}", json_str)?;
+        let all_items = self.sorted_items(result);
+
+        for (path, item) in all_items {
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.format_path(path));
+            let file_name = if self.options.colored && self.options.color_mode == ColorMode::ByFile
+            {
+                file_name
+                    .color(file_color(&self.format_path(path)))
+                    .to_string()
+            } else {
+                file_name
+            };
+
+            writeln!(
+                writer,
+                "{}:{} {} {}",
+                file_name,
+                item.line,
+                self.colorize_tag(&item.tag, item.priority),
+                truncate_message(
+                    &self.display_message(&item.message),
+                    self.options.compact_message_max
+                )
+            )?;
+        }
+
+        if self.options.show_summary {
+            writeln!(writer)?;
+            self.print_summary(writer, result)?;
+        }
 
         Ok(())
     }
 
-    /// Print summary statistics
-    fn print_summary<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
-        let summary_line = format!(
-            "Found {} TODO items in {} files ({} files scanned)",
-            result.summary.total_count,
-            result.summary.files_with_todos,
-            result.summary.files_scanned
-        );
+    /// Print results as an aligned table
+    ///
+    /// Columns adapt to the terminal width and long cells wrap. Borders use
+    /// Unicode box-drawing characters when colors are enabled, plain ASCII otherwise.
+    fn print_table<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        use comfy_table::{ContentArrangement, Table, presets};
 
-        if self.options.colored {
-            writeln!(writer, "{}", summary_line.bold())?;
-        } else {
-            writeln!(writer, "{}", summary_line)?;
+        if result.is_empty() {
+            writeln!(writer, "{}", "No TODO items found.".dimmed())?;
+            return Ok(());
         }
 
-        // Print tag breakdown
-        if !result.summary.tag_counts.is_empty() {
-            let mut tags: Vec<_> = result.summary.tag_counts.iter().collect();
-            tags.sort_by(|a, b| b.1.cmp(a.1));
+        let mut table = Table::new();
+        table
+            .load_preset(if self.options.colored {
+                presets::UTF8_FULL
+            } else {
+                presets::ASCII_FULL
+            })
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(["File", "L", "Col", "Tag", "Priority", "Author", "Message"]);
 
-            let breakdown: Vec<String> = tags
-                .iter()
-                .map(|(tag, count)| {
-                    if self.options.colored {
-                        format!("{}: {}", self.colorize_tag(tag), count)
-                    } else {
-                        format!("{}: {}", tag, count)
-                    }
-                })
-                .collect();
+        let all_items = self.sorted_items(result);
 
-            writeln!(writer, "  {}", breakdown.join(", "))?;
+        for (path, item) in all_items {
+            table.add_row([
+                self.display_path(path),
+                item.line.to_string(),
+                item.column.to_string(),
+                item.tag.clone(),
+                item.priority.to_string(),
+                item.author.clone().unwrap_or_default(),
+                self.display_message(&item.message),
+            ]);
+        }
+
+        writeln!(writer, "{}", table)?;
+
+        if self.options.show_summary {
+            writeln!(writer)?;
+            self.print_summary(writer, result)?;
         }
 
         Ok(())
     }
 
-    /// Format a path for display
-    fn format_path(&self, path: &Path) -> String {
-        if self.options.full_paths {
-            path.display().to_string()
-        } else if let Some(base) = &self.options.base_path {
-            path.strip_prefix(base)
-                .map(|p| p.display().to_string())
-                .unwrap_or_else(|_| path.display().to_string())
-        } else {
-            path.display().to_string()
+    /// Print results as a synthetic unified diff
+    ///
+    /// Each file becomes a new-file diff whose added lines are the TODO lines,
+    /// so the output can be piped into `delta` for syntax-aware highlighting.
+    fn print_delta<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        for (path, items) in result.sorted_files() {
+            let display_path = self.format_path(path);
+
+            writeln!(writer, "diff --git a/{} b/{}", display_path, display_path)?;
+            writeln!(writer, "--- /dev/null")?;
+            writeln!(writer, "+++ b/{}", display_path)?;
+            writeln!(writer, "@@ -0,0 +1,{} @@", items.len())?;
+
+            for item in items {
+                let line = item
+                    .line_content
+                    .clone()
+                    .unwrap_or_else(|| format!("{}: {}", item.tag, item.message));
+                writeln!(writer, "+{}", line.trim_end())?;
+            }
         }
+
+        Ok(())
     }
 
-    /// Create a clickable hyperlink using OSC 8 escape sequence
-    fn make_clickable_link(&self, path: &Path, line: usize) -> Option<String> {
-        if !self.options.clickable_links {
-            return None;
+    /// Print results as an Org-mode outline
+    ///
+    /// Each file is a `* File:` heading and each item a `** TODO` heading with a
+    /// priority cookie (`[#A]` Critical to `[#D]` Low), the tag as an Org tag, the
+    /// author in a property drawer and a link to the location.
+    pub fn print_org_mode<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        if let Some(title) = &self.options.title {
+            writeln!(writer, "#+TITLE: {}", title)?;
+            writeln!(writer)?;
         }
 
-        // Check if terminal supports hyperlinks
-        if !supports_hyperlinks() {
-            return None;
-        }
-
-        let display_path = self.format_path(path);
-        let abs_path = path.canonicalize().ok()?;
-        let file_url = format!("file://{}:{}", abs_path.display(), line);
+        for (path, items) in result.sorted_files() {
+            let display_path = self.format_path(path);
+            writeln!(writer, "* File: {}", display_path)?;
 
-        // OSC 8 hyperlink format: \x1b]8;;URL\x1b\\TEXT\x1b]8;;\x1b\\
-        let link = format!(
-            "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
-            file_url,
-            if self.options.colored {
-                display_path.bold().to_string()
-            } else {
-                display_path
+            for item in items {
+                writeln!(
+                    writer,
+                    "** TODO [#{}] {} :{}:",
+                    org_priority(item.priority),
+                    self.display_message(&item.message),
+                    org_tag(&item.tag)
+                )?;
+                if let Some(author) = &item.author {
+                    writeln!(writer, ":PROPERTIES:")?;
+                    writeln!(writer, ":AUTHOR: {}", author)?;
+                    writeln!(writer, ":END:")?;
+                }
+                writeln!(
+                    writer,
+                    "[[file:{}::{}][{}:{}]]",
+                    display_path, item.line, display_path, item.line
+                )?;
             }
-        );
+        }
 
-        Some(link)
+        Ok(())
     }
 
-    /// Create a clickable link for a line number
-    fn make_line_link(&self, path: &Path, line: usize) -> Option<String> {
-        if !self.options.clickable_links || !supports_hyperlinks() {
-            return None;
+    /// Print results as a TAP (Test Anything Protocol) stream
+    ///
+    /// Every item is a test point, `ok N - <file>:<line> <tag>: <message>`. Items
+    /// with a priority above `fail_above` are reported as `not ok`, so a TAP
+    /// consumer such as `prove` fails on them.
+    pub fn print_tap<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        writeln!(writer, "TAP version 13")?;
+        writeln!(writer, "1..{}", result.summary.total_count)?;
+        if let Some(title) = &self.options.title {
+            writeln!(writer, "# {}", title)?;
         }
 
-        let abs_path = path.canonicalize().ok()?;
-        let file_url = format!("file://{}:{}", abs_path.display(), line);
-        let display = format!("L{}", line);
+        let mut number = 0;
+        for (path, items) in result.sorted_files() {
+            let display_path = self.format_path(path);
 
-        let link = format!(
-            "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
-            file_url,
-            if self.options.colored {
-                display.cyan().to_string()
-            } else {
-                display
+            for item in items {
+                number += 1;
+                let status = match self.options.fail_above {
+                    Some(threshold) if item.priority > threshold => "not ok",
+                    _ => "ok",
+                };
+                writeln!(
+                    writer,
+                    "{} {} - {}:{} {}: {}",
+                    status,
+                    number,
+                    tap_escape(&display_path),
+                    item.line,
+                    tap_escape(&item.tag),
+                    tap_escape(&self.display_message(&item.message))
+                )?;
             }
-        );
+        }
 
-        Some(link)
+        Ok(())
     }
 
-    /// Colorize a tag based on its priority
-    fn colorize_tag(&self, tag: &str) -> String {
-        if !self.options.colored {
-            return tag.to_string();
+    /// Print results as Confluence wiki markup
+    ///
+    /// Each file is an `h2.` heading followed by a table with one row per item;
+    /// the priority is colored with the `{color}` macro (see [`atlassian_color`]).
+    pub fn print_atlassian<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        if let Some(title) = &self.options.title {
+            writeln!(writer, "h1. {}", atlassian_escape(title))?;
+            writeln!(writer)?;
         }
 
-        let color = priority_to_color(Priority::from_tag(tag));
-        tag.color(color).bold().to_string()
-    }
-}
+        if result.is_empty() {
+            writeln!(writer, "No TODO items found.")?;
+            return Ok(());
+        }
 
-/// JSON output structure
-#[derive(Debug, Serialize)]
-pub struct JsonOutput {
-    /// All TODO items grouped by file
-    pub files: Vec<JsonFileEntry>,
+        for (index, (path, items)) in result.sorted_files().into_iter().enumerate() {
+            if index > 0 {
+                writeln!(writer)?;
+            }
+            writeln!(writer, "h2. {}", atlassian_escape(&self.format_path(path)))?;
+            writeln!(writer, "|| Tag || Line || Priority || Message ||")?;
+            for item in items {
+                writeln!(
+                    writer,
+                    "| {} | {} | {{color:{}}}{}{{color}} | {} |",
+                    atlassian_escape(&item.tag),
+                    item.line,
+                    atlassian_color(item.priority),
+                    item.priority.display_name().to_uppercase(),
+                    atlassian_escape(&self.display_message(&item.message))
+                )?;
+            }
+        }
 
-    /// Summary statistics
-    pub summary: JsonSummary,
-}
+        if self.options.show_summary {
+            writeln!(writer)?;
+            writeln!(
+                writer,
+                "_Found {} TODO items in {} files ({} files scanned)_",
+                result.summary.total_count,
+                result.summary.files_with_todos,
+                result.summary.files_scanned
+            )?;
+        }
 
-/// JSON entry for a single file
-#[derive(Debug, Serialize)]
-pub struct JsonFileEntry {
-    /// File path
-    pub path: String,
+        Ok(())
+    }
 
-    /// TODO items in this file
-    pub items: Vec<JsonTodoItem>,
-}
+    /// Print results as Markdown
+    ///
+    /// Each file is a `##` heading and each item a bullet such as
+    /// `- **[TODO]** message _(L10)_`, followed by a `### Summary` section.
+    /// Terminal (OSC 8) links are never used; with `force_hyperlinks` the file
+    /// headings and line numbers become relative Markdown links instead.
+    pub fn print_markdown<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        if let Some(title) = &self.options.title {
+            writeln!(writer, "# {}", markdown_escape(title))?;
+            writeln!(writer)?;
+        }
 
-/// JSON representation of a TODO item
-#[derive(Debug, Serialize)]
-pub struct JsonTodoItem {
-    /// The tag (TODO, FIXME, etc.)
-    pub tag: String,
+        if result.is_empty() {
+            writeln!(writer, "No TODO items found.")?;
+            return Ok(());
+        }
 
-    /// The message content
-    pub message: String,
+        let links = self.options.force_hyperlinks;
+        for (index, (path, items)) in result.sorted_files().into_iter().enumerate() {
+            if index > 0 {
+                writeln!(writer)?;
+            }
 
-    /// Line number (1-indexed)
-    pub line: usize,
+            let display_path = self.format_path(path);
+            let target = display_path.replace('\\', "/").replace(' ', "%20");
+            if links {
+                writeln!(
+                    writer,
+                    "## [{}]({})",
+                    markdown_escape(&display_path),
+                    target
+                )?;
+            } else {
+                writeln!(writer, "## {}", markdown_escape(&display_path))?;
+            }
+            writeln!(writer)?;
 
-    /// Column number (1-indexed)
-    pub column: usize,
+            for item in items {
+                let line = if links {
+                    format!("[L{}]({}#L{})", item.line, target, item.line)
+                } else {
+                    format!("L{}", item.line)
+                };
+                writeln!(
+                    writer,
+                    "- **[{}]** {} _({})_",
+                    markdown_escape(&item.tag),
+                    markdown_escape(&self.display_message(&item.message)),
+                    line
+                )?;
+            }
+        }
 
-    /// Optional author
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub author: Option<String>,
+        if self.options.show_summary {
+            writeln!(writer)?;
+            writeln!(writer, "### Summary")?;
+            writeln!(writer)?;
+            writeln!(
+                writer,
+                "Found {} TODO items in {} files ({} files scanned)",
+                result.summary.total_count,
+                result.summary.files_with_todos,
+                result.summary.files_scanned
+            )?;
 
-    /// Priority level
-    pub priority: String,
-}
+            if !result.summary.tag_counts.is_empty() {
+                let mut tags: Vec<_> = result.summary.tag_counts.iter().collect();
+                tags.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+                writeln!(writer)?;
+                for (tag, count) in tags {
+                    writeln!(writer, "- **{}:** {}", markdown_escape(tag), count)?;
+                }
+            }
+        }
 
-/// JSON summary statistics
-#[derive(Debug, Serialize)]
-pub struct JsonSummary {
-    /// Total number of TODO items
-    pub total_count: usize,
+        Ok(())
+    }
 
-    /// Number of files with TODOs
-    pub files_with_todos: usize,
+    /// Print results as a Checkstyle XML report
+    ///
+    /// Each file is a `<file>` element and each item an `<error>` whose severity
+    /// comes from its priority (see [`checkstyle_severity`]) and whose source is
+    /// `todo-tree.<TAG>`.
+    pub fn print_checkstyle<W: Write>(
+        &self,
+        writer: &mut W,
+        result: &ScanResult,
+    ) -> io::Result<()> {
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, r#"<checkstyle version="4.3">"#)?;
 
-    /// Total files scanned
-    pub files_scanned: usize,
+        for (path, items) in result.sorted_files() {
+            writeln!(
+                writer,
+                r#"  <file name="{}">"#,
+                xml_escape(&self.format_path(path))
+            )?;
+            for item in items {
+                writeln!(
+                    writer,
+                    r#"    <error line="{}" column="{}" severity="{}" message="{}" source="todo-tree.{}"/>"#,
+                    item.line,
+                    item.column,
+                    checkstyle_severity(item.priority),
+                    xml_escape(&self.display_message(&item.message)),
+                    xml_escape(&item.tag)
+                )?;
+            }
+            writeln!(writer, "  </file>")?;
+        }
 
-    /// Count by tag
-    pub tag_counts: HashMap<String, usize>,
-}
+        writeln!(writer, "</checkstyle>")?;
+        Ok(())
+    }
 
-impl JsonOutput {
-    /// Create JSON output from scan result
-    pub fn from_scan_result(result: &ScanResult, options: &PrintOptions) -> Self {
-        let mut files: Vec<JsonFileEntry> = result
-            .sorted_files()
+    /// Print results as an xUnit.net v2 XML report
+    ///
+    /// Each file is a `<collection>` and each item a `<test>`: Critical and High
+    /// items fail, the others are skipped (see [`xunit_result`]), so .NET CI
+    /// reporters show them without failing on every note.
+    pub fn print_xunit<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        let files = result.sorted_files();
+        let count = |items: &[TodoItem], outcome: &str| {
+            items
+                .iter()
+                .filter(|item| xunit_result(item.priority) == outcome)
+                .count()
+        };
+        let all_items: Vec<TodoItem> = files
             .iter()
-            .map(|(path, items)| {
-                let display_path = if options.full_paths {
-                    path.display().to_string()
-                } else if let Some(base) = &options.base_path {
-                    path.strip_prefix(base)
-                        .map(|p| p.display().to_string())
-                        .unwrap_or_else(|_| path.display().to_string())
-                } else {
-                    path.display().to_string()
-                };
+            .flat_map(|(_, items)| items.iter().cloned())
+            .collect();
 
-                JsonFileEntry {
-                    path: display_path,
-                    items: items
-                        .iter()
-                        .map(|item| JsonTodoItem {
-                            tag: item.tag.clone(),
-                            message: item.message.clone(),
-                            line: item.line,
-                            column: item.column,
-                            author: item.author.clone(),
-                            priority: format!("{:?}", item.priority),
-                        })
-                        .collect(),
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(writer, "<assemblies>")?;
+        writeln!(
+            writer,
+            r#"  <assembly name="todo-tree" test-framework="todo-tree" total="{}" passed="0" failed="{}" skipped="{}" errors="0" time="0">"#,
+            all_items.len(),
+            count(&all_items, "Fail"),
+            count(&all_items, "Skip")
+        )?;
+
+        for (path, items) in &files {
+            let path = xml_escape(&self.format_path(path));
+            writeln!(
+                writer,
+                r#"    <collection name="{}" total="{}" passed="0" failed="{}" skipped="{}" time="0">"#,
+                path,
+                items.len(),
+                count(items, "Fail"),
+                count(items, "Skip")
+            )?;
+            for item in items.iter() {
+                let outcome = xunit_result(item.priority);
+                let message = xml_escape(&self.display_message(&item.message));
+                writeln!(
+                    writer,
+                    r#"      <test name="{}:{} {}: {}" type="{}" method="{}" time="0" result="{}">"#,
+                    path,
+                    item.line,
+                    xml_escape(&item.tag),
+                    message,
+                    path,
+                    xml_escape(&item.tag),
+                    outcome
+                )?;
+                if outcome == "Fail" {
+                    writeln!(
+                        writer,
+                        "        <failure><message>{}</message></failure>",
+                        message
+                    )?;
+                } else {
+                    writeln!(writer, "        <reason>{}</reason>", message)?;
                 }
+                writeln!(writer, "      </test>")?;
+            }
+            writeln!(writer, "    </collection>")?;
+        }
+
+        writeln!(writer, "  </assembly>")?;
+        writeln!(writer, "</assemblies>")?;
+        Ok(())
+    }
+
+    /// Print results in the SonarQube Generic Issue Import format
+    ///
+    /// Each item is a `CODE_SMELL` issue whose rule is the tag and whose severity
+    /// comes from its priority (see [`sonarqube_severity`]). Columns are 0-based,
+    /// as SonarQube expects.
+    pub fn print_sonarqube<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        let mut issues = Vec::new();
+        for (path, items) in result.sorted_files() {
+            let display_path = self.format_path(path);
+            for item in items {
+                issues.push(serde_json::json!({
+                    "engineId": "todo-tree",
+                    "ruleId": item.tag,
+                    "severity": sonarqube_severity(item.priority),
+                    "type": "CODE_SMELL",
+                    "primaryLocation": {
+                        "message": format!("{}: {}", item.tag, self.display_message(&item.message)),
+                        "filePath": display_path,
+                        "textRange": {
+                            "startLine": item.line,
+                            "startColumn": item.column.saturating_sub(1),
+                        },
+                    },
+                }));
+            }
+        }
+
+        let json = serde_json::json!({ "issues": issues });
+        let json_str = if self.options.json_compact {
+            serde_json::to_string(&json)
+        } else {
+            serde_json::to_string_pretty(&json)
+        }
+        .map_err(io::Error::other)?;
+
+        writeln!(writer, "{}", json_str)
+    }
+
+    /// Print results as a JSON array of LSP `PublishDiagnosticsParams`
+    ///
+    /// Diagnostics are built as the `lsp` command publishes them; files whose
+    /// path cannot be turned into a `file://` URI are skipped.
+    pub fn print_lsp_diagnostics<W: Write>(
+        &self,
+        writer: &mut W,
+        result: &ScanResult,
+    ) -> io::Result<()> {
+        let params: Vec<lsp_types::PublishDiagnosticsParams> = lsp::workspace_diagnostics(result)
+            .into_iter()
+            .map(|(uri, diagnostics)| {
+                lsp_types::PublishDiagnosticsParams::new(uri, diagnostics, None)
             })
             .collect();
 
-        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let json_str = if self.options.json_compact {
+            serde_json::to_string(&params)
+        } else {
+            serde_json::to_string_pretty(&params)
+        }
+        .map_err(io::Error::other)?;
 
-        let summary = JsonSummary {
-            total_count: result.summary.total_count,
-            files_with_todos: result.summary.files_with_todos,
-            files_scanned: result.summary.files_scanned,
-            tag_counts: result.summary.tag_counts.clone(),
+        writeln!(writer, "{}", json_str)
+    }
+
+    /// Print one comma-separated row per item, after a row of column names
+    ///
+    /// Fields are quoted as in RFC 4180 when they contain a comma, quote or
+    /// line break.
+    pub fn print_csv<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        self.print_delimited(writer, result, ",", csv_escape)
+    }
+
+    /// Print one tab-separated row per item, after a row of column names
+    ///
+    /// Tabs and line breaks inside fields are replaced by spaces.
+    pub fn print_tsv<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        self.print_delimited(writer, result, "\t", tsv_escape)
+    }
+
+    /// Print a header row and one row per item, sorted by file
+    ///
+    /// The `author` column is left out when `strip_author` is set.
+    fn print_delimited<W: Write>(
+        &self,
+        writer: &mut W,
+        result: &ScanResult,
+        delimiter: &str,
+        escape: fn(&str) -> String,
+    ) -> io::Result<()> {
+        let mut header = vec![
+            "file", "line", "column", "tag", "priority", "author", "message",
+        ];
+        if self.options.strip_author {
+            header.retain(|column| *column != "author");
+        }
+        writeln!(writer, "{}", header.join(delimiter))?;
+
+        for (path, items) in result.sorted_files() {
+            for item in items {
+                let mut row = vec![
+                    self.format_path(path),
+                    item.line.to_string(),
+                    item.column.to_string(),
+                    item.tag.clone(),
+                    item.priority.display_name().to_string(),
+                ];
+                if !self.options.strip_author {
+                    row.push(item.author.clone().unwrap_or_default());
+                }
+                row.push(self.display_message(&item.message));
+
+                let row: Vec<String> = row.iter().map(|field| escape(field)).collect();
+                writeln!(writer, "{}", row.join(delimiter))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print summary metrics in the Prometheus text exposition format
+    ///
+    /// `todo_tree_items` is labeled by tag and priority; the file counts are
+    /// plain gauges.
+    pub fn print_prometheus<W: Write>(
+        &self,
+        writer: &mut W,
+        result: &ScanResult,
+    ) -> io::Result<()> {
+        let mut counts: BTreeMap<(&str, Priority), usize> = BTreeMap::new();
+        for (_, item) in result.iter() {
+            *counts
+                .entry((item.tag.as_str(), item.priority))
+                .or_insert(0) += 1;
+        }
+
+        writeln!(
+            writer,
+            "# HELP todo_tree_items Number of TODO items by tag and priority."
+        )?;
+        writeln!(writer, "# TYPE todo_tree_items gauge")?;
+        for ((tag, priority), count) in counts {
+            writeln!(
+                writer,
+                "todo_tree_items{{tag=\"{}\",priority=\"{}\"}} {}",
+                prometheus_escape(tag),
+                format!("{:?}", priority).to_lowercase(),
+                count
+            )?;
+        }
+
+        let gauges = [
+            (
+                "todo_tree_files_with_todos",
+                "Number of files containing at least one TODO item.",
+                result.summary.files_with_todos,
+            ),
+            (
+                "todo_tree_files_scanned",
+                "Number of files scanned.",
+                result.summary.files_scanned,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            writeln!(writer, "# HELP {} {}", name, help)?;
+            writeln!(writer, "# TYPE {} gauge", name)?;
+            writeln!(writer, "{} {}", name, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Print summary metrics in the OpenMetrics text exposition format
+    ///
+    /// The same gauges as the Prometheus output, plus the scan time as a gauge
+    /// with a `seconds` unit; the output ends with `# EOF`.
+    pub fn print_open_metrics<W: Write>(
+        &self,
+        writer: &mut W,
+        result: &ScanResult,
+    ) -> io::Result<()> {
+        let scanned_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+
+        let mut counts: BTreeMap<(&str, Priority), usize> = BTreeMap::new();
+        for (_, item) in result.iter() {
+            *counts
+                .entry((item.tag.as_str(), item.priority))
+                .or_insert(0) += 1;
+        }
+
+        writeln!(writer, "# TYPE todo_tree_items gauge")?;
+        writeln!(
+            writer,
+            "# HELP todo_tree_items Number of TODO items by tag and priority."
+        )?;
+        for ((tag, priority), count) in counts {
+            writeln!(
+                writer,
+                "todo_tree_items{{tag=\"{}\",priority=\"{}\"}} {}",
+                prometheus_escape(tag),
+                format!("{:?}", priority).to_lowercase(),
+                count
+            )?;
+        }
+
+        writeln!(writer, "# TYPE todo_tree_files_scanned gauge")?;
+        writeln!(
+            writer,
+            "# HELP todo_tree_files_scanned Number of files scanned."
+        )?;
+        writeln!(
+            writer,
+            "todo_tree_files_scanned {}",
+            result.summary.files_scanned
+        )?;
+
+        writeln!(writer, "# TYPE todo_tree_files_with_todos gauge")?;
+        writeln!(
+            writer,
+            "# HELP todo_tree_files_with_todos Number of files containing at least one TODO item."
+        )?;
+        writeln!(
+            writer,
+            "todo_tree_files_with_todos {}",
+            result.summary.files_with_todos
+        )?;
+
+        writeln!(writer, "# TYPE todo_tree_scan_timestamp_seconds gauge")?;
+        writeln!(writer, "# UNIT todo_tree_scan_timestamp_seconds seconds")?;
+        writeln!(
+            writer,
+            "# HELP todo_tree_scan_timestamp_seconds Time of the scan as a Unix timestamp."
+        )?;
+        writeln!(writer, "todo_tree_scan_timestamp_seconds {:.3}", scanned_at)?;
+
+        writeln!(writer, "# EOF")
+    }
+
+    /// Print results as TeamCity service messages
+    ///
+    /// A `buildProblem` reports the item count (only when there are items), then
+    /// each item is a `message` with status `WARNING` for Critical and High
+    /// priority and `NORMAL` otherwise.
+    pub fn print_teamcity<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        if result.summary.total_count > 0 {
+            writeln!(
+                writer,
+                "##teamcity[buildProblem description='{}']",
+                teamcity_escape(&format!("TODO count: {}", result.summary.total_count))
+            )?;
+        }
+
+        for (path, items) in result.sorted_files() {
+            let display_path = self.format_path(path);
+            for item in items {
+                let status = if item.priority >= Priority::High {
+                    "WARNING"
+                } else {
+                    "NORMAL"
+                };
+                writeln!(
+                    writer,
+                    "##teamcity[message text='{}' errorDetails='{}' status='{}']",
+                    teamcity_escape(&format!(
+                        "{}: {}",
+                        item.tag,
+                        self.display_message(&item.message)
+                    )),
+                    teamcity_escape(&format!("{}:{}:{}", display_path, item.line, item.column)),
+                    status
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print results as a ctags file (`TAG<TAB>file<TAB>line;"<TAB>kind:tag`)
+    ///
+    /// Lines are sorted by tag name, then file and line, as `!_TAG_FILE_SORTED 1` requires.
+    pub fn print_ctags<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        let mut entries: Vec<(&str, String, usize)> = result
+            .iter()
+            .map(|(path, item)| (item.tag.as_str(), self.format_path(path), item.line))
+            .collect();
+        entries.sort();
+
+        writeln!(writer, "!_TAG_FILE_FORMAT\t2\t/extended format/")?;
+        writeln!(
+            writer,
+            "!_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/"
+        )?;
+        writeln!(writer, "!_TAG_PROGRAM_NAME\ttodo-tree\t//")?;
+
+        for (tag, path, line) in entries {
+            writeln!(
+                writer,
+                "{}\t{}\t{};\"\tkind:{}",
+                tag,
+                path,
+                line,
+                tag.to_lowercase()
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Print results as an Emacs TAGS file
+    ///
+    /// Each file gets a section (`\x0c`, then `path,size`) listing its items as
+    /// `line text\x7fTAG\x01line,`; the character offset is left empty.
+    pub fn print_etags<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        for (path, items) in result.sorted_files() {
+            let section: String = items
+                .iter()
+                .map(|item| {
+                    let text = item
+                        .line_content
+                        .clone()
+                        .unwrap_or_else(|| format!("{}: {}", item.tag, item.message));
+                    format!(
+                        "{}\x7f{}\x01{},\n",
+                        etags_escape(text.trim_end()),
+                        etags_escape(&item.tag),
+                        item.line
+                    )
+                })
+                .collect();
+
+            write!(
+                writer,
+                "\x0c\n{},{}\n{}",
+                self.format_path(path),
+                section.len(),
+                section
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Print the TODO items added and removed between two scan results
+    ///
+    /// Items are matched by path (relative to each result's root), tag and
+    /// message, so moving a TODO to another line does not count as a change.
+    pub fn print_diff<W: Write>(
+        &self,
+        writer: &mut W,
+        old: &ScanResult,
+        new: &ScanResult,
+    ) -> io::Result<()> {
+        let old_items = relative_items(old);
+        let new_items = relative_items(new);
+        let removed = subtract_items(&old_items, &new_items);
+        let added = subtract_items(&new_items, &old_items);
+
+        if added.is_empty() && removed.is_empty() {
+            writeln!(writer, "{}", "No changes in TODO items.".dimmed())?;
+        }
+
+        for (sign, items) in [("-", &removed), ("+", &added)] {
+            for (path, item) in items {
+                let line = format!(
+                    "{} {}:{} [{}] {}",
+                    sign,
+                    path.display(),
+                    item.line,
+                    item.tag,
+                    item.message
+                );
+                if !self.options.colored {
+                    writeln!(writer, "{}", line)?;
+                } else if sign == "+" {
+                    writeln!(writer, "{}", line.green())?;
+                } else {
+                    writeln!(writer, "{}", line.red())?;
+                }
+            }
+        }
+
+        if self.options.show_summary {
+            writeln!(writer)?;
+            let summary_line = format!(
+                "{} added, {} removed ({} -> {} TODO items)",
+                added.len(),
+                removed.len(),
+                old.summary.total_count,
+                new.summary.total_count
+            );
+            if self.options.colored {
+                writeln!(writer, "{}", summary_line.bold())?;
+            } else {
+                writeln!(writer, "{}", summary_line)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Print results in JSON format
+    fn print_json<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        // Without any rewrite, write the result as it is reached instead of building
+        // a `serde_json::Value` copy of every item first
+        if self.options.message_prefix.is_none()
+            && self.options.title.is_none()
+            && self.options.pagination.is_none()
+            && self.options.statistics.is_none()
+            && self.options.json_schema_version == JsonSchemaVersion::V1_1
+        {
+            let output = IncrementalJsonOutput {
+                result: result.json_view(),
+                schema_version: self.options.json_schema_version.as_str(),
+            };
+            if self.options.json_compact {
+                serde_json::to_writer(&mut *writer, &output)
+            } else {
+                serde_json::to_writer_pretty(&mut *writer, &output)
+            }
+            .map_err(io::Error::other)?;
+            return writeln!(writer);
+        }
+
+        let mut json_result =
+            serde_json::to_value(result.to_json_format()).map_err(io::Error::other)?;
+        if let Some(prefix) = &self.options.message_prefix
+            && let Some(files) = json_result["files"].as_array_mut()
+        {
+            for file in files {
+                for item in file["items"].as_array_mut().into_iter().flatten() {
+                    if let Some(message) = item["message"].as_str() {
+                        item["message"] =
+                            serde_json::Value::String(format!("{}{}", prefix, message));
+                    }
+                }
+            }
+        }
+        if let Some(title) = &self.options.title {
+            json_result["title"] = serde_json::Value::String(title.clone());
+        }
+        if self.options.pagination.is_some() {
+            json_result["paginated"] = serde_json::Value::Bool(true);
+        }
+        self.options.json_schema_version.apply(&mut json_result);
+        if let Some(statistics) = &self.options.statistics {
+            json_result["statistics"] = statistics.clone();
+        }
+        let json_str = if self.options.json_compact {
+            serde_json::to_string(&json_result)
+        } else {
+            serde_json::to_string_pretty(&json_result)
+        }
+        .map_err(io::Error::other)?;
+
+        writeln!(writer, "{}", json_str)?;
+
+        Ok(())
+    }
+
+    /// The message as displayed, with the `message_prefix` (if any) in front
+    fn display_message(&self, message: &str) -> String {
+        match &self.options.message_prefix {
+            Some(prefix) => format!("{}{}", prefix, message),
+            None => message.to_string(),
+        }
+    }
+
+    /// Print the title line, if any
+    pub fn print_title<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if let Some(title) = &self.options.title {
+            if self.options.colored {
+                writeln!(writer, "{}", title.bold())?;
+            } else {
+                writeln!(writer, "{}", title)?;
+            }
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Print summary statistics
+    pub fn print_summary<W: Write>(&self, writer: &mut W, result: &ScanResult) -> io::Result<()> {
+        let summary_line = format!(
+            "Found {} TODO items in {} files ({} files scanned)",
+            result.summary.total_count,
+            result.summary.files_with_todos,
+            result.summary.files_scanned
+        );
+
+        if self.options.colored {
+            writeln!(writer, "{}", summary_line.bold())?;
+        } else {
+            writeln!(writer, "{}", summary_line)?;
+        }
+
+        // Print tag breakdown
+        if !result.summary.tag_counts.is_empty() {
+            let mut tags: Vec<_> = result.summary.tag_counts.iter().collect();
+            tags.sort_by(|a, b| b.1.cmp(a.1));
+
+            let breakdown: Vec<String> = tags
+                .iter()
+                .map(|(tag, count)| {
+                    if self.options.colored {
+                        let priority = tag_priority(result.iter().map(|(_, item)| item), tag);
+                        format!("{}: {}", self.colorize_tag(tag, priority), count)
+                    } else {
+                        format!("{}: {}", tag, count)
+                    }
+                })
+                .collect();
+
+            writeln!(writer, "  {}", breakdown.join(", "))?;
+        }
+
+        if let Some(pagination) = &self.options.pagination {
+            writeln!(writer, "  {}", pagination.describe())?;
+        }
+
+        Ok(())
+    }
+
+    /// Format a path for display
+    fn format_path(&self, path: &Path) -> String {
+        if self.options.full_paths {
+            path.display().to_string()
+        } else if let Some(base) = &self.options.base_path {
+            path.strip_prefix(base)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| path.display().to_string())
+        } else {
+            path.display().to_string()
+        }
+    }
+
+    /// Format a path for human-readable output, keeping the last `truncate_paths` components
+    ///
+    /// Machine-readable formats and link targets use [`format_path`](Self::format_path).
+    fn display_path(&self, path: &Path) -> String {
+        let formatted = self.format_path(path);
+        match self.options.truncate_paths {
+            Some(n) if n > 0 => {
+                let components: Vec<_> = Path::new(&formatted).components().collect();
+                let start = components.len().saturating_sub(n);
+                components[start..]
+                    .iter()
+                    .collect::<PathBuf>()
+                    .display()
+                    .to_string()
+            }
+            _ => formatted,
+        }
+    }
+
+    /// Whether OSC 8 links should be emitted
+    ///
+    /// Terminal support is only detected when links are not forced on.
+    fn hyperlinks_enabled(&self) -> bool {
+        self.options.clickable_links && (self.options.force_hyperlinks || supports_hyperlinks())
+    }
+
+    /// Create a clickable hyperlink using OSC 8 escape sequence
+    fn make_clickable_link(&self, path: &Path, line: usize) -> Option<String> {
+        if !self.hyperlinks_enabled() {
+            return None;
+        }
+
+        let display_path = self.display_path(path);
+        let abs_path = path.canonicalize().ok()?;
+        let file_url = format!("file://{}:{}", abs_path.display(), line);
+
+        // OSC 8 hyperlink format: \x1b]8;;URL\x1b\\TEXT\x1b]8;;\x1b\\
+        let link = format!(
+            "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+            file_url,
+            self.colorize_path(&display_path)
+        );
+
+        Some(link)
+    }
+
+    /// Create a clickable link for a line number
+    fn make_line_link(&self, path: &Path, line: usize) -> Option<String> {
+        if !self.hyperlinks_enabled() {
+            return None;
+        }
+
+        let abs_path = path.canonicalize().ok()?;
+        let file_url = format!("file://{}:{}", abs_path.display(), line);
+        let display = format!("L{}", line);
+
+        let link = format!(
+            "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\",
+            file_url,
+            if self.options.colored {
+                display.cyan().to_string()
+            } else {
+                display
+            }
+        );
+
+        Some(link)
+    }
+
+    /// Colorize a displayed file path according to the color mode
+    fn colorize_path(&self, display_path: &str) -> String {
+        if !self.options.colored {
+            return display_path.to_string();
+        }
+
+        match self.options.color_mode {
+            ColorMode::ByTag => display_path.bold().to_string(),
+            ColorMode::ByFile => display_path
+                .color(file_color(display_path))
+                .bold()
+                .to_string(),
+        }
+    }
+
+    /// Highlight each case-insensitive occurrence of `tag` in a message
+    ///
+    /// Only applies with `highlight_matches` and colors on; otherwise the
+    /// message is returned unchanged.
+    pub fn colorize_message(&self, message: &str, tag: &str) -> String {
+        if !self.options.highlight_matches || !self.options.colored {
+            return message.to_string();
+        }
+
+        highlight_occurrences(message, tag, |text| text.bright_yellow().bold().to_string())
+    }
+
+    /// Colorize a tag with the color of its (resolved) priority
+    fn colorize_tag(&self, tag: &str, priority: Priority) -> String {
+        if !self.options.colored {
+            return tag.to_string();
+        }
+
+        let color = self.options.color_scheme.color_for(priority);
+        tag.color(color).bold().to_string()
+    }
+}
+
+/// The highest priority among the items with `tag`, for coloring a tag on its own
+///
+/// Falls back to the built-in priority when no item has the tag.
+fn tag_priority<'a, I>(items: I, tag: &str) -> Priority
+where
+    I: IntoIterator<Item = &'a TodoItem>,
+{
+    items
+        .into_iter()
+        .filter(|item| item.tag == tag)
+        .map(|item| item.priority)
+        .max()
+        .unwrap_or_else(|| Priority::from_tag(tag))
+}
+
+/// Replace each ASCII case-insensitive occurrence of `needle` with `style` applied to it
+///
+/// The matched text keeps its original case.
+fn highlight_occurrences<F: Fn(&str) -> String>(haystack: &str, needle: &str, style: F) -> String {
+    if needle.is_empty() {
+        return haystack.to_string();
+    }
+
+    // ASCII lowercasing keeps byte offsets valid in the original string
+    let lower = haystack.to_ascii_lowercase();
+    let needle = needle.to_ascii_lowercase();
+
+    let mut highlighted = String::with_capacity(haystack.len());
+    let mut rest = 0;
+    for (start, _) in lower.match_indices(&needle) {
+        let end = start + needle.len();
+        highlighted.push_str(&haystack[rest..start]);
+        highlighted.push_str(&style(&haystack[start..end]));
+        rest = end;
+    }
+    highlighted.push_str(&haystack[rest..]);
+    highlighted
+}
+
+/// Collect all items with paths relative to the result root, sorted by path and line
+fn relative_items(result: &ScanResult) -> Vec<(PathBuf, TodoItem)> {
+    let mut items: Vec<(PathBuf, TodoItem)> = result
+        .get_files()
+        .into_iter()
+        .flat_map(|file| {
+            let path = PathBuf::from(&file.path);
+            let path = result
+                .root
+                .as_deref()
+                .and_then(|root| path.strip_prefix(root).ok())
+                .map(Path::to_path_buf)
+                .unwrap_or(path);
+            file.items.into_iter().map(move |item| (path.clone(), item))
+        })
+        .collect();
+
+    items.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.line.cmp(&b.1.line)));
+    items
+}
+
+/// Items of `from` that have no counterpart (same path, tag and message) in `other`
+fn subtract_items<'a>(
+    from: &'a [(PathBuf, TodoItem)],
+    other: &[(PathBuf, TodoItem)],
+) -> Vec<&'a (PathBuf, TodoItem)> {
+    let mut remaining: HashMap<(&Path, &str, &str), usize> = HashMap::new();
+    for (path, item) in other {
+        *remaining
+            .entry((path.as_path(), item.tag.as_str(), item.message.as_str()))
+            .or_insert(0) += 1;
+    }
+
+    from.iter()
+        .filter(|(path, item)| {
+            match remaining.get_mut(&(path.as_path(), item.tag.as_str(), item.message.as_str())) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        })
+        .collect()
+}
+
+/// JSON output structure
+#[derive(Debug, Serialize)]
+pub struct JsonOutput {
+    /// Version of the JSON layout
+    pub schema_version: &'static str,
+
+    /// All TODO items grouped by file
+    pub files: Vec<JsonFileEntry>,
+
+    /// Summary statistics
+    pub summary: JsonSummary,
+}
+
+/// JSON entry for a single file
+#[derive(Debug, Serialize)]
+pub struct JsonFileEntry {
+    /// File path
+    pub path: String,
+
+    /// TODO items in this file
+    pub items: Vec<JsonTodoItem>,
+}
+
+/// JSON representation of a TODO item
+#[derive(Debug, Serialize)]
+pub struct JsonTodoItem {
+    /// The tag (TODO, FIXME, etc.)
+    pub tag: String,
+
+    /// The message content
+    pub message: String,
+
+    /// Line number (1-indexed)
+    pub line: usize,
+
+    /// Column number (1-indexed)
+    pub column: usize,
+
+    /// Optional author
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Priority level
+    pub priority: String,
+}
+
+/// JSON summary statistics
+#[derive(Debug, Serialize)]
+pub struct JsonSummary {
+    /// Total number of TODO items
+    pub total_count: usize,
+
+    /// Number of files with TODOs
+    pub files_with_todos: usize,
+
+    /// Total files scanned
+    pub files_scanned: usize,
+
+    /// Count by tag
+    pub tag_counts: HashMap<String, usize>,
+
+    /// Whether the scan stopped early
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+
+    /// Whether the scan hit its time limit
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+}
+
+impl JsonOutput {
+    /// Create JSON output from scan result
+    pub fn from_scan_result(result: &ScanResult, options: &PrintOptions) -> Self {
+        let mut files: Vec<JsonFileEntry> = result
+            .sorted_files()
+            .iter()
+            .map(|(path, items)| {
+                let display_path = if options.full_paths {
+                    path.display().to_string()
+                } else if let Some(base) = &options.base_path {
+                    path.strip_prefix(base)
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|_| path.display().to_string())
+                } else {
+                    path.display().to_string()
+                };
+
+                JsonFileEntry {
+                    path: display_path,
+                    items: items
+                        .iter()
+                        .map(|item| JsonTodoItem {
+                            tag: item.tag.clone(),
+                            message: format!(
+                                "{}{}",
+                                options.message_prefix.as_deref().unwrap_or_default(),
+                                item.message
+                            ),
+                            line: item.line,
+                            column: item.column,
+                            author: item.author.clone(),
+                            priority: format!("{:?}", item.priority),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let summary = JsonSummary {
+            total_count: result.summary.total_count,
+            files_with_todos: result.summary.files_with_todos,
+            files_scanned: result.summary.files_scanned,
+            tag_counts: result.summary.tag_counts.clone(),
+            truncated: result.summary.truncated,
+            timed_out: result.summary.timed_out,
+        };
+
+        Self {
+            schema_version: options.json_schema_version.as_str(),
+            files,
+            summary,
+        }
+    }
+}
+
+/// Truncate a message to at most `max` characters, appending an ellipsis if cut
+fn truncate_message(message: &str, max: usize) -> String {
+    if message.chars().count() <= max {
+        message.to_string()
+    } else {
+        let truncated: String = message.chars().take(max).collect();
+        format!("{}…", truncated.trim_end())
+    }
+}
+
+/// Org-mode priority cookie letter for a priority
+fn org_priority(priority: Priority) -> char {
+    match priority {
+        Priority::Critical => 'A',
+        Priority::High => 'B',
+        Priority::Medium => 'C',
+        Priority::Low => 'D',
+    }
+}
+
+/// Make a tag name valid as an Org tag (letters, digits, `_`, `@`, `#`, `%`)
+fn org_tag(tag: &str) -> String {
+    tag.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Map a priority to a Checkstyle severity
+///
+/// Critical items are errors, so `failsOnError` in the Maven plugin fails on them.
+fn checkstyle_severity(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Critical => "error",
+        Priority::High => "warning",
+        Priority::Medium | Priority::Low => "info",
+    }
+}
+
+/// Map a priority to a Confluence `{color}` macro color
+fn atlassian_color(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Critical => "red",
+        Priority::High => "orange",
+        Priority::Medium => "blue",
+        Priority::Low => "grey",
+    }
+}
+
+/// Escape the characters that start wiki markup (tables, macros, links, emphasis)
+fn atlassian_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '|' | '{' | '}' | '[' | ']' | '*' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escape the characters Markdown would treat as inline formatting
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Map a priority to an xUnit.net test result
+fn xunit_result(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Critical | Priority::High => "Fail",
+        Priority::Medium | Priority::Low => "Skip",
+    }
+}
+
+/// Quote a CSV field if it contains a comma, double quote or line break
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Replace the characters a TSV field cannot contain (tabs and line breaks) with spaces
+fn tsv_escape(field: &str) -> String {
+    field.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Escape text for use in XML attribute values
+///
+/// Tab, LF and CR become character references so parsers keep them. Other
+/// control characters are not allowed in XML 1.0 and are replaced with U+FFFD.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' => escaped.push_str("&#9;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\u{0}'..='\u{1f}' => escaped.push(char::REPLACEMENT_CHARACTER),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Map a priority to a SonarQube issue severity
+fn sonarqube_severity(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Critical => "BLOCKER",
+        Priority::High => "CRITICAL",
+        Priority::Medium => "MAJOR",
+        Priority::Low => "INFO",
+    }
+}
+
+/// Escape a value for a TeamCity service message attribute
+fn teamcity_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '|' => escaped.push_str("||"),
+            '\'' => escaped.push_str("|'"),
+            '\n' => escaped.push_str("|n"),
+            '\r' => escaped.push_str("|r"),
+            '[' => escaped.push_str("|["),
+            ']' => escaped.push_str("|]"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The context lines of an item that the options ask to show
+///
+/// Takes the lines closest to the item when more context was collected than requested.
+fn visible_context<'a>(item: &'a TodoItem, options: &PrintOptions) -> (&'a [String], &'a [String]) {
+    let before = &item.context_before;
+    let before = &before[before.len().saturating_sub(options.before_context)..];
+    let after = &item.context_after[..item.context_after.len().min(options.after_context)];
+    (before, after)
+}
+
+/// Escape a Prometheus label value (backslash, double quote and newline)
+fn prometheus_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Drop the characters that delimit fields in an etags entry
+fn etags_escape(text: &str) -> String {
+    text.replace(['\x7f', '\x01', '\n'], " ")
+}
+
+/// Escape `#` in a TAP test description, which would otherwise start a directive
+///
+/// Backslashes are escaped first, so a literal `\#` stays distinguishable.
+fn tap_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('#', "\\#")
+}
+
+/// Check if the terminal supports hyperlinks (OSC 8)
+fn supports_hyperlinks() -> bool {
+    // Check common environment variables that indicate hyperlink support
+    if let Ok(term_program) = std::env::var("TERM_PROGRAM") {
+        let supported_terminals = [
+            "iTerm.app",
+            "WezTerm",
+            "Hyper",
+            "Tabby",
+            "Alacritty",
+            "vscode",
+            "VSCodium",
+        ];
+        if supported_terminals.iter().any(|t| term_program.contains(t)) {
+            return true;
+        }
+    }
+
+    // Check for explicit hyperlink support
+    if let Ok(colorterm) = std::env::var("COLORTERM")
+        && (colorterm == "truecolor" || colorterm == "24bit")
+    {
+        // Modern terminals with truecolor often support hyperlinks
+        return true;
+    }
+
+    // Check VTE version (GNOME Terminal and derivatives)
+    if std::env::var("VTE_VERSION").is_ok() {
+        return true;
+    }
+
+    // Check for Konsole
+    if std::env::var("KONSOLE_VERSION").is_ok() {
+        return true;
+    }
+
+    // Default to false for unknown terminals
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_result() -> ScanResult {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/src/main.rs"),
+            vec![
+                TodoItem {
+                    tag: "TODO".to_string(),
+                    message: "Implement feature".to_string(),
+                    line: 10,
+                    column: 5,
+                    line_content: Some("// TODO: Implement feature".to_string()),
+                    author: None,
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "FIXME".to_string(),
+                    message: "Fix this bug".to_string(),
+                    line: 20,
+                    column: 5,
+                    line_content: Some("// FIXME: Fix this bug".to_string()),
+                    author: Some("john".to_string()),
+                    priority: Priority::Critical,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+            ],
+        );
+        result
+    }
+
+    #[test]
+    fn test_print_tree() {
+        let result = create_test_result();
+        let options = PrintOptions {
+            colored: false,
+            clickable_links: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("TODO"));
+        assert!(output_str.contains("FIXME"));
+        assert!(output_str.contains("Implement feature"));
+        assert!(output_str.contains("Fix this bug"));
+    }
+
+    #[test]
+    fn test_print_flat() {
+        let result = create_test_result();
+        let options = PrintOptions {
+            format: OutputFormat::Flat,
+            colored: false,
+            clickable_links: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains(":10:5"));
+        assert!(output_str.contains(":20:5"));
+    }
+
+    #[test]
+    fn test_print_title() {
+        let result = create_test_result();
+
+        for format in [OutputFormat::Tree, OutputFormat::Flat] {
+            let printer = Printer::new(PrintOptions {
+                format,
+                colored: false,
+                clickable_links: false,
+                title: Some("Sprint 42 TODO Audit".to_string()),
+                ..Default::default()
+            });
+
+            let mut output = Vec::new();
+            printer.print_to(&mut output, &result).unwrap();
+
+            let output_str = String::from_utf8(output).unwrap();
+            assert!(output_str.starts_with("Sprint 42 TODO Audit\n\n"));
+        }
+    }
+
+    #[test]
+    fn test_print_json_title() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Json,
+            colored: false,
+            title: Some("Audit".to_string()),
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["title"], "Audit");
+        assert_eq!(json["summary"]["total_count"], 2);
+    }
+
+    #[test]
+    fn test_print_json_compact() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Json,
+            colored: false,
+            json_compact: true,
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(output_str.lines().count(), 1);
+        let json: serde_json::Value = serde_json::from_str(&output_str).unwrap();
+        assert_eq!(json["summary"]["total_count"], 2);
+    }
+
+    #[test]
+    fn test_print_json_schema_version() {
+        let mut result = create_test_result();
+        result.summary.truncated = true;
+        let print = |json_schema_version| {
+            let printer = Printer::new(PrintOptions {
+                format: OutputFormat::Json,
+                colored: false,
+                title: Some("Audit".to_string()),
+                json_schema_version,
+                ..Default::default()
+            });
+            let mut output = Vec::new();
+            printer.print_to(&mut output, &result).unwrap();
+            serde_json::from_slice::<serde_json::Value>(&output).unwrap()
+        };
+
+        let latest = print(JsonSchemaVersion::default());
+        assert_eq!(latest["schema_version"], "1.1");
+        assert_eq!(latest["title"], "Audit");
+        assert_eq!(latest["summary"]["truncated"], true);
+
+        let v1_0 = print(JsonSchemaVersion::V1_0);
+        assert_eq!(
+            v1_0.as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["files", "summary"]
+        );
+        assert!(v1_0["summary"].get("truncated").is_none());
+        assert_eq!(v1_0["summary"]["total_count"], 2);
+        let item = &v1_0["files"][0]["items"][1];
+        assert_eq!(item["tag"], "FIXME");
+        assert_eq!(item["author"], "john");
+        assert_eq!(item.as_object().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn test_pagination_describe() {
+        let pagination = Pagination {
+            offset: 0,
+            shown: 10,
+            total: 250,
+            from_end: false,
+        };
+        assert_eq!(pagination.describe(), "Showing first 10 of 250 total");
+
+        let last = Pagination {
+            from_end: true,
+            ..pagination
+        };
+        assert_eq!(last.describe(), "Showing last 10 of 250 total");
+
+        let page = Pagination {
+            offset: 20,
+            ..pagination
+        };
+        assert_eq!(page.describe(), "Showing items 21-30 of 250 total");
+    }
+
+    #[test]
+    fn test_print_json_paginated() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Json,
+            colored: false,
+            pagination: Some(Pagination {
+                offset: 0,
+                shown: 2,
+                total: 5,
+                from_end: false,
+            }),
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["paginated"], true);
+    }
+
+    #[test]
+    fn test_print_json_statistics() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Json,
+            colored: false,
+            message_prefix: Some("[repo] ".to_string()),
+            statistics: Some(serde_json::json!({ "total_items": 2 })),
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["statistics"]["total_items"], 2);
+        assert!(
+            json["files"][0]["items"][0]["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("[repo] ")
+        );
+    }
+
+    #[test]
+    fn test_print_json() {
+        let result = create_test_result();
+        let options = PrintOptions {
+            format: OutputFormat::Json,
+            colored: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+
+        // Should be valid JSON
+        let parsed: serde_json::Value = serde_json::from_str(&output_str).unwrap();
+        assert!(parsed.get("files").is_some());
+        assert!(parsed.get("summary").is_some());
+
+        // Written incrementally, but the same document as the rewritten path
+        let mut expected = serde_json::to_value(result.to_json_format()).unwrap();
+        JsonSchemaVersion::V1_1.apply(&mut expected);
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_empty_result() {
+        let result = ScanResult::new(PathBuf::from("/test"));
+        let options = PrintOptions {
+            colored: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("No TODO items found"));
+    }
+
+    #[test]
+    fn test_colorize_tag() {
+        let options = PrintOptions {
+            colored: true,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        // Just verify it doesn't panic
+        let _ = printer.colorize_tag("TODO", Priority::Medium);
+        let _ = printer.colorize_tag("FIXME", Priority::Critical);
+        let _ = printer.colorize_tag("BUG", Priority::Critical);
+        let _ = printer.colorize_tag("NOTE", Priority::Low);
+    }
+
+    #[test]
+    fn test_tag_priority() {
+        let result = create_test_result();
+        let items = || result.iter().map(|(_, item)| item);
+
+        assert_eq!(tag_priority(items(), "FIXME"), Priority::Critical);
+
+        // Configured priorities win over the built-in mapping
+        let mut items_vec: Vec<TodoItem> = items().cloned().collect();
+        items_vec[0].priority = Priority::Low;
+        let tag = items_vec[0].tag.clone();
+        assert_eq!(tag_priority(&items_vec, &tag), Priority::Low);
+
+        assert_eq!(tag_priority(items(), "BUG"), Priority::Critical);
+        assert_eq!(tag_priority(items(), "SECURITY"), Priority::Medium);
+    }
+
+    #[test]
+    fn test_format_path() {
+        let options = PrintOptions {
+            full_paths: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let path = PathBuf::from("/test/src/main.rs");
+        let formatted = printer.format_path(&path);
+        assert_eq!(formatted, "src/main.rs");
+    }
+
+    #[test]
+    fn test_display_path_truncated() {
+        let printer = |truncate_paths| {
+            Printer::new(PrintOptions {
+                base_path: Some(PathBuf::from("/test")),
+                truncate_paths,
+                ..Default::default()
+            })
+        };
+        let path = PathBuf::from("/test/src/auth/oauth/handler.rs");
+
+        assert_eq!(
+            printer(Some(2)).display_path(&path),
+            Path::new("oauth").join("handler.rs").display().to_string()
+        );
+        assert_eq!(printer(Some(1)).display_path(&path), "handler.rs");
+        assert_eq!(
+            printer(Some(10)).display_path(&path),
+            "src/auth/oauth/handler.rs"
+        );
+        assert_eq!(
+            printer(Some(0)).display_path(&path),
+            "src/auth/oauth/handler.rs"
+        );
+        assert_eq!(
+            printer(Some(1)).format_path(&path),
+            "src/auth/oauth/handler.rs"
+        );
+    }
+
+    #[test]
+    fn test_format_path_full() {
+        let options = PrintOptions {
+            full_paths: true,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let path = PathBuf::from("/test/src/main.rs");
+        let formatted = printer.format_path(&path);
+        assert_eq!(formatted, "/test/src/main.rs");
+    }
+
+    #[test]
+    fn test_json_output_structure() {
+        let result = create_test_result();
+        let options = PrintOptions::default();
+        let json_output = JsonOutput::from_scan_result(&result, &options);
+
+        assert_eq!(json_output.summary.total_count, 2);
+        assert_eq!(json_output.summary.files_with_todos, 1);
+        assert_eq!(json_output.files.len(), 1);
+        assert_eq!(json_output.files[0].items.len(), 2);
+    }
+
+    #[test]
+    fn test_print_no_summary() {
+        let result = create_test_result();
+        let options = PrintOptions {
+            colored: false,
+            show_summary: false,
+            clickable_links: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(!output_str.contains("Found"));
+        assert!(!output_str.contains("files scanned"));
+    }
+
+    #[test]
+    fn test_print_tree_suppress_header() {
+        let result = create_test_result();
+
+        for group_by_tag in [false, true] {
+            let printer = Printer::new(PrintOptions {
+                colored: false,
+                clickable_links: false,
+                show_summary: false,
+                suppress_header: true,
+                group_by_tag,
+                ..Default::default()
+            });
+
+            let mut output = Vec::new();
+            printer.print_to(&mut output, &result).unwrap();
+
+            let output_str = String::from_utf8(output).unwrap();
+            let lines: Vec<&str> = output_str.lines().collect();
+            assert_eq!(lines.len(), 2, "{}", output_str);
+            assert!(
+                lines
+                    .iter()
+                    .all(|line| !line.starts_with("├──") && !line.starts_with("└──"))
+            );
+            assert!(!output_str.contains("main.rs ("));
+        }
+    }
+
+    #[test]
+    fn test_group_by_tag() {
+        let result = create_test_result();
+        let options = PrintOptions {
+            colored: false,
+            clickable_links: false,
+            group_by_tag: true,
+            show_summary: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        // Should have tag headers
+        assert!(output_str.contains("FIXME (1)") || output_str.contains("TODO (1)"));
+    }
+
+    #[test]
+    fn test_group_by_author() {
+        let mut result = create_test_result();
+        result.add_file(
+            PathBuf::from("/test/lib.rs"),
+            vec![TodoItem {
+                tag: "NOTE".to_string(),
+                message: "Document".to_string(),
+                line: 3,
+                column: 1,
+                line_content: None,
+                author: Some("alice".to_string()),
+                priority: Priority::Low,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            clickable_links: false,
+            group_by_author: true,
+            show_summary: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output_str,
+            "├── alice (1)\n\
+             │   └── lib.rs:3 [NOTE] Document\n\
+             ├── john (1)\n\
+             │   └── src/main.rs:20 [FIXME] Fix this bug\n\
+             └── (unassigned) (1)\n    \
+             └── src/main.rs:10 [TODO] Implement feature\n"
+        );
+    }
+
+    #[test]
+    fn test_file_color_is_deterministic() {
+        assert_eq!(file_color("src/main.rs"), file_color("src/main.rs"));
+
+        let colors: std::collections::HashSet<String> = (0..50)
+            .map(|i| format!("{:?}", file_color(&format!("src/file{}.rs", i))))
+            .collect();
+        assert!(colors.len() > 1);
+    }
+
+    #[test]
+    fn test_colorize_path_by_file() {
+        let printer = Printer::new(PrintOptions {
+            colored: true,
+            color_mode: ColorMode::ByFile,
+            ..Default::default()
+        });
+
+        // Should not panic and should keep the path text
+        assert!(printer.colorize_path("src/main.rs").contains("src/main.rs"));
+    }
+
+    #[test]
+    fn test_colorize_path_disabled() {
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            color_mode: ColorMode::ByFile,
+            ..Default::default()
+        });
+
+        assert_eq!(printer.colorize_path("src/main.rs"), "src/main.rs");
+    }
+
+    #[test]
+    fn test_color_scheme_tag_colors() {
+        assert_eq!(
+            ColorScheme::Default.tag_colors(),
+            [Color::Red, Color::Yellow, Color::Cyan, Color::Green]
+        );
+
+        assert_eq!(
+            ColorScheme::Nord.color_for(Priority::Critical),
+            Color::TrueColor {
+                r: 0xbf,
+                g: 0x61,
+                b: 0x6a
+            }
+        );
+        assert_eq!(
+            ColorScheme::HighContrast.color_for(Priority::Low),
+            Color::BrightGreen
+        );
+
+        let scheme: ColorScheme = serde_json::from_str("\"high-contrast\"").unwrap();
+        assert_eq!(scheme, ColorScheme::HighContrast);
+    }
+
+    #[test]
+    fn test_colorize_tag_disabled() {
+        let options = PrintOptions {
+            colored: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        // When color is disabled, should return plain text
+        let result = printer.colorize_tag("TODO", Priority::Medium);
+        assert_eq!(result, "TODO");
+    }
+
+    #[test]
+    fn test_highlight_occurrences() {
+        let brackets = |text: &str| format!("[{}]", text);
+
+        assert_eq!(
+            highlight_occurrences("this todo blocks the TODO list", "TODO", brackets),
+            "this [todo] blocks the [TODO] list"
+        );
+        assert_eq!(
+            highlight_occurrences("café FIXME", "fixme", brackets),
+            "café [FIXME]"
+        );
+        assert_eq!(highlight_occurrences("nothing", "BUG", brackets), "nothing");
+    }
+
+    #[test]
+    fn test_colorize_message_disabled() {
+        let printer = Printer::new(PrintOptions {
+            highlight_matches: false,
+            ..Default::default()
+        });
+        assert_eq!(
+            printer.colorize_message("this TODO blocks", "TODO"),
+            "this TODO blocks"
+        );
+
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            highlight_matches: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            printer.colorize_message("this TODO blocks", "TODO"),
+            "this TODO blocks"
+        );
+    }
+
+    #[test]
+    fn test_make_clickable_link_disabled() {
+        let options = PrintOptions {
+            clickable_links: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let path = PathBuf::from("/test/file.rs");
+        let result = printer.make_clickable_link(&path, 10);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_make_line_link_disabled() {
+        let options = PrintOptions {
+            clickable_links: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let path = PathBuf::from("/test/file.rs");
+        let result = printer.make_line_link(&path, 10);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_make_line_link_forced() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("file.rs");
+        std::fs::write(&path, "// TODO: test\n").unwrap();
+
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            force_hyperlinks: true,
+            ..Default::default()
+        });
+
+        let link = printer.make_line_link(&path, 10).unwrap();
+        assert!(link.starts_with("\x1b]8;;file://"));
+        assert!(link.contains(":10\x1b\\L10\x1b]8;;"));
+    }
+
+    #[test]
+    fn test_print_tree_with_author() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/src/main.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "With author".to_string(),
+                line: 10,
+                column: 5,
+                line_content: Some("// TODO(alice): With author".to_string()),
+                author: Some("alice".to_string()),
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
+        let options = PrintOptions {
+            colored: false,
+            clickable_links: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("(alice)"));
+        assert!(output_str.contains("With author"));
+    }
+
+    #[test]
+    fn test_print_flat_sorts_across_files() {
+        let item = |message: &str, line| TodoItem {
+            tag: "TODO".to_string(),
+            message: message.to_string(),
+            line,
+            column: 4,
+            line_content: None,
+            author: None,
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        };
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/a.rs"),
+            vec![item("apple", 1), item("cherry", 2)],
+        );
+        result.add_file(
+            PathBuf::from("/test/b.rs"),
+            vec![item("Banana", 1), item("date", 2)],
+        );
+
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Flat,
+            colored: false,
+            clickable_links: false,
+            show_summary: false,
+            sort: SortOrder::Message,
+            ..Default::default()
+        });
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let messages: Vec<&str> = output
+            .lines()
+            .filter_map(|line| line.rsplit(' ').next())
+            .collect();
+        assert_eq!(messages, vec!["apple", "Banana", "cherry", "date"]);
+    }
+
+    #[test]
+    fn test_print_tree_sort_reverse() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        for (file, message) in [("a.rs", "In A"), ("b.rs", "In B")] {
+            result.add_file(
+                PathBuf::from("/test").join(file),
+                vec![TodoItem {
+                    tag: "TODO".to_string(),
+                    message: message.to_string(),
+                    line: 1,
+                    column: 1,
+                    line_content: None,
+                    author: None,
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                }],
+            );
+        }
+
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            clickable_links: false,
+            show_summary: false,
+            base_path: Some(PathBuf::from("/test")),
+            sort_reverse: true,
+            ..Default::default()
+        });
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.find("In B").unwrap() < output.find("In A").unwrap());
+        assert!(output.contains("├── b.rs"));
+        assert!(output.contains("└── a.rs"));
+    }
+
+    #[test]
+    fn test_print_flat_sort_created_at() {
+        let item = |message: &str, line, created_at| TodoItem {
+            tag: "TODO".to_string(),
+            message: message.to_string(),
+            line,
+            column: 4,
+            line_content: None,
+            author: None,
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at,
+            file_extension: None,
+        };
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/a.rs"),
+            vec![item("newest", 1, Some(300)), item("oldest", 2, Some(100))],
+        );
+        result.add_file(
+            PathBuf::from("/test/b.rs"),
+            vec![item("untracked", 1, None), item("middle", 2, Some(200))],
+        );
+
+        let messages = |sort_reverse| {
+            let printer = Printer::new(PrintOptions {
+                format: OutputFormat::Flat,
+                colored: false,
+                clickable_links: false,
+                show_summary: false,
+                sort: SortOrder::CreatedAt,
+                sort_reverse,
+                ..Default::default()
+            });
+            let mut output = Vec::new();
+            printer.print_to(&mut output, &result).unwrap();
+            String::from_utf8(output)
+                .unwrap()
+                .lines()
+                .filter_map(|line| line.rsplit(' ').next().map(String::from))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(messages(false), ["oldest", "middle", "newest", "untracked"]);
+        assert_eq!(messages(true), ["untracked", "newest", "middle", "oldest"]);
+    }
+
+    #[test]
+    fn test_print_flat_empty() {
+        let result = ScanResult::new(PathBuf::from("/test"));
+        let options = PrintOptions {
+            format: OutputFormat::Flat,
+            colored: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("No TODO items found"));
+    }
+
+    #[test]
+    fn test_format_path_no_base() {
+        let options = PrintOptions {
+            full_paths: false,
+            base_path: None,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let path = PathBuf::from("/test/src/main.rs");
+        let formatted = printer.format_path(&path);
+        assert_eq!(formatted, "/test/src/main.rs");
+    }
+
+    #[test]
+    fn test_format_path_strip_prefix_fails() {
+        let options = PrintOptions {
+            full_paths: false,
+            base_path: Some(PathBuf::from("/different/base")),
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let path = PathBuf::from("/test/src/main.rs");
+        let formatted = printer.format_path(&path);
+        // Should fall back to full path when strip_prefix fails
+        assert_eq!(formatted, "/test/src/main.rs");
+    }
+
+    #[test]
+    fn test_json_output_with_author() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/src/main.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "Test".to_string(),
+                line: 10,
+                column: 5,
+                line_content: Some("// TODO(bob): Test".to_string()),
+                author: Some("bob".to_string()),
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
+        let options = PrintOptions::default();
+        let json_output = JsonOutput::from_scan_result(&result, &options);
+
+        assert_eq!(
+            json_output.files[0].items[0].author,
+            Some("bob".to_string())
+        );
+    }
+
+    #[test]
+    fn test_print_summary_with_multiple_tags() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/a.rs"),
+            vec![
+                TodoItem {
+                    tag: "TODO".to_string(),
+                    message: "First".to_string(),
+                    line: 1,
+                    column: 1,
+                    line_content: Some("// TODO: First".to_string()),
+                    author: None,
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "FIXME".to_string(),
+                    message: "Second".to_string(),
+                    line: 2,
+                    column: 1,
+                    line_content: Some("// FIXME: Second".to_string()),
+                    author: None,
+                    priority: Priority::Critical,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "NOTE".to_string(),
+                    message: "Third".to_string(),
+                    line: 3,
+                    column: 1,
+                    line_content: Some("// NOTE: Third".to_string()),
+                    author: None,
+                    priority: Priority::Low,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+            ],
+        );
+
+        let options = PrintOptions {
+            colored: false,
+            clickable_links: false,
+            show_summary: true,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("Found 3 TODO items"));
+        assert!(output_str.contains("TODO:"));
+        assert!(output_str.contains("FIXME:"));
+        assert!(output_str.contains("NOTE:"));
+    }
+
+    #[test]
+    fn test_print_tree_multiple_files() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/a.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "In A".to_string(),
+                line: 1,
+                column: 1,
+                line_content: Some("// TODO: In A".to_string()),
+                author: None,
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+        result.add_file(
+            PathBuf::from("/test/b.rs"),
+            vec![TodoItem {
+                tag: "FIXME".to_string(),
+                message: "In B".to_string(),
+                line: 1,
+                column: 1,
+                line_content: Some("// FIXME: In B".to_string()),
+                author: None,
+                priority: Priority::Critical,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
+        let options = PrintOptions {
+            colored: false,
+            clickable_links: false,
+            show_summary: false,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("In A"));
+        assert!(output_str.contains("In B"));
+        // Check tree structure characters
+        assert!(output_str.contains("├──") || output_str.contains("└──"));
+    }
+
+    #[test]
+    fn test_print_tree_colored() {
+        let result = create_test_result();
+        let options = PrintOptions {
+            colored: true,
+            clickable_links: false,
+            show_summary: true,
+            ..Default::default()
         };
+        let printer = Printer::new(options);
 
-        Self { files, summary }
-    }
-}
-
-/// Check if the terminal supports hyperlinks (OSC 8)
-fn supports_hyperlinks() -> bool {
-    // Check common environment variables that indicate hyperlink support
-    if let Ok(term_program) = std::env::var("TERM_PROGRAM") {
-        let supported_terminals = [
-            "iTerm.app",
-            "WezTerm",
-            "Hyper",
-            "Tabby",
-            "Alacritty",
-            "vscode",
-            "VSCodium",
-        ];
-        if supported_terminals.iter().any(|t| term_program.contains(t)) {
-            return true;
-        }
+        let mut output = Vec::new();
+        // Should not panic with colored output
+        printer.print_to(&mut output, &result).unwrap();
+        assert!(!output.is_empty());
     }
 
-    // Check for explicit hyperlink support
-    if let Ok(colorterm) = std::env::var("COLORTERM")
-        && (colorterm == "truecolor" || colorterm == "24bit")
-    {
-        // Modern terminals with truecolor often support hyperlinks
-        return true;
-    }
+    #[test]
+    fn test_print_flat_colored() {
+        let result = create_test_result();
+        let options = PrintOptions {
+            format: OutputFormat::Flat,
+            colored: true,
+            clickable_links: false,
+            show_summary: true,
+            ..Default::default()
+        };
+        let printer = Printer::new(options);
 
-    // Check VTE version (GNOME Terminal and derivatives)
-    if std::env::var("VTE_VERSION").is_ok() {
-        return true;
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+        assert!(!output.is_empty());
     }
 
-    // Check for Konsole
-    if std::env::var("KONSOLE_VERSION").is_ok() {
-        return true;
-    }
+    #[test]
+    fn test_json_item_priority() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/file.rs"),
+            vec![
+                TodoItem {
+                    tag: "BUG".to_string(),
+                    message: "Critical".to_string(),
+                    line: 1,
+                    column: 1,
+                    line_content: Some("// BUG: Critical".to_string()),
+                    author: None,
+                    priority: Priority::Critical,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "NOTE".to_string(),
+                    message: "Low".to_string(),
+                    line: 2,
+                    column: 1,
+                    line_content: Some("// NOTE: Low".to_string()),
+                    author: None,
+                    priority: Priority::Low,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+            ],
+        );
 
-    // Default to false for unknown terminals
-    false
-}
+        let options = PrintOptions::default();
+        let json_output = JsonOutput::from_scan_result(&result, &options);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Check priority values in JSON output (priority is serialized as a string)
+        let items = &json_output.files[0].items;
+        assert!(items.iter().any(|i| i.priority == "Critical"));
+        assert!(items.iter().any(|i| i.priority == "Low"));
+    }
 
-    fn create_test_result() -> ScanResult {
+    #[test]
+    fn test_group_by_tag_multiple_tags() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
         result.add_file(
-            PathBuf::from("/test/src/main.rs"),
+            PathBuf::from("/test/file.rs"),
             vec![
                 TodoItem {
                     tag: "TODO".to_string(),
-                    message: "Implement feature".to_string(),
-                    line: 10,
-                    column: 5,
-                    line_content: Some("// TODO: Implement feature".to_string()),
+                    message: "First TODO".to_string(),
+                    line: 1,
+                    column: 1,
+                    line_content: Some("// TODO: First TODO".to_string()),
                     author: None,
                     priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
                 },
                 TodoItem {
                     tag: "FIXME".to_string(),
-                    message: "Fix this bug".to_string(),
-                    line: 20,
-                    column: 5,
-                    line_content: Some("// FIXME: Fix this bug".to_string()),
-                    author: Some("john".to_string()),
+                    message: "A FIXME".to_string(),
+                    line: 2,
+                    column: 1,
+                    line_content: Some("// FIXME: A FIXME".to_string()),
+                    author: None,
                     priority: Priority::Critical,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "TODO".to_string(),
+                    message: "Second TODO".to_string(),
+                    line: 3,
+                    column: 1,
+                    line_content: Some("// TODO: Second TODO".to_string()),
+                    author: None,
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
                 },
             ],
         );
-        result
-    }
 
-    #[test]
-    fn test_print_tree() {
-        let result = create_test_result();
         let options = PrintOptions {
             colored: false,
             clickable_links: false,
+            group_by_tag: true,
+            show_summary: false,
             ..Default::default()
         };
         let printer = Printer::new(options);
@@ -663,129 +3573,196 @@ mod tests {
         printer.print_to(&mut output, &result).unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        assert!(output_str.contains("TODO"));
-        assert!(output_str.contains("FIXME"));
-        assert!(output_str.contains("Implement feature"));
-        assert!(output_str.contains("Fix this bug"));
+        // Both tags should appear as headers
+        assert!(output_str.contains("TODO (2)"));
+        assert!(output_str.contains("FIXME (1)"));
     }
 
     #[test]
-    fn test_print_flat() {
+    fn test_print_to_stdout() {
         let result = create_test_result();
         let options = PrintOptions {
-            format: OutputFormat::Flat,
             colored: false,
             clickable_links: false,
             ..Default::default()
         };
         let printer = Printer::new(options);
 
-        let mut output = Vec::new();
-        printer.print_to(&mut output, &result).unwrap();
-
-        let output_str = String::from_utf8(output).unwrap();
-        assert!(output_str.contains(":10:5"));
-        assert!(output_str.contains(":20:5"));
+        // Test that print() works (writes to stdout)
+        // We can't easily capture stdout, but we can verify it doesn't panic
+        // The actual test is done via print_to with a buffer
+        let mut buffer = Vec::new();
+        let result_io = printer.print_to(&mut buffer, &result);
+        assert!(result_io.is_ok());
     }
 
     #[test]
-    fn test_print_json() {
-        let result = create_test_result();
-        let options = PrintOptions {
-            format: OutputFormat::Json,
-            colored: false,
-            ..Default::default()
-        };
-        let printer = Printer::new(options);
+    fn test_output_format_enum() {
+        // Test that OutputFormat variants work correctly
+        let tree = OutputFormat::Tree;
+        let flat = OutputFormat::Flat;
+        let json = OutputFormat::Json;
 
-        let mut output = Vec::new();
-        printer.print_to(&mut output, &result).unwrap();
+        // Just verify they can be used in match expressions
+        match tree {
+            OutputFormat::Tree => {}
+            _ => panic!("Expected Tree"),
+        }
+        match flat {
+            OutputFormat::Flat => {}
+            _ => panic!("Expected Flat"),
+        }
+        match json {
+            OutputFormat::Json => {}
+            _ => panic!("Expected Json"),
+        }
+    }
 
-        let output_str = String::from_utf8(output).unwrap();
+    #[test]
+    fn test_print_options_default_values() {
+        let options = PrintOptions::default();
 
-        // Should be valid JSON
-        let parsed: serde_json::Value = serde_json::from_str(&output_str).unwrap();
-        assert!(parsed.get("files").is_some());
-        assert!(parsed.get("summary").is_some());
+        assert!(matches!(options.format, OutputFormat::Tree));
+        assert!(options.colored);
+        assert!(options.show_line_numbers);
+        assert!(!options.full_paths);
+        assert!(options.clickable_links);
+        assert!(options.base_path.is_none());
+        assert!(options.show_summary);
+        assert!(!options.group_by_tag);
+        assert_eq!(options.compact_message_max, 40);
     }
 
     #[test]
-    fn test_empty_result() {
-        let result = ScanResult::new(PathBuf::from("/test"));
-        let options = PrintOptions {
-            colored: false,
-            ..Default::default()
-        };
-        let printer = Printer::new(options);
-
-        let mut output = Vec::new();
-        printer.print_to(&mut output, &result).unwrap();
+    fn test_json_summary_fields() {
+        let result = create_test_result();
+        let options = PrintOptions::default();
+        let json_output = JsonOutput::from_scan_result(&result, &options);
 
-        let output_str = String::from_utf8(output).unwrap();
-        assert!(output_str.contains("No TODO items found"));
+        assert_eq!(json_output.summary.total_count, result.summary.total_count);
+        assert_eq!(
+            json_output.summary.files_with_todos,
+            result.summary.files_with_todos
+        );
+        assert_eq!(
+            json_output.summary.files_scanned,
+            result.summary.files_scanned
+        );
+        assert!(!json_output.summary.tag_counts.is_empty());
     }
 
     #[test]
-    fn test_colorize_tag() {
+    fn test_json_file_entry_path() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/deep/nested/file.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "Test".to_string(),
+                line: 1,
+                column: 1,
+                line_content: Some("// TODO: Test".to_string()),
+                author: None,
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
         let options = PrintOptions {
-            colored: true,
+            full_paths: false,
+            base_path: Some(PathBuf::from("/test")),
             ..Default::default()
         };
-        let printer = Printer::new(options);
+        let json_output = JsonOutput::from_scan_result(&result, &options);
 
-        // Just verify it doesn't panic
-        let _ = printer.colorize_tag("TODO");
-        let _ = printer.colorize_tag("FIXME");
-        let _ = printer.colorize_tag("BUG");
-        let _ = printer.colorize_tag("NOTE");
+        // Path should be relative when base_path is set
+        assert!(json_output.files[0].path.contains("nested"));
     }
 
-    #[test]
-    fn test_format_path() {
+    #[test]
+    fn test_json_file_entry_full_path() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/file.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "Test".to_string(),
+                line: 1,
+                column: 1,
+                line_content: Some("// TODO: Test".to_string()),
+                author: None,
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
         let options = PrintOptions {
-            full_paths: false,
-            base_path: Some(PathBuf::from("/test")),
+            full_paths: true,
             ..Default::default()
         };
-        let printer = Printer::new(options);
+        let json_output = JsonOutput::from_scan_result(&result, &options);
 
-        let path = PathBuf::from("/test/src/main.rs");
-        let formatted = printer.format_path(&path);
-        assert_eq!(formatted, "src/main.rs");
+        // Path should be full when full_paths is true
+        assert!(json_output.files[0].path.starts_with("/test"));
     }
 
     #[test]
-    fn test_format_path_full() {
+    fn test_clickable_links_enabled_no_terminal_support() {
+        // When clickable_links is enabled but terminal doesn't support them
+        // (most test environments), the functions should return None
         let options = PrintOptions {
-            full_paths: true,
+            clickable_links: true,
+            colored: false,
             ..Default::default()
         };
         let printer = Printer::new(options);
 
-        let path = PathBuf::from("/test/src/main.rs");
-        let formatted = printer.format_path(&path);
-        assert_eq!(formatted, "/test/src/main.rs");
-    }
+        // Create a real file to test with
+        use tempfile::TempDir;
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.rs");
+        std::fs::write(&file_path, "// TODO: test").unwrap();
 
-    #[test]
-    fn test_json_output_structure() {
-        let result = create_test_result();
-        let options = PrintOptions::default();
-        let json_output = JsonOutput::from_scan_result(&result, &options);
+        // These may return None if terminal doesn't support hyperlinks
+        let link = printer.make_clickable_link(&file_path, 1);
+        let line_link = printer.make_line_link(&file_path, 1);
 
-        assert_eq!(json_output.summary.total_count, 2);
-        assert_eq!(json_output.summary.files_with_todos, 1);
-        assert_eq!(json_output.files.len(), 1);
-        assert_eq!(json_output.files[0].items.len(), 2);
+        // In most test environments, these will be None
+        // but we just verify they don't panic
+        let _ = link;
+        let _ = line_link;
     }
 
     #[test]
-    fn test_print_no_summary() {
-        let result = create_test_result();
+    fn test_tree_item_with_last_file_and_item() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/only_file.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "Only item".to_string(),
+                line: 1,
+                column: 1,
+                line_content: Some("// TODO: Only item".to_string()),
+                author: None,
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
         let options = PrintOptions {
             colored: false,
-            show_summary: false,
             clickable_links: false,
+            show_summary: false,
             ..Default::default()
         };
         let printer = Printer::new(options);
@@ -794,17 +3771,33 @@ mod tests {
         printer.print_to(&mut output, &result).unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        assert!(!output_str.contains("Found"));
-        assert!(!output_str.contains("files scanned"));
+        // Last file uses └── prefix
+        assert!(output_str.contains("└──"));
     }
 
     #[test]
-    fn test_group_by_tag() {
-        let result = create_test_result();
+    fn test_print_tree_item_with_colored_author() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/file.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "With colored author".to_string(),
+                line: 1,
+                column: 1,
+                line_content: Some("// TODO(developer): With colored author".to_string()),
+                author: Some("developer".to_string()),
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
         let options = PrintOptions {
-            colored: false,
+            colored: true,
             clickable_links: false,
-            group_by_tag: true,
             show_summary: false,
             ..Default::default()
         };
@@ -813,69 +3806,84 @@ mod tests {
         let mut output = Vec::new();
         printer.print_to(&mut output, &result).unwrap();
 
-        let output_str = String::from_utf8(output).unwrap();
-        // Should have tag headers
-        assert!(output_str.contains("FIXME (1)") || output_str.contains("TODO (1)"));
+        // Should not panic and should produce output
+        assert!(!output.is_empty());
     }
 
     #[test]
-    fn test_colorize_tag_disabled() {
-        let options = PrintOptions {
+    fn test_print_table() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Table,
             colored: false,
+            clickable_links: false,
+            show_summary: false,
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
-        // When color is disabled, should return plain text
-        let result = printer.colorize_tag("TODO");
-        assert_eq!(result, "TODO");
-    }
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
 
-    #[test]
-    fn test_make_clickable_link_disabled() {
-        let options = PrintOptions {
-            clickable_links: false,
-            ..Default::default()
-        };
-        let printer = Printer::new(options);
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+        assert!(lines[0].starts_with("+-"));
+        assert!(lines[1].contains("| File"));
+        assert!(lines[1].contains("| Message"));
+        assert!(output_str.contains("| TODO"));
+        assert!(output_str.contains("| john"));
+        assert!(output_str.contains("Fix this bug"));
+        assert!(!output_str.contains('│'));
 
-        let path = PathBuf::from("/test/file.rs");
-        let result = printer.make_clickable_link(&path, 10);
-        assert!(result.is_none());
+        // Every row has the same width
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
     }
 
     #[test]
-    fn test_make_line_link_disabled() {
-        let options = PrintOptions {
+    fn test_print_table_unicode_borders() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Table,
+            colored: true,
             clickable_links: false,
+            show_summary: false,
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
-        let path = PathBuf::from("/test/file.rs");
-        let result = printer.make_line_link(&path, 10);
-        assert!(result.is_none());
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.starts_with('┌'));
+        assert!(output_str.contains('│'));
     }
 
     #[test]
-    fn test_print_tree_with_author() {
+    fn test_print_compact() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
         result.add_file(
             PathBuf::from("/test/src/main.rs"),
             vec![TodoItem {
                 tag: "TODO".to_string(),
-                message: "With author".to_string(),
+                message: "Refactor the authentication flow so it supports OAuth".to_string(),
                 line: 10,
                 column: 5,
-                line_content: Some("// TODO(alice): With author".to_string()),
-                author: Some("alice".to_string()),
+                line_content: None,
+                author: None,
                 priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
             }],
         );
 
         let options = PrintOptions {
+            format: OutputFormat::Compact,
             colored: false,
             clickable_links: false,
+            show_summary: false,
+            compact_message_max: 20,
             ..Default::default()
         };
         let printer = Printer::new(options);
@@ -884,16 +3892,17 @@ mod tests {
         printer.print_to(&mut output, &result).unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        assert!(output_str.contains("(alice)"));
-        assert!(output_str.contains("With author"));
+        assert_eq!(output_str, "main.rs:10 TODO Refactor the authent…\n");
     }
 
     #[test]
-    fn test_print_flat_empty() {
-        let result = ScanResult::new(PathBuf::from("/test"));
+    fn test_print_delta() {
+        let result = create_test_result();
         let options = PrintOptions {
-            format: OutputFormat::Flat,
+            format: OutputFormat::Delta,
             colored: false,
+            clickable_links: false,
+            base_path: Some(PathBuf::from("/test")),
             ..Default::default()
         };
         let printer = Printer::new(options);
@@ -902,490 +3911,890 @@ mod tests {
         printer.print_to(&mut output, &result).unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        assert!(output_str.contains("No TODO items found"));
+        assert_eq!(
+            output_str,
+            "diff --git a/src/main.rs b/src/main.rs\n\
+             --- /dev/null\n\
+             +++ b/src/main.rs\n\
+             @@ -0,0 +1,2 @@\n\
+             +// TODO: Implement feature\n\
+             +// FIXME: Fix this bug\n"
+        );
     }
 
     #[test]
-    fn test_format_path_no_base() {
-        let options = PrintOptions {
-            full_paths: false,
-            base_path: None,
+    fn test_print_org_mode() {
+        let mut result = create_test_result();
+        result.add_file(
+            PathBuf::from("/test/lib.rs"),
+            vec![TodoItem {
+                tag: "WON'T-FIX".to_string(),
+                message: "Document".to_string(),
+                line: 3,
+                column: 1,
+                line_content: None,
+                author: Some("alice".to_string()),
+                priority: Priority::Low,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::OrgMode,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
+            title: Some("Audit".to_string()),
             ..Default::default()
-        };
-        let printer = Printer::new(options);
-
-        let path = PathBuf::from("/test/src/main.rs");
-        let formatted = printer.format_path(&path);
-        assert_eq!(formatted, "/test/src/main.rs");
-    }
+        });
 
-    #[test]
-    fn test_format_path_strip_prefix_fails() {
-        let options = PrintOptions {
-            full_paths: false,
-            base_path: Some(PathBuf::from("/different/base")),
-            ..Default::default()
-        };
-        let printer = Printer::new(options);
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
 
-        let path = PathBuf::from("/test/src/main.rs");
-        let formatted = printer.format_path(&path);
-        // Should fall back to full path when strip_prefix fails
-        assert_eq!(formatted, "/test/src/main.rs");
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output_str,
+            "#+TITLE: Audit\n\
+             \n\
+             * File: lib.rs\n\
+             ** TODO [#D] Document :WON_T_FIX:\n\
+             :PROPERTIES:\n\
+             :AUTHOR: alice\n\
+             :END:\n\
+             [[file:lib.rs::3][lib.rs:3]]\n\
+             * File: src/main.rs\n\
+             ** TODO [#C] Implement feature :TODO:\n\
+             [[file:src/main.rs::10][src/main.rs:10]]\n\
+             ** TODO [#A] Fix this bug :FIXME:\n\
+             :PROPERTIES:\n\
+             :AUTHOR: john\n\
+             :END:\n\
+             [[file:src/main.rs::20][src/main.rs:20]]\n"
+        );
     }
 
     #[test]
-    fn test_json_output_with_author() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
+    fn test_print_tap() {
+        let mut result = create_test_result();
         result.add_file(
-            PathBuf::from("/test/src/main.rs"),
+            PathBuf::from("/test/lib.rs"),
             vec![TodoItem {
-                tag: "TODO".to_string(),
-                message: "Test".to_string(),
-                line: 10,
-                column: 5,
-                line_content: Some("// TODO(bob): Test".to_string()),
-                author: Some("bob".to_string()),
-                priority: Priority::Medium,
+                tag: "HACK".to_string(),
+                message: r"Remove after #42 from C:\tmp".to_string(),
+                line: 3,
+                column: 1,
+                line_content: None,
+                author: None,
+                priority: Priority::High,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
             }],
         );
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Tap,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
+            fail_above: Some(Priority::Medium),
+            ..Default::default()
+        });
 
-        let options = PrintOptions::default();
-        let json_output = JsonOutput::from_scan_result(&result, &options);
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
 
+        let output_str = String::from_utf8(output).unwrap();
         assert_eq!(
-            json_output.files[0].items[0].author,
-            Some("bob".to_string())
+            output_str,
+            "TAP version 13\n\
+             1..3\n\
+             not ok 1 - lib.rs:3 HACK: Remove after \\#42 from C:\\\\tmp\n\
+             ok 2 - src/main.rs:10 TODO: Implement feature\n\
+             not ok 3 - src/main.rs:20 FIXME: Fix this bug\n"
         );
     }
 
     #[test]
-    fn test_print_summary_with_multiple_tags() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
-        result.add_file(
-            PathBuf::from("/test/a.rs"),
-            vec![
-                TodoItem {
-                    tag: "TODO".to_string(),
-                    message: "First".to_string(),
-                    line: 1,
-                    column: 1,
-                    line_content: Some("// TODO: First".to_string()),
-                    author: None,
-                    priority: Priority::Medium,
-                },
-                TodoItem {
-                    tag: "FIXME".to_string(),
-                    message: "Second".to_string(),
-                    line: 2,
-                    column: 1,
-                    line_content: Some("// FIXME: Second".to_string()),
-                    author: None,
-                    priority: Priority::Critical,
-                },
-                TodoItem {
-                    tag: "NOTE".to_string(),
-                    message: "Third".to_string(),
-                    line: 3,
-                    column: 1,
-                    line_content: Some("// NOTE: Third".to_string()),
-                    author: None,
-                    priority: Priority::Low,
-                },
-            ],
-        );
-
-        let options = PrintOptions {
-            colored: false,
-            clickable_links: false,
-            show_summary: true,
+    fn test_print_tap_without_threshold() {
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Tap,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
         let mut output = Vec::new();
-        printer.print_to(&mut output, &result).unwrap();
+        printer
+            .print_to(&mut output, &create_test_result())
+            .unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        assert!(output_str.contains("Found 3 TODO items"));
-        assert!(output_str.contains("TODO:"));
-        assert!(output_str.contains("FIXME:"));
-        assert!(output_str.contains("NOTE:"));
+        assert!(!output_str.contains("not ok"));
+        assert!(output_str.contains("ok 2 - src/main.rs:20 FIXME: Fix this bug"));
     }
 
     #[test]
-    fn test_print_tree_multiple_files() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
-        result.add_file(
-            PathBuf::from("/test/a.rs"),
-            vec![TodoItem {
-                tag: "TODO".to_string(),
-                message: "In A".to_string(),
-                line: 1,
-                column: 1,
-                line_content: Some("// TODO: In A".to_string()),
-                author: None,
-                priority: Priority::Medium,
-            }],
+    fn test_xml_escape() {
+        assert_eq!(xml_escape("a < b & 'c'"), "a &lt; b &amp; &apos;c&apos;");
+        assert_eq!(
+            xml_escape("bell\u{7} esc\u{1b} nul\u{0}"),
+            "bell\u{fffd} esc\u{fffd} nul\u{fffd}"
         );
+        assert_eq!(xml_escape("a\tb\r\nc"), "a&#9;b&#13;&#10;c");
+    }
+
+    #[test]
+    fn test_print_checkstyle() {
+        let mut result = create_test_result();
         result.add_file(
-            PathBuf::from("/test/b.rs"),
+            PathBuf::from("/test/lib.rs"),
             vec![TodoItem {
-                tag: "FIXME".to_string(),
-                message: "In B".to_string(),
-                line: 1,
+                tag: "NOTE".to_string(),
+                message: "Use <T> & \"quotes\"".to_string(),
+                line: 3,
                 column: 1,
-                line_content: Some("// FIXME: In B".to_string()),
+                line_content: None,
                 author: None,
-                priority: Priority::Critical,
+                priority: Priority::Low,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
             }],
         );
-
-        let options = PrintOptions {
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Checkstyle,
             colored: false,
-            clickable_links: false,
-            show_summary: false,
+            base_path: Some(PathBuf::from("/test")),
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
         let mut output = Vec::new();
         printer.print_to(&mut output, &result).unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        assert!(output_str.contains("In A"));
-        assert!(output_str.contains("In B"));
-        // Check tree structure characters
-        assert!(output_str.contains("├──") || output_str.contains("└──"));
+        assert_eq!(
+            output_str,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="lib.rs">
+    <error line="3" column="1" severity="info" message="Use &lt;T&gt; &amp; &quot;quotes&quot;" source="todo-tree.NOTE"/>
+  </file>
+  <file name="src/main.rs">
+    <error line="10" column="5" severity="info" message="Implement feature" source="todo-tree.TODO"/>
+    <error line="20" column="5" severity="error" message="Fix this bug" source="todo-tree.FIXME"/>
+  </file>
+</checkstyle>
+"#
+        );
     }
 
     #[test]
-    fn test_print_tree_colored() {
+    fn test_print_xunit() {
         let result = create_test_result();
-        let options = PrintOptions {
-            colored: true,
-            clickable_links: false,
-            show_summary: true,
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::XUnit,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
         let mut output = Vec::new();
-        // Should not panic with colored output
         printer.print_to(&mut output, &result).unwrap();
-        assert!(!output.is_empty());
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output_str,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<assemblies>
+  <assembly name="todo-tree" test-framework="todo-tree" total="2" passed="0" failed="1" skipped="1" errors="0" time="0">
+    <collection name="src/main.rs" total="2" passed="0" failed="1" skipped="1" time="0">
+      <test name="src/main.rs:10 TODO: Implement feature" type="src/main.rs" method="TODO" time="0" result="Skip">
+        <reason>Implement feature</reason>
+      </test>
+      <test name="src/main.rs:20 FIXME: Fix this bug" type="src/main.rs" method="FIXME" time="0" result="Fail">
+        <failure><message>Fix this bug</message></failure>
+      </test>
+    </collection>
+  </assembly>
+</assemblies>
+"#
+        );
     }
 
     #[test]
-    fn test_print_flat_colored() {
-        let result = create_test_result();
-        let options = PrintOptions {
-            format: OutputFormat::Flat,
-            colored: true,
-            clickable_links: false,
-            show_summary: true,
+    fn test_print_dot() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/src/lib.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "Depends on #42".to_string(),
+                line: 7,
+                column: 1,
+                line_content: None,
+                author: None,
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Dot,
+            colored: false,
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
         let mut output = Vec::new();
         printer.print_to(&mut output, &result).unwrap();
-        assert!(!output.is_empty());
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.starts_with("digraph todos {"));
+        assert!(output_str.contains(r#""todo0" -> "issue42" [label="depends on"];"#));
     }
 
     #[test]
-    fn test_json_item_priority() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
+    fn test_print_markdown() {
+        let mut result = create_test_result();
         result.add_file(
-            PathBuf::from("/test/file.rs"),
-            vec![
-                TodoItem {
-                    tag: "BUG".to_string(),
-                    message: "Critical".to_string(),
-                    line: 1,
-                    column: 1,
-                    line_content: Some("// BUG: Critical".to_string()),
-                    author: None,
-                    priority: Priority::Critical,
-                },
-                TodoItem {
-                    tag: "NOTE".to_string(),
-                    message: "Low".to_string(),
-                    line: 2,
-                    column: 1,
-                    line_content: Some("// NOTE: Low".to_string()),
-                    author: None,
-                    priority: Priority::Low,
-                },
-            ],
+            PathBuf::from("/test/lib.rs"),
+            vec![TodoItem {
+                tag: "NOTE".to_string(),
+                message: "Keep *sync* with `foo_bar`".to_string(),
+                line: 3,
+                column: 1,
+                line_content: None,
+                author: None,
+                priority: Priority::Low,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
         );
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Markdown,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        });
 
-        let options = PrintOptions::default();
-        let json_output = JsonOutput::from_scan_result(&result, &options);
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
 
-        // Check priority values in JSON output (priority is serialized as a string)
-        let items = &json_output.files[0].items;
-        assert!(items.iter().any(|i| i.priority == "Critical"));
-        assert!(items.iter().any(|i| i.priority == "Low"));
-    }
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output_str,
+            r"## lib.rs
 
-    #[test]
-    fn test_group_by_tag_multiple_tags() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
-        result.add_file(
-            PathBuf::from("/test/file.rs"),
-            vec![
-                TodoItem {
-                    tag: "TODO".to_string(),
-                    message: "First TODO".to_string(),
-                    line: 1,
-                    column: 1,
-                    line_content: Some("// TODO: First TODO".to_string()),
-                    author: None,
-                    priority: Priority::Medium,
-                },
-                TodoItem {
-                    tag: "FIXME".to_string(),
-                    message: "A FIXME".to_string(),
-                    line: 2,
-                    column: 1,
-                    line_content: Some("// FIXME: A FIXME".to_string()),
-                    author: None,
-                    priority: Priority::Critical,
-                },
-                TodoItem {
-                    tag: "TODO".to_string(),
-                    message: "Second TODO".to_string(),
-                    line: 3,
-                    column: 1,
-                    line_content: Some("// TODO: Second TODO".to_string()),
-                    author: None,
-                    priority: Priority::Medium,
-                },
-            ],
+- **[NOTE]** Keep \*sync\* with \`foo\_bar\` _(L3)_
+
+## src/main.rs
+
+- **[TODO]** Implement feature _(L10)_
+- **[FIXME]** Fix this bug _(L20)_
+
+### Summary
+
+Found 3 TODO items in 2 files (2 files scanned)
+
+- **FIXME:** 1
+- **NOTE:** 1
+- **TODO:** 1
+"
         );
+    }
 
-        let options = PrintOptions {
+    #[test]
+    fn test_print_markdown_links() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Markdown,
             colored: false,
-            clickable_links: false,
-            group_by_tag: true,
+            base_path: Some(PathBuf::from("/test")),
+            force_hyperlinks: true,
             show_summary: false,
+            title: Some("Open work".to_string()),
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
         let mut output = Vec::new();
         printer.print_to(&mut output, &result).unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        // Both tags should appear as headers
-        assert!(output_str.contains("TODO (2)"));
-        assert!(output_str.contains("FIXME (1)"));
+        assert!(output_str.starts_with("# Open work\n\n## [src/main.rs](src/main.rs)\n"));
+        assert!(output_str.contains("- **[TODO]** Implement feature _([L10](src/main.rs#L10))_\n"));
+        assert!(!output_str.contains('\x1b'));
+        assert!(!output_str.contains("### Summary"));
     }
 
     #[test]
-    fn test_print_to_stdout() {
-        let result = create_test_result();
-        let options = PrintOptions {
+    fn test_print_atlassian() {
+        let mut result = create_test_result();
+        result.add_file(
+            PathBuf::from("/test/lib.rs"),
+            vec![TodoItem {
+                tag: "NOTE".to_string(),
+                message: "Keep [a|b] in *sync*".to_string(),
+                line: 3,
+                column: 1,
+                line_content: None,
+                author: None,
+                priority: Priority::Low,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Atlassian,
             colored: false,
-            clickable_links: false,
+            base_path: Some(PathBuf::from("/test")),
             ..Default::default()
-        };
-        let printer = Printer::new(options);
+        });
 
-        // Test that print() works (writes to stdout)
-        // We can't easily capture stdout, but we can verify it doesn't panic
-        // The actual test is done via print_to with a buffer
-        let mut buffer = Vec::new();
-        let result_io = printer.print_to(&mut buffer, &result);
-        assert!(result_io.is_ok());
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output_str,
+            r"h2. lib.rs
+|| Tag || Line || Priority || Message ||
+| NOTE | 3 | {color:grey}LOW{color} | Keep \[a\|b\] in \*sync\* |
+
+h2. src/main.rs
+|| Tag || Line || Priority || Message ||
+| TODO | 10 | {color:blue}MEDIUM{color} | Implement feature |
+| FIXME | 20 | {color:red}CRITICAL{color} | Fix this bug |
+
+_Found 3 TODO items in 2 files (2 files scanned)_
+"
+        );
     }
 
     #[test]
-    fn test_output_format_enum() {
-        // Test that OutputFormat variants work correctly
-        let tree = OutputFormat::Tree;
-        let flat = OutputFormat::Flat;
-        let json = OutputFormat::Json;
+    fn test_print_csv() {
+        let mut result = create_test_result();
+        result
+            .files_map
+            .get_mut(&PathBuf::from("/test/src/main.rs"))
+            .unwrap()[0]
+            .message = "Handle \"a, b\"".to_string();
+        let printer = |strip_author| {
+            Printer::new(PrintOptions {
+                format: OutputFormat::CsvWithHeader,
+                colored: false,
+                base_path: Some(PathBuf::from("/test")),
+                strip_author,
+                ..Default::default()
+            })
+        };
 
-        // Just verify they can be used in match expressions
-        match tree {
-            OutputFormat::Tree => {}
-            _ => panic!("Expected Tree"),
-        }
-        match flat {
-            OutputFormat::Flat => {}
-            _ => panic!("Expected Flat"),
-        }
-        match json {
-            OutputFormat::Json => {}
-            _ => panic!("Expected Json"),
-        }
+        let mut output = Vec::new();
+        printer(false).print_to(&mut output, &result).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "file,line,column,tag,priority,author,message\n\
+             src/main.rs,10,5,TODO,Medium,,\"Handle \"\"a, b\"\"\"\n\
+             src/main.rs,20,5,FIXME,Critical,john,Fix this bug\n"
+        );
+
+        let mut output = Vec::new();
+        printer(true).print_to(&mut output, &result).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.starts_with("file,line,column,tag,priority,message\n"));
+        assert!(output_str.contains("\nsrc/main.rs,20,5,FIXME,Critical,Fix this bug\n"));
     }
 
     #[test]
-    fn test_print_options_default_values() {
-        let options = PrintOptions::default();
+    fn test_print_tsv() {
+        let mut result = create_test_result();
+        result
+            .files_map
+            .get_mut(&PathBuf::from("/test/src/main.rs"))
+            .unwrap()[1]
+            .message = "Fix\tthis, bug".to_string();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::TsvWithHeader,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        });
 
-        assert!(matches!(options.format, OutputFormat::Tree));
-        assert!(options.colored);
-        assert!(options.show_line_numbers);
-        assert!(!options.full_paths);
-        assert!(options.clickable_links);
-        assert!(options.base_path.is_none());
-        assert!(options.show_summary);
-        assert!(!options.group_by_tag);
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.starts_with("file\tline\tcolumn\ttag\tpriority\tauthor\tmessage\n"));
+        assert!(output_str.ends_with("src/main.rs\t20\t5\tFIXME\tCritical\tjohn\tFix this, bug\n"));
     }
 
-    #[test]
-    fn test_json_summary_fields() {
-        let result = create_test_result();
-        let options = PrintOptions::default();
-        let json_output = JsonOutput::from_scan_result(&result, &options);
+    #[test]
+    fn test_print_sonarqube() {
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::SonarQube,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer
+            .print_to(&mut output, &create_test_result())
+            .unwrap();
 
-        assert_eq!(json_output.summary.total_count, result.summary.total_count);
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let issues = json["issues"].as_array().unwrap();
+        assert_eq!(issues.len(), 2);
         assert_eq!(
-            json_output.summary.files_with_todos,
-            result.summary.files_with_todos
-        );
-        assert_eq!(
-            json_output.summary.files_scanned,
-            result.summary.files_scanned
+            issues[1],
+            serde_json::json!({
+                "engineId": "todo-tree",
+                "ruleId": "FIXME",
+                "severity": "BLOCKER",
+                "type": "CODE_SMELL",
+                "primaryLocation": {
+                    "message": "FIXME: Fix this bug",
+                    "filePath": "src/main.rs",
+                    "textRange": { "startLine": 20, "startColumn": 4 },
+                },
+            })
         );
-        assert!(!json_output.summary.tag_counts.is_empty());
+        assert_eq!(issues[0]["severity"], "MAJOR");
     }
 
     #[test]
-    fn test_json_file_entry_path() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
+    fn test_sonarqube_severity() {
+        assert_eq!(sonarqube_severity(Priority::High), "CRITICAL");
+        assert_eq!(sonarqube_severity(Priority::Low), "INFO");
+    }
+
+    #[test]
+    fn test_print_teamcity() {
+        let mut result = create_test_result();
         result.add_file(
-            PathBuf::from("/test/deep/nested/file.rs"),
+            PathBuf::from("/test/lib.rs"),
             vec![TodoItem {
-                tag: "TODO".to_string(),
-                message: "Test".to_string(),
-                line: 1,
+                tag: "NOTE".to_string(),
+                message: "Don't use [x]|y".to_string(),
+                line: 3,
                 column: 1,
-                line_content: Some("// TODO: Test".to_string()),
+                line_content: None,
                 author: None,
-                priority: Priority::Medium,
+                priority: Priority::Low,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
             }],
         );
-
-        let options = PrintOptions {
-            full_paths: false,
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::TeamCity,
+            colored: false,
             base_path: Some(PathBuf::from("/test")),
             ..Default::default()
-        };
-        let json_output = JsonOutput::from_scan_result(&result, &options);
+        });
 
-        // Path should be relative when base_path is set
-        assert!(json_output.files[0].path.contains("nested"));
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output_str,
+            "##teamcity[buildProblem description='TODO count: 3']\n\
+             ##teamcity[message text='NOTE: Don|'t use |[x|]||y' errorDetails='lib.rs:3:1' status='NORMAL']\n\
+             ##teamcity[message text='TODO: Implement feature' errorDetails='src/main.rs:10:5' status='NORMAL']\n\
+             ##teamcity[message text='FIXME: Fix this bug' errorDetails='src/main.rs:20:5' status='WARNING']\n"
+        );
+
+        let mut output = Vec::new();
+        printer
+            .print_to(&mut output, &ScanResult::new(PathBuf::from("/test")))
+            .unwrap();
+        assert!(output.is_empty());
     }
 
     #[test]
-    fn test_json_file_entry_full_path() {
+    fn test_print_context_lines() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
         result.add_file(
-            PathBuf::from("/test/file.rs"),
+            PathBuf::from("/test/main.rs"),
             vec![TodoItem {
                 tag: "TODO".to_string(),
-                message: "Test".to_string(),
-                line: 1,
-                column: 1,
-                line_content: Some("// TODO: Test".to_string()),
+                message: "Handle errors".to_string(),
+                line: 3,
+                column: 5,
+                line_content: Some("    // TODO: Handle errors".to_string()),
                 author: None,
                 priority: Priority::Medium,
+                context_before: vec!["fn main() {".to_string(), "    let x = 1;".to_string()],
+                context_after: vec!["    run(x);".to_string()],
+                created_at: None,
+                file_extension: None,
             }],
         );
 
-        let options = PrintOptions {
-            full_paths: true,
-            ..Default::default()
+        let print = |format, before_context, after_context| {
+            let printer = Printer::new(PrintOptions {
+                format,
+                colored: false,
+                clickable_links: false,
+                show_summary: false,
+                base_path: Some(PathBuf::from("/test")),
+                before_context,
+                after_context,
+                ..Default::default()
+            });
+            let mut output = Vec::new();
+            printer.print_to(&mut output, &result).unwrap();
+            String::from_utf8(output).unwrap()
         };
-        let json_output = JsonOutput::from_scan_result(&result, &options);
 
-        // Path should be full when full_paths is true
-        assert!(json_output.files[0].path.starts_with("/test"));
+        let flat = print(OutputFormat::Flat, 1, 1);
+        let lines: Vec<&str> = flat.lines().collect();
+        assert_eq!(lines[0], "main.rs-2-     let x = 1;");
+        assert!(lines[1].starts_with("main.rs:3:5 [TODO] Handle errors"));
+        assert_eq!(lines[2], "main.rs-4-     run(x);");
+        assert!(!flat.contains("fn main()"));
+
+        let tree = print(OutputFormat::Tree, 2, 0);
+        assert!(tree.contains("1- fn main() {"));
+        assert!(!tree.contains("run(x)"));
+
+        let plain = print(OutputFormat::Flat, 0, 0);
+        assert_eq!(plain.lines().count(), 1);
     }
 
     #[test]
-    fn test_clickable_links_enabled_no_terminal_support() {
-        // When clickable_links is enabled but terminal doesn't support them
-        // (most test environments), the functions should return None
-        let options = PrintOptions {
-            clickable_links: true,
+    fn test_print_ctags() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Ctags,
             colored: false,
+            base_path: Some(PathBuf::from("/test")),
             ..Default::default()
-        };
-        let printer = Printer::new(options);
-
-        // Create a real file to test with
-        use tempfile::TempDir;
-        let temp_dir = TempDir::new().unwrap();
-        let file_path = temp_dir.path().join("test.rs");
-        std::fs::write(&file_path, "// TODO: test").unwrap();
+        });
 
-        // These may return None if terminal doesn't support hyperlinks
-        let link = printer.make_clickable_link(&file_path, 1);
-        let line_link = printer.make_line_link(&file_path, 1);
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
 
-        // In most test environments, these will be None
-        // but we just verify they don't panic
-        let _ = link;
-        let _ = line_link;
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[..3].iter().all(|line| line.starts_with("!_TAG_")));
+        assert_eq!(lines[3], "FIXME\tsrc/main.rs\t20;\"\tkind:fixme");
+        assert_eq!(lines[4], "TODO\tsrc/main.rs\t10;\"\tkind:todo");
     }
 
     #[test]
-    fn test_tree_item_with_last_file_and_item() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
-        result.add_file(
-            PathBuf::from("/test/only_file.rs"),
-            vec![TodoItem {
-                tag: "TODO".to_string(),
-                message: "Only item".to_string(),
-                line: 1,
-                column: 1,
-                line_content: Some("// TODO: Only item".to_string()),
-                author: None,
-                priority: Priority::Medium,
-            }],
+    fn test_print_etags() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::CtagsEtags,
+            colored: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let section = "// TODO: Implement feature\x7fTODO\x0110,\n\
+                       // FIXME: Fix this bug\x7fFIXME\x0120,\n";
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("\x0c\nsrc/main.rs,{}\n{}", section.len(), section)
         );
+    }
 
-        let options = PrintOptions {
+    #[test]
+    fn test_message_prefix() {
+        let result = create_test_result();
+        let options = |format| PrintOptions {
+            format,
             colored: false,
             clickable_links: false,
             show_summary: false,
+            message_prefix: Some("[repo] ".to_string()),
             ..Default::default()
         };
-        let printer = Printer::new(options);
+
+        let grouped = [
+            PrintOptions {
+                group_by_tag: true,
+                ..options(OutputFormat::Tree)
+            },
+            PrintOptions {
+                group_by_author: true,
+                ..options(OutputFormat::Tree)
+            },
+        ];
+        let formats = [
+            OutputFormat::Tree,
+            OutputFormat::Flat,
+            OutputFormat::Json,
+            OutputFormat::Compact,
+            OutputFormat::Table,
+            OutputFormat::OrgMode,
+            OutputFormat::Tap,
+            OutputFormat::Checkstyle,
+            OutputFormat::XUnit,
+        ];
+        for options in grouped.into_iter().chain(formats.map(options)) {
+            let mut output = Vec::new();
+            let label = format!(
+                "{:?} by tag {} by author {}",
+                options.format, options.group_by_tag, options.group_by_author
+            );
+            Printer::new(options)
+                .print_to(&mut output, &result)
+                .unwrap();
+            let output_str = String::from_utf8(output).unwrap();
+            assert!(output_str.contains("[repo] Implement feature"), "{}", label);
+            assert!(output_str.contains("[repo] Fix this bug"), "{}", label);
+        }
+
+        let json_output = JsonOutput::from_scan_result(&result, &options(OutputFormat::Json));
+        assert!(
+            json_output.files[0]
+                .items
+                .iter()
+                .all(|item| item.message.starts_with("[repo] "))
+        );
+        assert!(
+            result
+                .iter()
+                .all(|(_, item)| !item.message.starts_with("[repo]"))
+        );
+    }
+
+    #[test]
+    fn test_print_lsp_diagnostics() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::LspDiagnostics,
+            colored: false,
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        let files = json.as_array().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0]["uri"], "file:///test/src/main.rs");
+
+        let diagnostics = files[0]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0]["code"], "TODO");
+        assert_eq!(diagnostics[0]["source"], "todo-tree");
+        assert_eq!(diagnostics[0]["message"], "TODO: Implement feature");
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 9);
+        assert_eq!(diagnostics[1]["severity"], 1);
+    }
+
+    #[test]
+    fn test_print_prometheus() {
+        let mut result = create_test_result();
+        result.summary.files_scanned = 3;
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::Prometheus,
+            colored: false,
+            ..Default::default()
+        });
 
         let mut output = Vec::new();
         printer.print_to(&mut output, &result).unwrap();
 
         let output_str = String::from_utf8(output).unwrap();
-        // Last file uses └── prefix
-        assert!(output_str.contains("└──"));
+        assert!(output_str.contains("# TYPE todo_tree_items gauge\n"));
+        assert!(output_str.contains("todo_tree_items{tag=\"TODO\",priority=\"medium\"} 1\n"));
+        assert!(output_str.contains("todo_tree_items{tag=\"FIXME\",priority=\"critical\"} 1\n"));
+        assert!(output_str.contains("\ntodo_tree_files_with_todos 1\n"));
+        assert!(output_str.contains("\ntodo_tree_files_scanned 3\n"));
     }
 
     #[test]
-    fn test_print_tree_item_with_colored_author() {
-        let mut result = ScanResult::new(PathBuf::from("/test"));
+    fn test_print_open_metrics() {
+        let mut result = create_test_result();
+        result.summary.files_scanned = 3;
+        let printer = Printer::new(PrintOptions {
+            format: OutputFormat::OpenMetrics,
+            colored: false,
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_to(&mut output, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.starts_with("# TYPE todo_tree_items gauge\n"));
+        assert!(output_str.contains("\ntodo_tree_items{tag=\"TODO\",priority=\"medium\"} 1\n"));
+        assert!(output_str.contains("\n# TYPE todo_tree_files_scanned gauge\n"));
+        assert!(output_str.contains("\ntodo_tree_files_scanned 3\n"));
+        assert!(!output_str.contains("_created"));
+        assert!(!output_str.contains("_total"));
+        assert!(output_str.contains("\n# TYPE todo_tree_files_with_todos gauge\n"));
+        assert!(output_str.contains("\ntodo_tree_files_with_todos 1\n"));
+        assert!(output_str.contains("\n# UNIT todo_tree_scan_timestamp_seconds seconds\n"));
+        assert!(output_str.ends_with("\n# EOF\n"));
+    }
+
+    #[test]
+    fn test_prometheus_escape() {
+        assert_eq!(prometheus_escape(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(prometheus_escape("a\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn test_print_diff() {
+        let old = create_test_result();
+
+        // Same project moved elsewhere: FIXME resolved, TODO moved, NOTE added
+        let mut new = ScanResult::new(PathBuf::from("/moved"));
+        new.add_file(
+            PathBuf::from("/moved/src/main.rs"),
+            vec![
+                TodoItem {
+                    tag: "TODO".to_string(),
+                    message: "Implement feature".to_string(),
+                    line: 12,
+                    column: 5,
+                    line_content: None,
+                    author: None,
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "NOTE".to_string(),
+                    message: "Document this".to_string(),
+                    line: 30,
+                    column: 5,
+                    line_content: None,
+                    author: None,
+                    priority: Priority::Low,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+            ],
+        );
+
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            ..Default::default()
+        });
+        let mut output = Vec::new();
+        printer.print_diff(&mut output, &old, &new).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output_str,
+            "- src/main.rs:20 [FIXME] Fix this bug\n\
+             + src/main.rs:30 [NOTE] Document this\n\
+             \n\
+             1 added, 1 removed (2 -> 2 TODO items)\n"
+        );
+    }
+
+    #[test]
+    fn test_print_diff_no_changes() {
+        let result = create_test_result();
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            show_summary: false,
+            ..Default::default()
+        });
+
+        let mut output = Vec::new();
+        printer.print_diff(&mut output, &result, &result).unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("No changes in TODO items."));
+    }
+
+    #[test]
+    fn test_pprint_item() {
+        let result = create_test_result();
+        let (path, items) = result.sorted_files()[0];
+
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            clickable_links: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        });
+        let mut output = Vec::new();
+        printer.pprint_item(&mut output, &items[1], path).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "src/main.rs:20:5 [FIXME] Fix this bug\n"
+        );
+
+        let printer = Printer::new(PrintOptions {
+            colored: false,
+            clickable_links: false,
+            show_line_numbers: false,
+            base_path: Some(PathBuf::from("/test")),
+            ..Default::default()
+        });
+        let mut output = Vec::new();
+        printer.pprint_item(&mut output, &items[0], path).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "src/main.rs [TODO] Implement feature\n"
+        );
+    }
+
+    #[test]
+    fn test_print_tree_inline_counts() {
+        let mut result = create_test_result();
         result.add_file(
-            PathBuf::from("/test/file.rs"),
-            vec![TodoItem {
-                tag: "TODO".to_string(),
-                message: "With colored author".to_string(),
-                line: 1,
-                column: 1,
-                line_content: Some("// TODO(developer): With colored author".to_string()),
-                author: Some("developer".to_string()),
-                priority: Priority::Medium,
-            }],
+            PathBuf::from("/test/src/lib.rs"),
+            vec![
+                TodoItem {
+                    tag: "TODO".to_string(),
+                    message: "First".to_string(),
+                    line: 1,
+                    column: 4,
+                    line_content: None,
+                    author: None,
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "TODO".to_string(),
+                    message: "Second".to_string(),
+                    line: 2,
+                    column: 4,
+                    line_content: None,
+                    author: None,
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+            ],
         );
 
         let options = PrintOptions {
-            colored: true,
+            colored: false,
             clickable_links: false,
+            base_path: Some(PathBuf::from("/test")),
+            inline_counts: true,
             show_summary: false,
             ..Default::default()
         };
@@ -1394,8 +4803,17 @@ mod tests {
         let mut output = Vec::new();
         printer.print_to(&mut output, &result).unwrap();
 
-        // Should not panic and should produce output
-        assert!(!output.is_empty());
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("├── src/lib.rs (TODO:2)\n"));
+        assert!(output_str.contains("└── src/main.rs (FIXME:1 TODO:1)\n"));
+    }
+
+    #[test]
+    fn test_truncate_message() {
+        assert_eq!(truncate_message("short", 40), "short");
+        assert_eq!(truncate_message("exactly", 7), "exactly");
+        assert_eq!(truncate_message("fix the thing", 7), "fix the…");
+        assert_eq!(truncate_message("añadir más", 6), "añadir…");
     }
 
     #[test]
@@ -1,9 +1,23 @@
-use crate::parser::TodoParser;
-use anyhow::{Context, Result};
+use crate::archive::{self, DEFAULT_ARCHIVE_EXTENSIONS};
+use crate::encoding;
+use crate::parser::{TodoParser, lossy_lines};
+use anyhow::{Context, Result, bail};
 use ignore::WalkBuilder;
-use ignore::overrides::OverrideBuilder;
-use std::path::Path;
-use todo_tree_core::{ScanResult, TodoItem};
+use ignore::overrides::{Override, OverrideBuilder};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use todo_tree_core::{ScanResult, Summary, TodoItem};
+
+/// Default file size (in bytes) above which files are parsed line by line
+pub const DEFAULT_STREAMING_THRESHOLD: u64 = 8 * 1024 * 1024;
+
+/// Version control metadata directories walked by `include_vcs_dirs`
+const VCS_DIRS: [&str; 3] = [".git", ".hg", ".svn"];
 
 /// Options for scanning
 #[derive(Debug, Clone)]
@@ -17,6 +31,11 @@ pub struct ScanOptions {
     /// Maximum depth to scan (0 = unlimited)
     pub max_depth: usize,
 
+    /// Scan only the files directly in the root, without include/exclude glob matching
+    ///
+    /// Overrides `max_depth`. Ignore files such as `.gitignore` still apply.
+    pub no_walk: bool,
+
     /// Follow symbolic links
     pub follow_links: bool,
 
@@ -26,8 +45,43 @@ pub struct ScanOptions {
     /// Number of threads to use (0 = auto)
     pub threads: usize,
 
-    /// Respect .gitignore files
+    /// Number of files parsed in parallel, independent of `threads` (0 or 1 = sequential)
+    pub parallel_files: usize,
+
+    /// Respect .gitignore files, the global gitignore and .git/info/exclude
     pub respect_gitignore: bool,
+
+    /// Respect .svnignore files (enabled automatically when the root has a .svn directory)
+    pub respect_svnignore: bool,
+
+    /// Respect .dockerignore files (enabled automatically when the root has a Dockerfile)
+    pub respect_dockerignore: bool,
+
+    /// Walk .git, .hg and .svn directories even though they are hidden (can be slow)
+    pub include_vcs_dirs: bool,
+
+    /// Files larger than this many bytes are streamed instead of read into memory
+    pub streaming_threshold: u64,
+
+    /// Stop after this many files have been processed (result is marked truncated)
+    pub max_files: Option<usize>,
+
+    /// Lines of context kept before each item
+    pub before_context: usize,
+
+    /// Lines of context kept after each item
+    pub after_context: usize,
+
+    /// Scan the files inside zip and tar archives
+    pub scan_archives: bool,
+
+    /// Extensions (without the dot) of the files treated as archives
+    pub archive_extensions: Vec<String>,
+
+    /// Guess each file's encoding (e.g. Latin-1, Shift-JIS) instead of requiring UTF-8
+    ///
+    /// Files are then always read whole, however large.
+    pub encoding_detection: bool,
 }
 
 impl Default for ScanOptions {
@@ -36,15 +90,31 @@ impl Default for ScanOptions {
             include: Vec::new(),
             exclude: Vec::new(),
             max_depth: 0,
+            no_walk: false,
             follow_links: false,
             hidden: false,
             threads: 0,
+            parallel_files: num_cpus::get(),
             respect_gitignore: true,
+            respect_svnignore: false,
+            respect_dockerignore: false,
+            include_vcs_dirs: false,
+            streaming_threshold: DEFAULT_STREAMING_THRESHOLD,
+            max_files: None,
+            before_context: 0,
+            after_context: 0,
+            scan_archives: false,
+            archive_extensions: DEFAULT_ARCHIVE_EXTENSIONS
+                .iter()
+                .map(|ext| ext.to_string())
+                .collect(),
+            encoding_detection: false,
         }
     }
 }
 
 /// Scanner for finding TODO items in a directory
+#[derive(Clone)]
 pub struct Scanner {
     parser: TodoParser,
     options: ScanOptions,
@@ -56,6 +126,11 @@ impl Scanner {
         Self { parser, options }
     }
 
+    /// The parser used for every file
+    pub fn parser(&self) -> &TodoParser {
+        &self.parser
+    }
+
     /// Scan a directory for TODO items
     pub fn scan(&self, root: &Path) -> Result<ScanResult> {
         let root = root
@@ -63,9 +138,238 @@ impl Scanner {
             .with_context(|| format!("Failed to resolve path: {}", root.display()))?;
 
         let mut result = ScanResult::new(root.clone());
+        let truncated =
+            self.scan_with_callback(&root, |path, items| result.add_file(path, items))?;
+        result.summary.truncated = truncated;
+
+        Ok(result)
+    }
+
+    /// Scan a directory, giving up after `timeout`
+    ///
+    /// The scan runs on its own thread. Once the deadline passes, the files
+    /// parsed so far are returned with `timed_out` and `truncated` set in the
+    /// summary. The abandoned thread is not interrupted (it may be stuck in a
+    /// read) and finishes, or dies with the process, in the background.
+    pub fn scan_with_timeout(&self, root: &Path, timeout: Duration) -> Result<ScanResult> {
+        let deadline = Instant::now() + timeout;
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path: {}", root.display()))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let scanner = self.clone();
+        let walk_root = root.clone();
+        let handle = std::thread::spawn(move || {
+            scanner.scan_with_callback(&walk_root, |path, items| {
+                // The receiver is gone once the scan timed out
+                let _ = sender.send((path, items));
+            })
+        });
+
+        let mut result = ScanResult::new(root);
+        loop {
+            let now = Instant::now();
+            let received = if now >= deadline {
+                Err(RecvTimeoutError::Timeout)
+            } else {
+                receiver.recv_timeout(deadline - now)
+            };
+
+            match received {
+                Ok((path, items)) => result.add_file(path, items),
+                Err(RecvTimeoutError::Timeout) => {
+                    tracing::debug!(?timeout, "scan timed out");
+                    result.summary.timed_out = true;
+                    result.summary.truncated = true;
+                    break;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    result.summary.truncated = handle
+                        .join()
+                        .map_err(|_| anyhow::anyhow!("Scan thread panicked"))??;
+                    break;
+                }
+            }
+        }
+
+        Ok(result)
+    }
 
+    /// Scan a directory, passing each file's items to `callback` as soon as it is parsed
+    ///
+    /// Every scanned file is reported, with no items if it has none or cannot be
+    /// read, so feeding the calls to [`ScanResult::add_file`] gives the result of
+    /// [`scan`](Self::scan). With `parallel_files` above one, files arrive in the
+    /// order they finish parsing rather than walk order. Returns whether
+    /// `max_files` cut the walk short.
+    pub fn scan_with_callback<F>(&self, root: &Path, mut callback: F) -> Result<bool>
+    where
+        F: FnMut(PathBuf, Vec<TodoItem>),
+    {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path: {}", root.display()))?;
+
+        let (mut paths, truncated) = self.collect_files(&root)?;
+        tracing::debug!(root = %root.display(), files = paths.len(), truncated, "collected files to scan");
+
+        if self.options.scan_archives {
+            let archives: Vec<PathBuf>;
+            (archives, paths) = paths.into_iter().partition(|path| self.is_archive(path));
+            for archive in archives {
+                match self.parse_archive(&archive) {
+                    Ok(files) => {
+                        for (path, items) in files {
+                            callback(path, items);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::warn!(path = %archive.display(), "skipping unreadable archive: {:#}", err);
+                        callback(archive, Vec::new());
+                    }
+                }
+            }
+        }
+
+        self.for_each_parsed(paths, |path, parsed| match parsed {
+            Ok(items) => callback(path, items),
+            Err(err) => {
+                // Skip files that can't be read (binary files, permission errors, etc.)
+                tracing::trace!(path = %path.display(), "skipping unreadable file: {:#}", err);
+                callback(path, Vec::new());
+            }
+        })?;
+
+        Ok(truncated)
+    }
+
+    /// List the files a scan of `root` would parse, without reading them
+    ///
+    /// Uses the same walk settings as [`scan`](Self::scan). Paths are canonical
+    /// and sorted; archives are listed as single files.
+    pub fn dry_run(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path: {}", root.display()))?;
+
+        let (mut paths, _) = self.collect_files(&root)?;
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Scan an explicit list of files instead of walking `root`
+    ///
+    /// Relative paths are resolved against the current directory. Paths that do
+    /// not exist or are not files are skipped, and include/exclude patterns are
+    /// matched relative to `root` as in a walk. `max_files` still applies.
+    pub fn scan_files<I>(&self, root: &Path, files: I) -> Result<ScanResult>
+    where
+        I: IntoIterator<Item = PathBuf>,
+    {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path: {}", root.display()))?;
+        let overrides = self.build_overrides(&root)?;
+
+        let mut result = ScanResult::new(root);
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for file in files {
+            let Ok(path) = file.canonicalize() else {
+                continue;
+            };
+            if !path.is_file() {
+                continue;
+            }
+            if let Some(overrides) = &overrides
+                && overrides.matched(&path, false).is_ignore()
+            {
+                continue;
+            }
+
+            if let Some(max_files) = self.options.max_files
+                && paths.len() >= max_files
+            {
+                result.summary.truncated = true;
+                break;
+            }
+
+            paths.push(path);
+        }
+
+        for (path, parsed) in self.parse_files(paths)? {
+            match parsed {
+                Ok(items) => result.add_file(path, items),
+                Err(_) => result.summary.files_scanned += 1,
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Scan the text files inside a zip or tar archive
+    ///
+    /// Items are reported under `archive.zip!/inner/path`. Include/exclude
+    /// patterns are matched against the inner path.
+    pub fn scan_archive(&self, path: &Path) -> Result<ScanResult> {
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
+
+        let mut result = ScanResult::new(path.clone());
+        for (path, items) in self.parse_archive(&path)? {
+            result.add_file(path, items);
+        }
+
+        Ok(result)
+    }
+
+    /// Count TODO items without keeping them
+    ///
+    /// Files are parsed as in [`scan`](Self::scan), but items are only tallied
+    /// per tag, so no `TodoItem` vectors are built.
+    pub fn count_only(&self, root: &Path) -> Result<Summary> {
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve path: {}", root.display()))?;
+
+        let (paths, truncated) = self.collect_files(&root)?;
+        let mut summary = Summary {
+            total_count: 0,
+            files_with_todos: 0,
+            files_scanned: 0,
+            tag_counts: HashMap::new(),
+            truncated,
+            timed_out: false,
+        };
+
+        for (_, counted) in self.map_files(paths, |path| self.count_file(path))? {
+            summary.files_scanned += 1;
+
+            // Skip files that can't be read, as `scan` does
+            let Ok(tag_counts) = counted else {
+                continue;
+            };
+            if tag_counts.is_empty() {
+                continue;
+            }
+
+            summary.files_with_todos += 1;
+            for (tag, count) in tag_counts {
+                summary.total_count += count;
+                *summary.tag_counts.entry(tag).or_insert(0) += count;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Walk the (canonical) root, collecting the files to parse
+    ///
+    /// The flag is set when the walk stopped early because of `max_files`.
+    fn collect_files(&self, root: &Path) -> Result<(Vec<PathBuf>, bool)> {
         // Build the walker
-        let mut builder = WalkBuilder::new(&root);
+        let mut builder = WalkBuilder::new(root);
 
         // Configure the walker
         builder
@@ -75,8 +379,36 @@ impl Scanner {
             .git_global(self.options.respect_gitignore)
             .git_exclude(self.options.respect_gitignore);
 
+        // Subversion stores ignores as properties; honor the conventional .svnignore file
+        if self.options.respect_svnignore || root.join(".svn").is_dir() {
+            builder.add_custom_ignore_filename(".svnignore");
+        }
+
+        // Exclude what a Docker build context leaves out (read with gitignore syntax)
+        if self.options.respect_dockerignore || root.join("Dockerfile").is_file() {
+            builder.add_custom_ignore_filename(".dockerignore");
+        }
+
+        // Walk VCS directories while still skipping other hidden files
+        if self.options.include_vcs_dirs {
+            builder.require_git(false);
+            if !self.options.hidden {
+                let root = root.to_path_buf();
+                builder.hidden(false).filter_entry(move |entry| {
+                    let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+                    let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+                    !is_hidden
+                        || relative
+                            .components()
+                            .any(|c| VCS_DIRS.iter().any(|dir| c.as_os_str() == *dir))
+                });
+            }
+        }
+
         // Set max depth if specified
-        if self.options.max_depth > 0 {
+        if self.options.no_walk {
+            builder.max_depth(Some(1));
+        } else if self.options.max_depth > 0 {
             builder.max_depth(Some(self.options.max_depth));
         }
 
@@ -85,31 +417,15 @@ impl Scanner {
             builder.threads(self.options.threads);
         }
 
-        // Add include/exclude patterns as overrides
-        if !self.options.include.is_empty() || !self.options.exclude.is_empty() {
-            let mut override_builder = OverrideBuilder::new(&root);
-
-            // Add include patterns (must be prefixed with !)
-            for pattern in &self.options.include {
-                // Include patterns are added as-is
-                override_builder
-                    .add(pattern)
-                    .with_context(|| format!("Invalid include pattern: {}", pattern))?;
-            }
-
-            // Add exclude patterns (prefixed with !)
-            for pattern in &self.options.exclude {
-                let exclude_pattern = format!("!{}", pattern);
-                override_builder
-                    .add(&exclude_pattern)
-                    .with_context(|| format!("Invalid exclude pattern: {}", pattern))?;
-            }
-
-            let overrides = override_builder.build()?;
+        if !self.options.no_walk
+            && let Some(overrides) = self.build_overrides(root)?
+        {
             builder.overrides(overrides);
         }
 
-        // Walk the directory
+        // Walk the directory, collecting the files to parse
+        let mut paths: Vec<PathBuf> = Vec::new();
+        let mut truncated = false;
         for entry in builder.build() {
             match entry {
                 Ok(entry) => {
@@ -127,16 +443,15 @@ impl Scanner {
                         continue;
                     }
 
-                    // Parse the file
-                    match self.parse_file(path) {
-                        Ok(items) => {
-                            result.add_file(path.to_path_buf(), items);
-                        }
-                        Err(_) => {
-                            // Skip files that can't be read (binary files, permission errors, etc.)
-                            result.summary.files_scanned += 1;
-                        }
+                    // Stop once the file limit is reached
+                    if let Some(max_files) = self.options.max_files
+                        && paths.len() >= max_files
+                    {
+                        truncated = true;
+                        break;
                     }
+
+                    paths.push(path.to_path_buf());
                 }
                 Err(_) => {
                     // Skip entries that can't be accessed
@@ -145,14 +460,247 @@ impl Scanner {
             }
         }
 
-        Ok(result)
+        Ok((paths, truncated))
+    }
+
+    /// Build the include/exclude matcher, if any patterns are set
+    fn build_overrides(&self, root: &Path) -> Result<Option<Override>> {
+        if self.options.include.is_empty() && self.options.exclude.is_empty() {
+            return Ok(None);
+        }
+
+        let mut override_builder = OverrideBuilder::new(root);
+
+        // Add include patterns (must be prefixed with !)
+        for pattern in &self.options.include {
+            // Include patterns are added as-is
+            override_builder
+                .add(pattern)
+                .with_context(|| format!("Invalid include pattern: {}", pattern))?;
+        }
+
+        // Add exclude patterns (prefixed with !)
+        for pattern in &self.options.exclude {
+            let exclude_pattern = format!("!{}", pattern);
+            override_builder
+                .add(&exclude_pattern)
+                .with_context(|| format!("Invalid exclude pattern: {}", pattern))?;
+        }
+
+        Ok(Some(override_builder.build()?))
+    }
+
+    /// Parse files, in parallel when `parallel_files` is greater than one
+    ///
+    /// Results keep the walk order.
+    fn parse_files(&self, paths: Vec<PathBuf>) -> Result<Vec<(PathBuf, Result<Vec<TodoItem>>)>> {
+        self.map_files(paths, |path| self.parse_file_items(path))
+    }
+
+    /// Parse one file and record its extension on the items
+    fn parse_file_items(&self, path: &Path) -> Result<Vec<TodoItem>> {
+        let mut items = self.parse_file(path)?;
+        set_file_extension(&mut items, path);
+        Ok(items)
+    }
+
+    /// Parse each file and hand the outcome to `f` on the calling thread as it completes
+    ///
+    /// Like [`parse_files`](Self::parse_files), but without waiting for the whole
+    /// batch; in parallel mode the results arrive in completion order.
+    fn for_each_parsed<F>(&self, paths: Vec<PathBuf>, mut f: F) -> Result<()>
+    where
+        F: FnMut(PathBuf, Result<Vec<TodoItem>>),
+    {
+        if self.options.parallel_files <= 1 || paths.len() <= 1 {
+            for path in paths {
+                let parsed = self.parse_file_items(&path);
+                f(path, parsed);
+            }
+            return Ok(());
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.options.parallel_files)
+            .build()
+            .context("Failed to build file parsing thread pool")?;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                pool.install(|| {
+                    paths.into_par_iter().for_each_with(sender, |sender, path| {
+                        let parsed = self.parse_file_items(&path);
+                        // The receiver only goes away once every file was handled
+                        let _ = sender.send((path, parsed));
+                    });
+                });
+            });
+
+            for (path, parsed) in receiver {
+                f(path, parsed);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Apply `f` to each file, in parallel when `parallel_files` is greater than one
+    ///
+    /// Results keep the walk order.
+    fn map_files<T, F>(&self, paths: Vec<PathBuf>, f: F) -> Result<Vec<(PathBuf, T)>>
+    where
+        T: Send,
+        F: Fn(&Path) -> T + Sync,
+    {
+        if self.options.parallel_files <= 1 || paths.len() <= 1 {
+            return Ok(paths
+                .into_iter()
+                .map(|path| {
+                    let mapped = f(&path);
+                    (path, mapped)
+                })
+                .collect());
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.options.parallel_files)
+            .build()
+            .context("Failed to build file parsing thread pool")?;
+
+        Ok(pool.install(|| {
+            paths
+                .into_par_iter()
+                .map(|path| {
+                    let mapped = f(&path);
+                    (path, mapped)
+                })
+                .collect()
+        }))
+    }
+
+    /// Whether the file has one of the configured archive extensions
+    fn is_archive(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|ext| {
+            self.options
+                .archive_extensions
+                .iter()
+                .any(|archive_ext| ext.eq_ignore_ascii_case(archive_ext.as_str()))
+        })
+    }
+
+    /// Parse the entries of an archive, keyed by `archive!/inner/path`
+    fn parse_archive(&self, path: &Path) -> Result<Vec<(PathBuf, Vec<TodoItem>)>> {
+        let overrides = self.build_overrides(Path::new(""))?;
+
+        let mut files = Vec::new();
+        archive::for_each_text_entry(path, archive::MAX_ENTRY_SIZE, |name, content| {
+            if overrides
+                .as_ref()
+                .is_some_and(|overrides| overrides.matched(name, false).is_ignore())
+            {
+                return;
+            }
+
+            let mut items = self.parser.parse_content_with_context(
+                content,
+                self.options.before_context,
+                self.options.after_context,
+            );
+            set_file_extension(&mut items, Path::new(name));
+            files.push((
+                PathBuf::from(format!("{}!/{}", path.display(), name)),
+                items,
+            ));
+        })?;
+
+        Ok(files)
     }
 
     /// Parse a single file for TODO items
     fn parse_file(&self, path: &Path) -> Result<Vec<TodoItem>> {
-        self.parser
-            .parse_file(path)
-            .with_context(|| format!("Failed to parse file: {}", path.display()))
+        let mut items = Vec::new();
+        self.for_each_item(path, |item| items.push(item))?;
+        Ok(items)
+    }
+
+    /// Count the items of a single file per tag
+    fn count_file(&self, path: &Path) -> Result<HashMap<String, usize>> {
+        let mut tag_counts = HashMap::new();
+        self.for_each_item(path, |item| *tag_counts.entry(item.tag).or_insert(0) += 1)?;
+        Ok(tag_counts)
+    }
+
+    /// Read a single file and pass each of its items to `f`
+    ///
+    /// Files above `streaming_threshold` are read line by line to bound memory usage,
+    /// and are an error if they look binary. Smaller files that are not valid UTF-8
+    /// are an error unless `encoding_detection` is enabled.
+    fn for_each_item(&self, path: &Path, f: impl FnMut(TodoItem)) -> Result<()> {
+        let size = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read metadata: {}", path.display()))?
+            .len();
+        let (before, after) = (self.options.before_context, self.options.after_context);
+        let with_context = before > 0 || after > 0;
+
+        if !self.options.encoding_detection && size > self.options.streaming_threshold {
+            let file = File::open(path)
+                .with_context(|| format!("Failed to open file: {}", path.display()))?;
+            let mut reader = BufReader::with_capacity(encoding::BINARY_SNIFF_LEN, file);
+            let head = reader
+                .fill_buf()
+                .with_context(|| format!("Failed to read file: {}", path.display()))?;
+            // Reading small files as UTF-8 rejects binaries, so skip large ones too
+            if encoding::is_binary(head) {
+                bail!("Failed to parse file: {}: binary content", path.display());
+            }
+            if with_context {
+                self.parser
+                    .parse_lines_with_context(lossy_lines(reader), before, after)
+                    .into_iter()
+                    .for_each(f);
+            } else {
+                self.parser.parse_content_streaming(reader).for_each(f);
+            }
+            return Ok(());
+        }
+
+        let content = if self.options.encoding_detection {
+            read_detected(path)?
+        } else {
+            std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to parse file: {}", path.display()))?
+        };
+        let items = if with_context {
+            self.parser
+                .parse_content_with_context(&content, before, after)
+        } else {
+            self.parser.parse_content(&content)
+        };
+        items.into_iter().for_each(f);
+        Ok(())
+    }
+}
+
+/// Read a file in its detected encoding (see [`encoding::detect`])
+fn read_detected(path: &Path) -> Result<String> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read file: {}", path.display()))?;
+    let (content, detected) = encoding::decode(&bytes)
+        .with_context(|| format!("Failed to parse file: {}", path.display()))?;
+    if detected != encoding_rs::UTF_8 {
+        tracing::debug!(path = %path.display(), encoding = detected.name(), "decoded non-UTF-8 file");
+    }
+    Ok(content)
+}
+
+/// Record the extension of `path` on each of its items
+fn set_file_extension(items: &mut [TodoItem], path: &Path) {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().into_owned());
+    for item in items {
+        item.file_extension = extension.clone();
     }
 }
 
@@ -341,6 +889,35 @@ fn main() {
         assert_eq!(result.summary.total_count, 2);
     }
 
+    #[test]
+    fn test_scan_no_walk() {
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(temp_dir.path(), "top.rs", "// TODO: Top");
+        create_test_file(temp_dir.path(), "top.min.js", "// TODO: Minified");
+        create_test_file(temp_dir.path(), "sub/nested.rs", "// TODO: Nested");
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let options = ScanOptions {
+            no_walk: true,
+            include: vec!["**/*.rs".to_string()],
+            exclude: vec!["*.min.js".to_string()],
+            ..Default::default()
+        };
+        let scanner = Scanner::new(parser, options);
+
+        let result = scanner.scan(temp_dir.path()).unwrap();
+
+        // The nested file is not reached, and the globs do not filter the top level
+        let mut messages: Vec<_> = result
+            .all_items()
+            .into_iter()
+            .map(|(_, item)| item.message)
+            .collect();
+        messages.sort();
+        assert_eq!(messages, vec!["Minified", "Top"]);
+    }
+
     #[test]
     fn test_scan_hidden_files() {
         let temp_dir = TempDir::new().unwrap();
@@ -544,6 +1121,338 @@ fn main() {
         assert_eq!(result.summary.total_count, 2);
     }
 
+    #[test]
+    fn test_scan_respects_svnignore() {
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(temp_dir.path(), ".svnignore", "ignored/\n");
+        create_test_file(temp_dir.path(), "included.rs", "// TODO: Included");
+        create_test_file(temp_dir.path(), "ignored/hidden.rs", "// TODO: Ignored");
+
+        // Not a working copy and option disabled: .svnignore is ignored
+        let parser = TodoParser::new(&default_tags(), false);
+        let scanner = Scanner::new(parser.clone(), ScanOptions::default());
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 2);
+
+        // Explicitly enabled
+        let options = ScanOptions {
+            respect_svnignore: true,
+            ..Default::default()
+        };
+        let scanner = Scanner::new(parser.clone(), options);
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 1);
+
+        // Auto-detected from the .svn directory
+        fs::create_dir(temp_dir.path().join(".svn")).unwrap();
+        let scanner = Scanner::new(parser, ScanOptions::default());
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 1);
+    }
+
+    #[test]
+    fn test_scan_include_vcs_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(temp_dir.path(), "src/main.rs", "// TODO: Source");
+        create_test_file(temp_dir.path(), ".git/hooks/pre-commit", "# TODO: Hook");
+        create_test_file(temp_dir.path(), ".hg/hgrc", "# TODO: Config");
+        create_test_file(temp_dir.path(), ".cache/notes.rs", "// TODO: Hidden");
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let scanner = Scanner::new(parser.clone(), ScanOptions::default());
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 1);
+
+        let options = ScanOptions {
+            include_vcs_dirs: true,
+            ..Default::default()
+        };
+        let scanner = Scanner::new(parser, options);
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 3);
+        assert!(
+            result
+                .files_map
+                .keys()
+                .all(|path| !path.to_string_lossy().contains(".cache"))
+        );
+    }
+
+    #[test]
+    fn test_scan_respects_dockerignore() {
+        let temp_dir = TempDir::new().unwrap();
+
+        create_test_file(temp_dir.path(), ".dockerignore", "tests/\n");
+        create_test_file(temp_dir.path(), "src/main.rs", "// TODO: In the image");
+        create_test_file(temp_dir.path(), "tests/it.rs", "// TODO: Not in the image");
+
+        // No Dockerfile and option disabled: .dockerignore is ignored
+        let parser = TodoParser::new(&default_tags(), false);
+        let scanner = Scanner::new(parser.clone(), ScanOptions::default());
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 2);
+
+        // Explicitly enabled
+        let options = ScanOptions {
+            respect_dockerignore: true,
+            ..Default::default()
+        };
+        let scanner = Scanner::new(parser.clone(), options);
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 1);
+
+        // Auto-detected from the Dockerfile
+        create_test_file(temp_dir.path(), "Dockerfile", "FROM scratch\n");
+        let scanner = Scanner::new(parser, ScanOptions::default());
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 1);
+    }
+
+    #[test]
+    fn test_scan_streams_large_files() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(
+            temp_dir.path(),
+            "large.rs",
+            "fn main() {}\n// TODO: streamed\n// FIXME: also streamed\n",
+        );
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let options = ScanOptions {
+            streaming_threshold: 0,
+            ..Default::default()
+        };
+        let scanner = Scanner::new(parser, options);
+
+        let result = scanner.scan(temp_dir.path()).unwrap();
+
+        assert_eq!(result.summary.total_count, 2);
+        assert_eq!(result.summary.files_scanned, 1);
+        let items = result.all_items();
+        assert!(items.iter().any(|(_, item)| item.line == 2));
+    }
+
+    #[test]
+    fn test_scan_skips_large_binary_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("lib.so"),
+            b"\x7fELF\0\0\0\n// TODO: not a comment\n",
+        )
+        .unwrap();
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let options = ScanOptions {
+            streaming_threshold: 0,
+            ..Default::default()
+        };
+        let scanner = Scanner::new(parser, options);
+
+        let result = scanner.scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 0);
+        assert_eq!(scanner.count_only(temp_dir.path()).unwrap().total_count, 0);
+    }
+
+    #[test]
+    fn test_scan_max_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..5 {
+            create_test_file(
+                temp_dir.path(),
+                &format!("file{}.rs", i),
+                "// TODO: something",
+            );
+        }
+
+        let parser = TodoParser::new(&default_tags(), false);
+
+        let options = ScanOptions {
+            max_files: Some(3),
+            ..Default::default()
+        };
+        let result = Scanner::new(parser.clone(), options)
+            .scan(temp_dir.path())
+            .unwrap();
+        assert_eq!(result.summary.files_scanned, 3);
+        assert_eq!(result.summary.total_count, 3);
+        assert!(result.summary.truncated);
+
+        // A limit that is not reached leaves the result complete
+        let options = ScanOptions {
+            max_files: Some(5),
+            ..Default::default()
+        };
+        let result = Scanner::new(parser, options).scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.files_scanned, 5);
+        assert!(!result.summary.truncated);
+    }
+
+    #[test]
+    fn test_scan_with_context() {
+        let temp_dir = TempDir::new().unwrap();
+        let content = "fn main() {\n    // TODO: context\n    run();\n}\n";
+        create_test_file(temp_dir.path(), "main.rs", content);
+
+        for streaming_threshold in [DEFAULT_STREAMING_THRESHOLD, 0] {
+            let parser = TodoParser::new(&default_tags(), false);
+            let options = ScanOptions {
+                before_context: 0,
+                after_context: 2,
+                streaming_threshold,
+                ..Default::default()
+            };
+            let result = Scanner::new(parser, options).scan(temp_dir.path()).unwrap();
+
+            let item = result.iter().next().unwrap().1;
+            assert!(item.context_before.is_empty());
+            assert_eq!(item.context_after, vec!["    run();", "}"]);
+        }
+    }
+
+    #[test]
+    fn test_scan_sets_file_extension() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(temp_dir.path(), "main.rs", "// TODO: rust");
+        create_test_file(temp_dir.path(), "Makefile", "# TODO: make");
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let result = Scanner::new(parser, ScanOptions::default())
+            .scan(temp_dir.path())
+            .unwrap();
+
+        let mut extensions: Vec<Option<&str>> = result
+            .iter()
+            .map(|(_, item)| item.file_extension())
+            .collect();
+        extensions.sort();
+        assert_eq!(extensions, vec![None, Some("rs")]);
+    }
+
+    #[test]
+    fn test_scan_archives() {
+        use std::io::Write;
+
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(temp_dir.path(), "main.rs", "// TODO: outside");
+        let archive = temp_dir.path().join("vendor.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("lib/util.rs", options).unwrap();
+        writer.write_all(b"// FIXME: inside").unwrap();
+        writer.start_file("README.md", options).unwrap();
+        writer.write_all(b"<!-- TODO: excluded -->").unwrap();
+        writer.finish().unwrap();
+
+        let scan = |scan_archives| {
+            let options = ScanOptions {
+                scan_archives,
+                exclude: vec!["*.md".to_string()],
+                ..Default::default()
+            };
+            Scanner::new(TodoParser::new(&default_tags(), false), options)
+        };
+
+        let result = scan(false).scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 1);
+
+        let result = scan(true).scan(temp_dir.path()).unwrap();
+        assert_eq!(result.summary.total_count, 2);
+        let inner = PathBuf::from(format!(
+            "{}!/lib/util.rs",
+            archive.canonicalize().unwrap().display()
+        ));
+        assert_eq!(result.files_map[&inner][0].message, "inside");
+
+        let result = scan(true).scan_archive(&archive).unwrap();
+        assert_eq!(result.summary.files_scanned, 1);
+        assert_eq!(result.summary.total_count, 1);
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(temp_dir.path(), "src/main.rs", "// TODO: main");
+        create_test_file(temp_dir.path(), "src/lib.rs", "fn lib() {}");
+        create_test_file(temp_dir.path(), "notes.md", "<!-- TODO: notes -->");
+        create_test_file(temp_dir.path(), ".hidden.rs", "// TODO: hidden");
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let options = ScanOptions {
+            exclude: vec!["*.md".to_string()],
+            ..Default::default()
+        };
+        let files = Scanner::new(parser, options)
+            .dry_run(temp_dir.path())
+            .unwrap();
+
+        let root = temp_dir.path().canonicalize().unwrap();
+        assert_eq!(
+            files,
+            vec![root.join("src/lib.rs"), root.join("src/main.rs")]
+        );
+    }
+
+    #[test]
+    fn test_scan_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let main = create_test_file(temp_dir.path(), "src/main.rs", "// TODO: listed");
+        let other = create_test_file(temp_dir.path(), "src/other.rs", "// TODO: not listed");
+        let notes = create_test_file(temp_dir.path(), "notes.md", "<!-- TODO: excluded -->");
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let options = ScanOptions {
+            exclude: vec!["*.md".to_string()],
+            ..Default::default()
+        };
+        let scanner = Scanner::new(parser, options);
+
+        let result = scanner
+            .scan_files(
+                temp_dir.path(),
+                vec![
+                    main.clone(),
+                    notes,
+                    temp_dir.path().join("missing.rs"),
+                    temp_dir.path().join("src"),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(result.summary.total_count, 1);
+        assert_eq!(result.summary.files_scanned, 1);
+        assert!(result.files_map.contains_key(&main.canonicalize().unwrap()));
+        assert!(
+            !result
+                .files_map
+                .contains_key(&other.canonicalize().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_count_only_matches_scan() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(
+            temp_dir.path(),
+            "a.rs",
+            "// TODO: one\n// FIXME: two\n// TODO: three\n",
+        );
+        create_test_file(temp_dir.path(), "b.py", "# BUG: four\n");
+        create_test_file(temp_dir.path(), "c.txt", "no items here\n");
+        fs::write(temp_dir.path().join("d.bin"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let scanner = Scanner::new(parser, ScanOptions::default());
+
+        let summary = scanner.count_only(temp_dir.path()).unwrap();
+        assert_eq!(summary, scanner.scan(temp_dir.path()).unwrap().summary);
+        assert_eq!(summary.total_count, 4);
+        assert_eq!(summary.files_with_todos, 2);
+        assert_eq!(summary.files_scanned, 4);
+        assert_eq!(summary.tag_counts.get("TODO"), Some(&2));
+    }
+
     #[test]
     fn test_scan_result_new() {
         let root = PathBuf::from("/test/root");
@@ -557,6 +1466,117 @@ fn main() {
         assert!(result.summary.tag_counts.is_empty());
     }
 
+    #[test]
+    fn test_scan_parallel_files_matches_sequential() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..20 {
+            create_test_file(
+                temp_dir.path(),
+                &format!("dir{}/file{}.rs", i % 3, i),
+                &format!("// TODO: item {}\n// FIXME: other {}\n", i, i),
+            );
+        }
+
+        let scan = |parallel_files| {
+            let parser = TodoParser::new(&default_tags(), false);
+            let options = ScanOptions {
+                parallel_files,
+                ..Default::default()
+            };
+            Scanner::new(parser, options).scan(temp_dir.path()).unwrap()
+        };
+
+        let sequential = scan(1);
+        let parallel = scan(4);
+        assert_eq!(sequential.summary.total_count, 40);
+        assert_eq!(parallel.summary.total_count, 40);
+        assert_eq!(parallel.summary.files_scanned, 20);
+        assert_eq!(sequential.sorted_files(), parallel.sorted_files());
+    }
+
+    #[test]
+    fn test_scan_encoding_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("latin1.c"),
+            b"/* TODO: r\xe9sum\xe9 */\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("image.bin"), b"\x00\x01TODO: not text").unwrap();
+
+        let scan = |encoding_detection| {
+            let parser = TodoParser::new(&default_tags(), false);
+            let options = ScanOptions {
+                encoding_detection,
+                ..Default::default()
+            };
+            Scanner::new(parser, options).scan(temp_dir.path()).unwrap()
+        };
+
+        assert_eq!(scan(false).summary.total_count, 0);
+
+        let result = scan(true);
+        assert_eq!(result.summary.total_count, 1);
+        assert_eq!(result.summary.files_scanned, 2);
+        let (_, item) = result.iter().next().unwrap();
+        assert_eq!(item.message, "résumé */");
+    }
+
+    #[test]
+    fn test_scan_with_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(temp_dir.path(), "a.rs", "// TODO: first\n");
+        create_test_file(temp_dir.path(), "c/d.rs", "// FIXME: second\n");
+
+        let parser = TodoParser::new(&default_tags(), false);
+        let scanner = Scanner::new(parser, ScanOptions::default());
+
+        let result = scanner
+            .scan_with_timeout(temp_dir.path(), Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(result.summary.total_count, 2);
+        assert!(!result.summary.timed_out);
+        assert!(!result.summary.truncated);
+
+        let result = scanner
+            .scan_with_timeout(temp_dir.path(), Duration::ZERO)
+            .unwrap();
+        assert!(result.summary.timed_out);
+        assert!(result.summary.truncated);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_scan_with_callback() {
+        let temp_dir = TempDir::new().unwrap();
+        create_test_file(temp_dir.path(), "a.rs", "// TODO: first\n");
+        create_test_file(temp_dir.path(), "b.rs", "fn main() {}\n");
+        create_test_file(temp_dir.path(), "c/d.rs", "// FIXME: second\n");
+
+        for parallel_files in [1, 4] {
+            let parser = TodoParser::new(&default_tags(), false);
+            let options = ScanOptions {
+                parallel_files,
+                ..Default::default()
+            };
+            let scanner = Scanner::new(parser, options);
+
+            let mut files = Vec::new();
+            let truncated = scanner
+                .scan_with_callback(temp_dir.path(), |path, items| {
+                    files.push((path.file_name().unwrap().to_owned(), items.len()))
+                })
+                .unwrap();
+            files.sort();
+
+            assert!(!truncated);
+            assert_eq!(
+                files,
+                vec![("a.rs".into(), 1), ("b.rs".into(), 0), ("d.rs".into(), 1)]
+            );
+        }
+    }
+
     #[test]
     fn test_scan_options_default() {
         let options = ScanOptions::default();
@@ -564,10 +1584,21 @@ fn main() {
         assert!(options.include.is_empty());
         assert!(options.exclude.is_empty());
         assert_eq!(options.max_depth, 0);
+        assert!(!options.no_walk);
         assert!(!options.follow_links);
         assert!(!options.hidden);
         assert_eq!(options.threads, 0);
+        assert_eq!(options.parallel_files, num_cpus::get());
         assert!(options.respect_gitignore);
+        assert!(!options.respect_svnignore);
+        assert!(!options.respect_dockerignore);
+        assert!(!options.include_vcs_dirs);
+        assert_eq!(options.streaming_threshold, DEFAULT_STREAMING_THRESHOLD);
+        assert_eq!(options.max_files, None);
+        assert_eq!(options.before_context, 0);
+        assert_eq!(options.after_context, 0);
+        assert!(!options.scan_archives);
+        assert_eq!(options.archive_extensions, vec!["zip", "jar", "tar"]);
     }
 
     #[test]
@@ -628,6 +1659,10 @@ fn main() {
                     line_content: Some("// TODO: First".to_string()),
                     author: None,
                     priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
                 },
                 TodoItem {
                     tag: "TODO".to_string(),
@@ -637,6 +1672,10 @@ fn main() {
                     line_content: Some("// TODO: Second".to_string()),
                     author: None,
                     priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
                 },
                 TodoItem {
                     tag: "FIXME".to_string(),
@@ -646,6 +1685,10 @@ fn main() {
                     line_content: Some("// FIXME: Third".to_string()),
                     author: None,
                     priority: Priority::Critical,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
                 },
             ],
         );
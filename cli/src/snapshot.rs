@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use todo_tree_core::ScanResult;
+
+/// Directory (relative to the project root) used for project-local snapshots
+const PROJECT_SNAPSHOT_DIR: &str = ".todo-tree/snapshots";
+
+/// A named scan result saved to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Name of the snapshot (also the file stem)
+    pub name: String,
+
+    /// When the snapshot was taken
+    pub created_at: DateTime<Utc>,
+
+    /// Root directory that was scanned
+    pub root: PathBuf,
+
+    /// The saved scan result
+    pub result: ScanResult,
+}
+
+impl Snapshot {
+    /// Create a snapshot of a scan result taken now
+    pub fn new(name: &str, root: &Path, result: &ScanResult) -> Self {
+        Self {
+            name: name.to_string(),
+            created_at: Utc::now(),
+            root: root.to_path_buf(),
+            result: result.to_json_format(),
+        }
+    }
+
+    /// Get the saved result with its root restored
+    pub fn scan_result(&self) -> ScanResult {
+        let mut result = self.result.clone();
+        result.root = Some(self.root.clone());
+        result
+    }
+
+    /// Save the snapshot as `<name>.json` in the given directory
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create snapshot directory: {}", dir.display()))?;
+
+        let path = snapshot_path(dir, &self.name)?;
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write snapshot: {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Load a named snapshot from the given directory
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let path = snapshot_path(dir, name)?;
+        Self::load_from_file(&path)
+    }
+
+    /// Load a snapshot from a specific file
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot: {}", path.display()))?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse snapshot: {}", path.display()))
+    }
+
+    /// List all snapshots in the given directory, oldest first
+    pub fn list(dir: &Path) -> Result<Vec<Self>> {
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read snapshot directory: {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("json") {
+                snapshots.push(Self::load_from_file(&path)?);
+            }
+        }
+
+        snapshots.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        Ok(snapshots)
+    }
+}
+
+/// Get the directory where snapshots are stored
+///
+/// Project-local snapshots live in `<project>/.todo-tree/snapshots`, others in
+/// the user data directory (e.g. `~/.local/share/todo-tree/snapshots`).
+pub fn snapshot_dir(project_root: &Path, project_local: bool) -> Result<PathBuf> {
+    if project_local {
+        return Ok(project_root.join(PROJECT_SNAPSHOT_DIR));
+    }
+
+    dirs::data_dir()
+        .map(|dir| dir.join("todo-tree").join("snapshots"))
+        .context("Could not determine the user data directory")
+}
+
+/// Get the file path of a named snapshot, rejecting names that are not plain file names
+fn snapshot_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        anyhow::bail!("Invalid snapshot name: {}", name);
+    }
+
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use todo_tree_core::{Priority, TodoItem};
+
+    fn create_test_result() -> ScanResult {
+        let mut result = ScanResult::new(PathBuf::from("/project"));
+        result.add_file(
+            PathBuf::from("/project/src/main.rs"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "Implement feature".to_string(),
+                line: 3,
+                column: 4,
+                line_content: None,
+                author: None,
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+        result
+    }
+
+    #[test]
+    fn test_save_and_load_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let snapshot = Snapshot::new("baseline", Path::new("/project"), &create_test_result());
+
+        let path = snapshot.save(temp_dir.path()).unwrap();
+        assert_eq!(path, temp_dir.path().join("baseline.json"));
+
+        let loaded = Snapshot::load(temp_dir.path(), "baseline").unwrap();
+        assert_eq!(loaded.name, "baseline");
+        assert_eq!(loaded.created_at, snapshot.created_at);
+
+        let result = loaded.scan_result();
+        assert_eq!(result.root, Some(PathBuf::from("/project")));
+        assert_eq!(result.summary.total_count, 1);
+        assert_eq!(result.get_files()[0].path, "/project/src/main.rs");
+    }
+
+    #[test]
+    fn test_list_snapshots() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(Snapshot::list(temp_dir.path()).unwrap().is_empty());
+
+        let result = create_test_result();
+        Snapshot::new("first", Path::new("/project"), &result)
+            .save(temp_dir.path())
+            .unwrap();
+        Snapshot::new("second", Path::new("/project"), &result)
+            .save(temp_dir.path())
+            .unwrap();
+
+        let snapshots = Snapshot::list(temp_dir.path()).unwrap();
+        let names: Vec<&str> = snapshots.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["first", "second"]);
+    }
+
+    #[test]
+    fn test_list_missing_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let snapshots = Snapshot::list(&temp_dir.path().join("missing")).unwrap();
+        assert!(snapshots.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_snapshot_name() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(Snapshot::load(temp_dir.path(), "../escape").is_err());
+        assert!(Snapshot::load(temp_dir.path(), "").is_err());
+    }
+
+    #[test]
+    fn test_project_local_snapshot_dir() {
+        let dir = snapshot_dir(Path::new("/project"), true).unwrap();
+        assert_eq!(dir, PathBuf::from("/project/.todo-tree/snapshots"));
+    }
+}
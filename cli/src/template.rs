@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use tera::Tera;
+use todo_tree_core::{Priority, ScanResult};
+
+/// Build the template context for a scan result
+///
+/// Variables:
+/// - `files`      - List of `{ path, relative_path, items }`, sorted by path
+/// - `summary`    - Scan summary (`total_count`, `files_with_todos`, ...)
+/// - `tag_counts` - Map of tag name to number of items
+/// - `root`       - Scanned root directory (if known)
+/// - `priorities` - Priority names from lowest to highest
+pub fn template_context(result: &ScanResult) -> tera::Context {
+    let files: Vec<serde_json::Value> = result
+        .get_files()
+        .into_iter()
+        .map(|file| {
+            let relative_path = result
+                .root
+                .as_deref()
+                .and_then(|root| Path::new(&file.path).strip_prefix(root).ok())
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| file.path.clone());
+
+            serde_json::json!({
+                "path": file.path,
+                "relative_path": relative_path,
+                "items": file.items,
+            })
+        })
+        .collect();
+
+    let priorities: Vec<String> = [
+        Priority::Low,
+        Priority::Medium,
+        Priority::High,
+        Priority::Critical,
+    ]
+    .iter()
+    .map(|priority| format!("{:?}", priority))
+    .collect();
+
+    let mut context = tera::Context::new();
+    context.insert("files", &files);
+    context.insert("summary", &result.summary);
+    context.insert("tag_counts", &result.summary.tag_counts);
+    context.insert("root", &result.root);
+    context.insert("priorities", &priorities);
+
+    context
+}
+
+/// Render a scan result through a Tera template
+pub fn render(template: &str, result: &ScanResult) -> Result<String> {
+    let context = template_context(result);
+    Tera::one_off(template, &context, false).context("Failed to render output template")
+}
+
+/// Render a scan result through a Tera template file
+pub fn render_file(path: &Path, result: &ScanResult) -> Result<String> {
+    let template = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read template file: {}", path.display()))?;
+    render(&template, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use todo_tree_core::TodoItem;
+
+    fn create_test_result() -> ScanResult {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/src/main.rs"),
+            vec![
+                TodoItem {
+                    tag: "TODO".to_string(),
+                    message: "Implement feature".to_string(),
+                    line: 10,
+                    column: 4,
+                    line_content: Some("// TODO: Implement feature".to_string()),
+                    author: Some("alice".to_string()),
+                    priority: Priority::Medium,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+                TodoItem {
+                    tag: "FIXME".to_string(),
+                    message: "Fix this bug".to_string(),
+                    line: 20,
+                    column: 4,
+                    line_content: Some("// FIXME: Fix this bug".to_string()),
+                    author: None,
+                    priority: Priority::Critical,
+                    context_before: Vec::new(),
+                    context_after: Vec::new(),
+                    created_at: None,
+                    file_extension: None,
+                },
+            ],
+        );
+        result
+    }
+
+    #[test]
+    fn test_render_template() {
+        let result = create_test_result();
+        let template = "{{ summary.total_count }}\n\
+            {% for file in files %}{{ file.relative_path }}:{% for item in file.items %} {{ item.tag }}@{{ item.line }}{% endfor %}{% endfor %}\n\
+            {{ tag_counts.FIXME }} {{ priorities | last }}";
+
+        let output = render(template, &result).unwrap();
+        assert_eq!(output, "2\nsrc/main.rs: TODO@10 FIXME@20\n1 Critical");
+    }
+
+    #[test]
+    fn test_render_template_does_not_escape() {
+        let mut result = ScanResult::new(PathBuf::from("."));
+        result.add_file(
+            PathBuf::from("a.html"),
+            vec![TodoItem {
+                tag: "TODO".to_string(),
+                message: "use <b> & <i>".to_string(),
+                line: 1,
+                column: 1,
+                line_content: None,
+                author: None,
+                priority: Priority::Medium,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
+                created_at: None,
+                file_extension: None,
+            }],
+        );
+
+        let output = render("{{ files.0.items.0.message }}", &result).unwrap();
+        assert_eq!(output, "use <b> & <i>");
+    }
+
+    #[test]
+    fn test_render_invalid_template() {
+        let result = create_test_result();
+        assert!(render("{% for %}", &result).is_err());
+    }
+
+    #[test]
+    fn test_render_bundled_templates() {
+        let result = create_test_result();
+
+        let slack = render(include_str!("../templates/slack.tera"), &result).unwrap();
+        assert!(slack.contains("*TODO report*: 2 items in 1 files"));
+        assert!(slack.contains("*src/main.rs*"));
+        assert!(slack.contains("`FIXME` line 20: Fix this bug"));
+
+        let org = render(include_str!("../templates/orgmode.tera"), &result).unwrap();
+        assert!(org.contains("* src/main.rs"));
+        assert!(org.contains("** TODO [TODO] Implement feature"));
+        assert!(org.contains("[[file:/test/src/main.rs::20][line 20]]"));
+
+        let wiki = render(include_str!("../templates/mediawiki.tera"), &result).unwrap();
+        assert!(wiki.contains("{| class=\"wikitable sortable\""));
+        assert!(wiki.contains("| src/main.rs || 10 || TODO || Medium"));
+    }
+}
@@ -0,0 +1,443 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::{cursor, execute, queue, terminal};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use todo_tree_core::TodoItem;
+
+/// One TODO item in the browser
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// File containing the item, as passed to the editor
+    pub path: PathBuf,
+
+    /// Path shown in the list
+    pub display_path: String,
+
+    /// The item itself
+    pub item: TodoItem,
+
+    /// Marked as resolved with `d` (display only, the file is not changed)
+    pub resolved: bool,
+}
+
+/// What the caller should do after a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Keep browsing
+    Continue,
+
+    /// Open the file at the line in `$EDITOR`
+    Edit(PathBuf, usize),
+
+    /// Leave the browser
+    Quit,
+}
+
+/// A line of the rendered screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// Text of the line, already cut to the screen width
+    pub text: String,
+
+    /// Whether the line is the selected item
+    pub selected: bool,
+
+    /// Whether the line is a resolved item (drawn struck through)
+    pub resolved: bool,
+}
+
+impl Line {
+    fn plain(text: String) -> Self {
+        Self {
+            text,
+            selected: false,
+            resolved: false,
+        }
+    }
+}
+
+/// State of the fullscreen TODO browser (`tt interactive`)
+///
+/// Reacts to key presses and renders lines without touching the terminal;
+/// [`run`] owns the terminal and draws them.
+#[derive(Debug, Clone)]
+pub struct Browser {
+    entries: Vec<Entry>,
+    /// Case-insensitive substring the tags of the listed items must contain
+    filter: String,
+    /// Whether keys are being typed into the filter
+    editing_filter: bool,
+    /// Index into the visible entries
+    selected: usize,
+    /// First visible entry shown, for scrolling
+    offset: usize,
+    /// Whether the context of the selected item is shown
+    show_context: bool,
+}
+
+impl Browser {
+    /// Create a browser over the given entries, in the order they are listed
+    pub fn new(entries: Vec<Entry>) -> Self {
+        Self {
+            entries,
+            filter: String::new(),
+            editing_filter: false,
+            selected: 0,
+            offset: 0,
+            show_context: false,
+        }
+    }
+
+    /// Indices of the entries matching the tag filter
+    pub fn visible(&self) -> Vec<usize> {
+        let filter = self.filter.to_lowercase();
+        (0..self.entries.len())
+            .filter(|&index| {
+                self.entries[index]
+                    .item
+                    .tag
+                    .to_lowercase()
+                    .contains(&filter)
+            })
+            .collect()
+    }
+
+    /// The selected entry, if any item is visible
+    pub fn selected_entry(&self) -> Option<&Entry> {
+        self.visible()
+            .get(self.selected)
+            .map(|&index| &self.entries[index])
+    }
+
+    /// Update the state for a key press
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::Quit;
+        }
+
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Enter => self.editing_filter = false,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.editing_filter = false;
+                }
+                _ => {}
+            }
+            self.selected = 0;
+            self.offset = 0;
+            return Action::Continue;
+        }
+
+        let count = self.visible().len();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(count.saturating_sub(1))
+            }
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => self.selected = count.saturating_sub(1),
+            KeyCode::Enter => self.show_context = !self.show_context,
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Char('d') => {
+                if let Some(&index) = self.visible().get(self.selected) {
+                    self.entries[index].resolved = !self.entries[index].resolved;
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Some(entry) = self.selected_entry() {
+                    return Action::Edit(entry.path.clone(), entry.item.line);
+                }
+            }
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    /// Render the screen as lines of at most `width` characters, `height` lines in total
+    pub fn render(&mut self, width: usize, height: usize) -> Vec<Line> {
+        let visible = self.visible();
+        let footer = if self.editing_filter {
+            format!("/{}", self.filter)
+        } else {
+            format!(
+                "{} of {} items{} | ↑↓ move  Enter context  / filter tag  e edit  d resolve  q quit",
+                visible.len(),
+                self.entries.len(),
+                if self.filter.is_empty() {
+                    String::new()
+                } else {
+                    format!(" tagged *{}*", self.filter)
+                }
+            )
+        };
+
+        let context: Vec<String> = match self.selected_entry() {
+            Some(entry) if self.show_context => {
+                let item = &entry.item;
+                let first = item.line - item.context_before.len();
+                let mut lines = vec!["─".repeat(width)];
+                lines.extend(
+                    item.context_before
+                        .iter()
+                        .chain(item.line_content.iter())
+                        .chain(&item.context_after)
+                        .enumerate()
+                        .map(|(i, text)| format!("{:>6} │ {}", first + i, text)),
+                );
+                lines
+            }
+            _ => Vec::new(),
+        };
+
+        let list_height = height.saturating_sub(1 + context.len()).max(1);
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + list_height {
+            self.offset = self.selected + 1 - list_height;
+        }
+
+        let mut lines: Vec<Line> = visible
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(list_height)
+            .map(|(position, &index)| {
+                let entry = &self.entries[index];
+                Line {
+                    text: truncate(
+                        &format!(
+                            "{}:{} {}: {}",
+                            entry.display_path, entry.item.line, entry.item.tag, entry.item.message
+                        ),
+                        width,
+                    ),
+                    selected: position == self.selected,
+                    resolved: entry.resolved,
+                }
+            })
+            .collect();
+        if visible.is_empty() {
+            lines.push(Line::plain("No TODO items found.".to_string()));
+        }
+        while lines.len() < list_height {
+            lines.push(Line::plain(String::new()));
+        }
+
+        lines.extend(
+            context
+                .iter()
+                .map(|text| Line::plain(truncate(text, width))),
+        );
+        lines.push(Line::plain(truncate(&footer, width)));
+        lines
+    }
+}
+
+/// Cut text to at most `width` characters
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+/// Run the browser in the terminal until the user quits
+pub fn run(mut browser: Browser) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    enter(&mut stdout)?;
+    let outcome = event_loop(&mut browser, &mut stdout);
+    leave(&mut stdout)?;
+    outcome
+}
+
+fn event_loop(browser: &mut Browser, stdout: &mut io::Stdout) -> io::Result<()> {
+    loop {
+        draw(browser, stdout)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match browser.handle_key(key) {
+            Action::Continue => {}
+            Action::Quit => return Ok(()),
+            Action::Edit(path, line) => {
+                leave(stdout)?;
+                let editor = std::env::var("VISUAL")
+                    .or_else(|_| std::env::var("EDITOR"))
+                    .unwrap_or_else(|_| "vi".to_string());
+                let status = match editor_command(&editor) {
+                    Some(mut command) => command.arg(format!("+{}", line)).arg(&path).status(),
+                    None => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "not a valid command line",
+                    )),
+                };
+                enter(stdout)?;
+                if let Err(err) = status {
+                    tracing::warn!("failed to start {}: {}", editor, err);
+                }
+            }
+        }
+    }
+}
+
+fn draw(browser: &mut Browser, stdout: &mut io::Stdout) -> io::Result<()> {
+    let (width, height) = terminal::size()?;
+    queue!(
+        stdout,
+        terminal::Clear(terminal::ClearType::All),
+        cursor::MoveTo(0, 0)
+    )?;
+
+    for (row, line) in browser
+        .render(width as usize, height as usize)
+        .into_iter()
+        .enumerate()
+    {
+        queue!(stdout, cursor::MoveTo(0, row as u16))?;
+        if line.selected {
+            queue!(stdout, SetAttribute(Attribute::Reverse))?;
+        }
+        if line.resolved {
+            queue!(stdout, SetAttribute(Attribute::CrossedOut))?;
+        }
+        queue!(stdout, Print(line.text), SetAttribute(Attribute::Reset))?;
+    }
+    stdout.flush()
+}
+
+/// Build the command for an `EDITOR` value, split into words as a shell would
+///
+/// So `code --wait` runs `code` with `--wait`. `None` for an empty value or
+/// unbalanced quotes.
+fn editor_command(editor: &str) -> Option<Command> {
+    let words = shlex::split(editor)?;
+    let (program, args) = words.split_first()?;
+    let mut command = Command::new(program);
+    command.args(args);
+    Some(command)
+}
+
+fn enter(stdout: &mut io::Stdout) -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)
+}
+
+fn leave(stdout: &mut io::Stdout) -> io::Result<()> {
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use todo_tree_core::Priority;
+
+    fn entry(tag: &str, line: usize) -> Entry {
+        Entry {
+            path: PathBuf::from("/p/src/main.rs"),
+            display_path: "src/main.rs".to_string(),
+            item: TodoItem {
+                tag: tag.to_string(),
+                message: format!("{} message", tag),
+                line,
+                column: 1,
+                line_content: Some(format!("// {}: message", tag)),
+                author: None,
+                priority: Priority::from_tag(tag),
+                context_before: vec!["fn main() {".to_string()],
+                context_after: vec!["}".to_string()],
+                created_at: None,
+                file_extension: None,
+            },
+            resolved: false,
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_browser_navigation_and_actions() {
+        let mut browser = Browser::new(vec![entry("TODO", 2), entry("FIXME", 5)]);
+
+        assert_eq!(browser.handle_key(key(KeyCode::Up)), Action::Continue);
+        assert_eq!(browser.selected_entry().unwrap().item.line, 2);
+        browser.handle_key(key(KeyCode::Down));
+        browser.handle_key(key(KeyCode::Down));
+        assert_eq!(browser.selected_entry().unwrap().item.line, 5);
+
+        browser.handle_key(key(KeyCode::Char('d')));
+        assert!(browser.selected_entry().unwrap().resolved);
+        assert_eq!(
+            browser.handle_key(key(KeyCode::Char('e'))),
+            Action::Edit(PathBuf::from("/p/src/main.rs"), 5)
+        );
+        assert_eq!(browser.handle_key(key(KeyCode::Char('q'))), Action::Quit);
+    }
+
+    #[test]
+    fn test_browser_filter() {
+        let mut browser = Browser::new(vec![entry("TODO", 2), entry("FIXME", 5)]);
+
+        browser.handle_key(key(KeyCode::Char('/')));
+        browser.handle_key(key(KeyCode::Char('f')));
+        browser.handle_key(key(KeyCode::Char('i')));
+        // Keys go to the filter while it is edited
+        assert_eq!(
+            browser.handle_key(key(KeyCode::Char('q'))),
+            Action::Continue
+        );
+        browser.handle_key(key(KeyCode::Backspace));
+        browser.handle_key(key(KeyCode::Enter));
+        assert_eq!(browser.visible(), vec![1]);
+
+        browser.handle_key(key(KeyCode::Char('/')));
+        browser.handle_key(key(KeyCode::Esc));
+        assert_eq!(browser.visible(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_browser_render() {
+        let mut browser = Browser::new(vec![entry("TODO", 2), entry("FIXME", 5)]);
+        browser.handle_key(key(KeyCode::Char('d')));
+
+        let lines = browser.render(30, 5);
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0].text, "src/main.rs:2 TODO: TODO messa");
+        assert!(lines[0].selected && lines[0].resolved);
+        assert!(!lines[1].selected);
+        assert!(lines[4].text.starts_with("2 of 2 items"));
+
+        browser.handle_key(key(KeyCode::Enter));
+        let lines = browser.render(40, 8);
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[4].text, "     1 │ fn main() {");
+        assert_eq!(lines[5].text, "     2 │ // TODO: message");
+        assert_eq!(lines[6].text, "     3 │ }");
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let command = editor_command("code --wait").unwrap();
+        assert_eq!(command.get_program(), "code");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!["--wait"]);
+
+        let command = editor_command("'/opt/My Editor/bin/edit' -n").unwrap();
+        assert_eq!(command.get_program(), "/opt/My Editor/bin/edit");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!["-n"]);
+
+        assert!(editor_command("").is_none());
+        assert!(editor_command("vim 'unbalanced").is_none());
+    }
+}
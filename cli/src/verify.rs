@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::path::PathBuf;
+use todo_tree_core::{ScanResult, TodoItem};
+
+/// Tags that must reference a ticket when none are configured
+pub const DEFAULT_REQUIRED_TICKET_TAGS: &[&str] = &["FIXME", "BUG"];
+
+/// Ticket reference pattern used when none is configured (e.g. `ABC-123` or `#42`)
+pub const DEFAULT_TICKET_REGEX: &str = r"^(?:[A-Z][A-Z0-9]*-\d+|#\d+)$";
+
+/// A TODO item that is missing a valid ticket reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// File containing the item
+    pub path: PathBuf,
+
+    /// The offending item
+    pub item: TodoItem,
+}
+
+/// Compile a ticket regex, falling back to [`DEFAULT_TICKET_REGEX`]
+pub fn ticket_regex(pattern: Option<&str>) -> Result<Regex> {
+    let pattern = pattern.unwrap_or(DEFAULT_TICKET_REGEX);
+    Regex::new(pattern).with_context(|| format!("Invalid ticket regex: {}", pattern))
+}
+
+/// Find items with a required tag whose author field does not reference a ticket
+///
+/// Tags are compared case-insensitively. Violations are ordered by path and line.
+pub fn verify(
+    result: &ScanResult,
+    required_tags: &[String],
+    ticket_regex: &Regex,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (path, items) in result.sorted_files() {
+        for item in items {
+            let required = required_tags
+                .iter()
+                .any(|tag| tag.eq_ignore_ascii_case(&item.tag));
+            let has_ticket = item
+                .author
+                .as_deref()
+                .is_some_and(|author| ticket_regex.is_match(author));
+
+            if required && !has_ticket {
+                violations.push(Violation {
+                    path: path.clone(),
+                    item: item.clone(),
+                });
+            }
+        }
+    }
+
+    violations.sort_by(|a, b| a.path.cmp(&b.path).then(a.item.line.cmp(&b.item.line)));
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use todo_tree_core::Priority;
+
+    fn create_test_item(tag: &str, author: Option<&str>, line: usize) -> TodoItem {
+        TodoItem {
+            tag: tag.to_string(),
+            message: "Fix this".to_string(),
+            line,
+            column: 4,
+            line_content: None,
+            author: author.map(str::to_string),
+            priority: Priority::from_tag(tag),
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        }
+    }
+
+    fn required_tags() -> Vec<String> {
+        DEFAULT_REQUIRED_TICKET_TAGS
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_finds_missing_tickets() {
+        let mut result = ScanResult::new(PathBuf::from("/project"));
+        result.add_file(
+            PathBuf::from("/project/main.rs"),
+            vec![
+                create_test_item("FIXME", Some("PROJ-123"), 1),
+                create_test_item("FIXME", Some("alice"), 2),
+                create_test_item("bug", None, 3),
+                create_test_item("TODO", None, 4),
+                create_test_item("BUG", Some("#42"), 5),
+            ],
+        );
+
+        let regex = ticket_regex(None).unwrap();
+        let violations = verify(&result, &required_tags(), &regex);
+
+        let lines: Vec<usize> = violations.iter().map(|v| v.item.line).collect();
+        assert_eq!(lines, vec![2, 3]);
+        assert_eq!(violations[0].path, PathBuf::from("/project/main.rs"));
+    }
+
+    #[test]
+    fn test_verify_custom_regex() {
+        let mut result = ScanResult::new(PathBuf::from("/project"));
+        result.add_file(
+            PathBuf::from("/project/main.rs"),
+            vec![create_test_item("TODO", Some("GH-7"), 1)],
+        );
+
+        let regex = ticket_regex(Some(r"^JIRA-\d+$")).unwrap();
+        let violations = verify(&result, &["TODO".to_string()], &regex);
+        assert_eq!(violations.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_ticket_regex() {
+        assert!(ticket_regex(Some("(")).is_err());
+    }
+}
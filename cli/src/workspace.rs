@@ -0,0 +1,182 @@
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+
+/// A member crate of a Cargo workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    /// Package name from the member's `Cargo.toml`, or its directory name
+    pub name: String,
+
+    /// Absolute path of the member directory
+    pub path: PathBuf,
+}
+
+/// Directory containing a manifest, `.` for a bare `Cargo.toml`
+pub fn root(manifest: &Path) -> &Path {
+    manifest
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+}
+
+/// Read the members of the Cargo workspace described by `manifest`
+///
+/// Glob patterns in `members` (e.g. `crates/*`) are expanded and `exclude` is
+/// honoured. As with Cargo, matches without a `Cargo.toml` are skipped and a
+/// root `[package]` is a member too.
+pub fn members(manifest: &Path) -> Result<Vec<Member>> {
+    let root = root(manifest)
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve path: {}", manifest.display()))?;
+    let table = read_manifest(manifest)?;
+    let Some(workspace) = table.get("workspace").and_then(|value| value.as_table()) else {
+        bail!("{} has no [workspace] section", manifest.display());
+    };
+
+    let exclude: Vec<PathBuf> = string_array(workspace, "exclude")
+        .map(|path| root.join(path))
+        .collect();
+
+    let mut paths = Vec::new();
+    if table.contains_key("package") {
+        paths.push(root.clone());
+    }
+    for pattern in string_array(workspace, "members") {
+        let pattern = root.join(pattern);
+        let matches = glob::glob(&pattern.to_string_lossy())
+            .with_context(|| format!("Invalid workspace member pattern: {}", pattern.display()))?;
+        for path in matches.flatten() {
+            if path.join("Cargo.toml").is_file() && !exclude.contains(&path) {
+                paths.push(path.canonicalize()?);
+            }
+        }
+    }
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            Ok(Member {
+                name: package_name(&path)?,
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Keep only the members named in `names`, failing on names that are not members
+pub fn filter(members: Vec<Member>, names: &[String]) -> Result<Vec<Member>> {
+    if let Some(unknown) = names
+        .iter()
+        .find(|name| !members.iter().any(|member| &member.name == *name))
+    {
+        bail!("No workspace member named {}", unknown);
+    }
+
+    Ok(members
+        .into_iter()
+        .filter(|member| names.contains(&member.name))
+        .collect())
+}
+
+fn read_manifest(path: &Path) -> Result<toml::Table> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read manifest: {}", path.display()))?;
+    text.parse()
+        .with_context(|| format!("Failed to parse manifest: {}", path.display()))
+}
+
+fn string_array<'a>(table: &'a toml::Table, key: &str) -> impl Iterator<Item = &'a str> {
+    table
+        .get(key)
+        .and_then(|value| value.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.as_str())
+}
+
+fn package_name(dir: &Path) -> Result<String> {
+    let table = read_manifest(&dir.join("Cargo.toml"))?;
+    let name = table
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str());
+
+    Ok(match name {
+        Some(name) => name.to_string(),
+        None => dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_workspace() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\", \"tools\"]\nexclude = [\"crates/old\"]\n",
+        )
+        .unwrap();
+        for (dir, name) in [
+            ("crates/core", "app-core"),
+            ("crates/old", "app-old"),
+            ("tools", "tools"),
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(
+                root.join(dir).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n", name),
+            )
+            .unwrap();
+        }
+        fs::create_dir_all(root.join("crates/docs")).unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_members() {
+        let temp_dir = create_workspace();
+        let members = members(&temp_dir.path().join("Cargo.toml")).unwrap();
+
+        let names: Vec<_> = members.iter().map(|member| member.name.as_str()).collect();
+        assert_eq!(names, vec!["app-core", "tools"]);
+        assert!(members[0].path.ends_with("crates/core"));
+        assert!(members[0].path.is_absolute());
+    }
+
+    #[test]
+    fn test_members_requires_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let manifest = temp_dir.path().join("Cargo.toml");
+        fs::write(&manifest, "[package]\nname = \"solo\"\n").unwrap();
+
+        assert!(members(&manifest).is_err());
+    }
+
+    #[test]
+    fn test_filter() {
+        let temp_dir = create_workspace();
+        let members = members(&temp_dir.path().join("Cargo.toml")).unwrap();
+
+        let filtered = filter(members.clone(), &["tools".to_string()]).unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name, "tools");
+
+        assert!(filter(members, &["missing".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_root() {
+        assert_eq!(root(Path::new("Cargo.toml")), Path::new("."));
+        assert_eq!(root(Path::new("repo/Cargo.toml")), Path::new("repo"));
+    }
+}
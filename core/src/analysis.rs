@@ -0,0 +1,275 @@
+use crate::types::{ScanResult, TodoItem};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Longest message shown in a DOT node label, in characters
+const DOT_LABEL_MESSAGE_LEN: usize = 40;
+
+/// A group of items whose messages are similar enough to be the same concern
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemCluster {
+    /// Message of the first item in the cluster, which the others were compared to
+    pub representative: String,
+
+    /// Items in the cluster, ordered by path and line
+    pub items: Vec<(PathBuf, TodoItem)>,
+}
+
+impl ItemCluster {
+    /// Number of items in the cluster
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the cluster has no items
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl ScanResult {
+    /// Group items with similar messages, as candidates for deduplication
+    ///
+    /// Messages are compared by the Jaccard similarity of their lowercased
+    /// words; an item joins the first cluster whose representative is at least
+    /// `threshold` (0.0-1.0) similar. Only clusters of two or more items are
+    /// returned, largest first.
+    pub fn cluster_by_message_similarity(&self, threshold: f64) -> Vec<ItemCluster> {
+        let mut items: Vec<(PathBuf, TodoItem)> = self.all_items();
+        items.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.line.cmp(&b.1.line)));
+
+        let mut clusters: Vec<(HashSet<String>, ItemCluster)> = Vec::new();
+        for (path, item) in items {
+            let words = message_words(&item.message);
+            if words.is_empty() {
+                continue;
+            }
+
+            match clusters
+                .iter_mut()
+                .find(|(representative, _)| jaccard(representative, &words) >= threshold)
+            {
+                Some((_, cluster)) => cluster.items.push((path, item)),
+                None => {
+                    let cluster = ItemCluster {
+                        representative: item.message.clone(),
+                        items: vec![(path, item)],
+                    };
+                    clusters.push((words, cluster));
+                }
+            }
+        }
+
+        let mut clusters: Vec<ItemCluster> = clusters
+            .into_iter()
+            .map(|(_, cluster)| cluster)
+            .filter(|cluster| cluster.len() > 1)
+            .collect();
+        clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.len()));
+        clusters
+    }
+
+    /// Render the references between items and issues as a Graphviz DOT graph
+    ///
+    /// Every `#123` in a message becomes an edge from the item to an issue node,
+    /// labeled with the phrase before it (`depends on`, `blocked by`, `blocks`,
+    /// `see also`) or `refs`. Items referencing the same issue share that node,
+    /// so related TODOs end up connected. Items without references are left out.
+    pub fn to_dot_graph(&self) -> String {
+        let reference =
+            Regex::new(r"(?i)(?:\b(depends\s+on|blocked\s+by|blocks|see\s+also)\s*:?\s*)?#(\d+)\b")
+                .expect("reference pattern is valid");
+
+        let mut items = self.all_items();
+        items.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.line.cmp(&b.1.line)));
+
+        let mut nodes = String::new();
+        let mut edges = String::new();
+        let mut issues = BTreeSet::new();
+        for (index, (path, item)) in items.iter().enumerate() {
+            let mut references = reference.captures_iter(&item.message).peekable();
+            if references.peek().is_none() {
+                continue;
+            }
+
+            let path = path
+                .strip_prefix(self.root.as_deref().unwrap_or(Path::new("")))
+                .unwrap_or(path);
+            nodes.push_str(&format!(
+                "  \"todo{}\" [label=\"{}: {}\\n{}:{}\"];\n",
+                index,
+                dot_escape(&item.tag),
+                dot_escape(&short_message(&item.message)),
+                dot_escape(&path.display().to_string()),
+                item.line
+            ));
+
+            for captures in references {
+                let relation = captures.get(1).map_or("refs".to_string(), |relation| {
+                    relation
+                        .as_str()
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .to_lowercase()
+                });
+                let issue: u64 = match captures[2].parse() {
+                    Ok(issue) => issue,
+                    Err(_) => continue,
+                };
+                issues.insert(issue);
+                edges.push_str(&format!(
+                    "  \"todo{}\" -> \"issue{}\" [label=\"{}\"];\n",
+                    index, issue, relation
+                ));
+            }
+        }
+
+        let mut dot = String::from("digraph todos {\n  rankdir=LR;\n  node [shape=box];\n");
+        dot.push_str(&nodes);
+        for issue in issues {
+            dot.push_str(&format!(
+                "  \"issue{}\" [label=\"#{}\", shape=ellipse];\n",
+                issue, issue
+            ));
+        }
+        dot.push_str(&edges);
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// First characters of a message, with `...` if it was cut
+fn short_message(message: &str) -> String {
+    if message.chars().count() <= DOT_LABEL_MESSAGE_LEN {
+        return message.to_string();
+    }
+    let short: String = message.chars().take(DOT_LABEL_MESSAGE_LEN - 3).collect();
+    format!("{}...", short.trim_end())
+}
+
+/// Escape text for a double-quoted DOT string
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Lowercased alphanumeric words of a message
+fn message_words(message: &str) -> HashSet<String> {
+    message
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard similarity of two word sets: shared words over all words
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::priority::Priority;
+
+    fn create_test_item(message: &str, line: usize) -> TodoItem {
+        TodoItem {
+            tag: "TODO".to_string(),
+            message: message.to_string(),
+            line,
+            column: 1,
+            line_content: None,
+            author: None,
+            priority: Priority::Medium,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
+        }
+    }
+
+    #[test]
+    fn test_jaccard() {
+        let a = message_words("Handle the error");
+        let b = message_words("handle error, properly");
+        assert_eq!(jaccard(&a, &b), 0.5);
+        assert_eq!(jaccard(&a, &a), 1.0);
+        assert_eq!(jaccard(&HashSet::new(), &HashSet::new()), 0.0);
+    }
+
+    #[test]
+    fn test_cluster_by_message_similarity() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![
+                create_test_item("Handle the error", 1),
+                create_test_item("Add logging", 2),
+                create_test_item("handle the error here", 3),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("b.rs"),
+            vec![
+                create_test_item("Handle the error!", 1),
+                create_test_item("add more logging", 2),
+                create_test_item("Unrelated", 3),
+            ],
+        );
+
+        let clusters = result.cluster_by_message_similarity(0.5);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].representative, "Handle the error");
+        assert_eq!(clusters[0].len(), 3);
+        assert_eq!(clusters[1].representative, "Add logging");
+        assert_eq!(clusters[1].items[1].0, PathBuf::from("b.rs"));
+
+        assert_eq!(result.cluster_by_message_similarity(1.0).len(), 1);
+    }
+
+    #[test]
+    fn test_to_dot_graph() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/src/a.rs"),
+            vec![
+                create_test_item("Depends on #12, see also #7", 3),
+                create_test_item("No reference here", 5),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("/test/src/b.rs"),
+            vec![create_test_item("Handle \"quoted\" case, blocks #12", 9)],
+        );
+
+        assert_eq!(
+            result.to_dot_graph(),
+            r##"digraph todos {
+  rankdir=LR;
+  node [shape=box];
+  "todo0" [label="TODO: Depends on #12, see also #7\nsrc/a.rs:3"];
+  "todo2" [label="TODO: Handle \"quoted\" case, blocks #12\nsrc/b.rs:9"];
+  "issue7" [label="#7", shape=ellipse];
+  "issue12" [label="#12", shape=ellipse];
+  "todo0" -> "issue12" [label="depends on"];
+  "todo0" -> "issue7" [label="see also"];
+  "todo2" -> "issue12" [label="blocks"];
+}
+"##
+        );
+    }
+
+    #[test]
+    fn test_short_message() {
+        assert_eq!(short_message("short"), "short");
+        let long = "a".repeat(60);
+        assert_eq!(short_message(&long).chars().count(), DOT_LABEL_MESSAGE_LEN);
+        assert!(short_message(&long).ends_with("..."));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::priority::Priority;
+use chrono::NaiveDate;
+use regex::Regex;
+
+/// Criteria for selecting TODO items, combined with AND
+///
+/// Unset fields match every item. Used by [`TodoItem::matches_filter`](crate::TodoItem::matches_filter)
+/// and [`ScanResult::filter`](crate::ScanResult::filter).
+#[derive(Debug, Clone, Default)]
+pub struct ItemFilter {
+    /// Keep items whose tag is one of these (case-insensitive)
+    pub tags: Option<Vec<String>>,
+
+    /// Keep items with this author (case-insensitive)
+    pub author: Option<String>,
+
+    /// Keep items at or above this priority
+    pub min_priority: Option<Priority>,
+
+    /// Keep items whose message matches this regex
+    pub message_regex: Option<Regex>,
+
+    /// Keep items created on or after this date (UTC), per `created_at`
+    ///
+    /// Items without a `created_at` timestamp do not match.
+    pub since_date: Option<NaiveDate>,
+}
+
+impl ItemFilter {
+    /// Filter matching a single tag (case-insensitive)
+    pub fn tag(tag: &str) -> Self {
+        Self {
+            tags: Some(vec![tag.to_string()]),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+/// Programming languages todo-tree can recognize from file names
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    Java,
+    Kotlin,
+    C,
+    Cpp,
+    CSharp,
+    Ruby,
+    Php,
+    Swift,
+    Shell,
+    Lua,
+    Sql,
+    Html,
+}
+
+impl Language {
+    /// All supported languages
+    pub const ALL: [Language; 17] = [
+        Language::Rust,
+        Language::Python,
+        Language::JavaScript,
+        Language::TypeScript,
+        Language::Go,
+        Language::Java,
+        Language::Kotlin,
+        Language::C,
+        Language::Cpp,
+        Language::CSharp,
+        Language::Ruby,
+        Language::Php,
+        Language::Swift,
+        Language::Shell,
+        Language::Lua,
+        Language::Sql,
+        Language::Html,
+    ];
+
+    /// Infer the language from a file extension (without the dot, any case)
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let language = match extension.to_lowercase().as_str() {
+            "rs" => Language::Rust,
+            "py" | "pyi" | "pyw" => Language::Python,
+            "js" | "jsx" | "mjs" | "cjs" => Language::JavaScript,
+            "ts" | "tsx" | "mts" | "cts" => Language::TypeScript,
+            "go" => Language::Go,
+            "java" => Language::Java,
+            "kt" | "kts" => Language::Kotlin,
+            "c" | "h" => Language::C,
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Language::Cpp,
+            "cs" => Language::CSharp,
+            "rb" | "rake" => Language::Ruby,
+            "php" => Language::Php,
+            "swift" => Language::Swift,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "lua" => Language::Lua,
+            "sql" => Language::Sql,
+            "html" | "htm" | "xml" => Language::Html,
+            _ => return None,
+        };
+        Some(language)
+    }
+
+    /// Comment markers used by the language, line comments first
+    pub fn comment_markers(&self) -> &'static [&'static str] {
+        match self {
+            Language::Python | Language::Ruby | Language::Shell => &["#"],
+            Language::Php => &["//", "#", "/*"],
+            Language::Lua | Language::Sql => &["--"],
+            Language::Html => &["<!--"],
+            _ => &["//", "/*"],
+        }
+    }
+
+    /// Files in a project root that indicate the language (e.g. `Cargo.toml`)
+    pub fn marker_files(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &["Cargo.toml"],
+            Language::Python => &["pyproject.toml", "setup.py", "requirements.txt"],
+            Language::JavaScript => &["package.json"],
+            Language::TypeScript => &["tsconfig.json"],
+            Language::Go => &["go.mod"],
+            Language::Java => &["pom.xml", "build.gradle"],
+            Language::Kotlin => &["build.gradle.kts"],
+            Language::C | Language::Cpp => &["CMakeLists.txt"],
+            Language::Ruby => &["Gemfile"],
+            Language::Php => &["composer.json"],
+            Language::Swift => &["Package.swift"],
+            Language::CSharp | Language::Shell | Language::Lua | Language::Sql | Language::Html => {
+                &[]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_from_extension() {
+        assert_eq!(Language::from_extension("rs"), Some(Language::Rust));
+        assert_eq!(Language::from_extension("PYI"), Some(Language::Python));
+        assert_eq!(Language::from_extension("tsx"), Some(Language::TypeScript));
+        assert_eq!(Language::from_extension("md"), None);
+    }
+
+    #[test]
+    fn test_language_comment_markers() {
+        assert_eq!(Language::Python.comment_markers(), &["#"]);
+        assert_eq!(Language::Rust.comment_markers(), &["//", "/*"]);
+    }
+
+    #[test]
+    fn test_language_serialization() {
+        let json = serde_json::to_string(&Language::CSharp).unwrap();
+        assert_eq!(json, "\"c-sharp\"");
+    }
+}
@@ -1,7 +1,13 @@
+pub mod analysis;
+pub mod filter;
+pub mod language;
 pub mod priority;
 pub mod tags;
 pub mod types;
 
+pub use analysis::ItemCluster;
+pub use filter::ItemFilter;
+pub use language::Language;
 pub use priority::Priority;
 pub use tags::{DEFAULT_TAGS, TagDefinition};
 pub use types::{FileResult, ScanResult, Summary, TodoItem};
@@ -1,11 +1,15 @@
 use serde::{Deserialize, Serialize};
 
 /// Priority levels for different tag types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Priority {
+    #[serde(alias = "low")]
     Low,
+    #[serde(alias = "medium")]
     Medium,
+    #[serde(alias = "high")]
     High,
+    #[serde(alias = "critical")]
     Critical,
 }
 
@@ -48,6 +52,24 @@ impl std::fmt::Display for Priority {
     }
 }
 
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    /// Parse a priority name, ignoring case (e.g. `high` or `Critical`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "critical" => Ok(Priority::Critical),
+            "high" => Ok(Priority::High),
+            "medium" => Ok(Priority::Medium),
+            "low" => Ok(Priority::Low),
+            _ => Err(format!(
+                "invalid priority '{}' (expected critical, high, medium or low)",
+                s
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +161,13 @@ mod tests {
         assert_eq!(Priority::Critical, Priority::Critical);
         assert_ne!(Priority::Critical, Priority::High);
     }
+
+    #[test]
+    fn test_priority_from_str() {
+        assert_eq!("critical".parse::<Priority>(), Ok(Priority::Critical));
+        assert_eq!("High".parse::<Priority>(), Ok(Priority::High));
+        assert_eq!("MEDIUM".parse::<Priority>(), Ok(Priority::Medium));
+        assert_eq!("low".parse::<Priority>(), Ok(Priority::Low));
+        assert!("urgent".parse::<Priority>().is_err());
+    }
 }
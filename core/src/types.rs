@@ -1,7 +1,13 @@
+use crate::filter::ItemFilter;
 use crate::priority::Priority;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use chrono::NaiveTime;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Represents a found TODO item in the source code
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -28,9 +34,30 @@ pub struct TodoItem {
 
     /// Priority level inferred from tag type
     pub priority: Priority,
+
+    /// Lines preceding the item, oldest first (only collected when requested)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_before: Vec<String>,
+
+    /// Lines following the item (only collected when requested)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_after: Vec<String>,
+
+    /// Unix time (seconds) of the commit that last touched the line, from `git blame`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+
+    /// Extension of the file the item was found in, without the dot (set by the scanner)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_extension: Option<String>,
 }
 
 impl TodoItem {
+    /// Extension of the containing file (e.g. `rs`), if the scanner recorded one
+    pub fn file_extension(&self) -> Option<&str> {
+        self.file_extension.as_deref()
+    }
+
     /// Format the author for display, returns "(author)" or empty string
     pub fn format_author(&self) -> String {
         self.author
@@ -38,6 +65,86 @@ impl TodoItem {
             .map(|a| format!("({})", a))
             .unwrap_or_default()
     }
+
+    /// Check whether the item matches every criterion set in the filter
+    pub fn matches_filter(&self, filter: &ItemFilter) -> bool {
+        if let Some(tags) = &filter.tags
+            && !tags.iter().any(|tag| tag.eq_ignore_ascii_case(&self.tag))
+        {
+            return false;
+        }
+
+        if let Some(author) = &filter.author
+            && !self
+                .author
+                .as_ref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(author))
+        {
+            return false;
+        }
+
+        if filter
+            .min_priority
+            .is_some_and(|min_priority| self.priority < min_priority)
+        {
+            return false;
+        }
+
+        if let Some(since_date) = filter.since_date {
+            let since = since_date.and_time(NaiveTime::MIN).and_utc().timestamp();
+            if self.created_at.is_none_or(|created_at| created_at < since) {
+                return false;
+            }
+        }
+
+        filter
+            .message_regex
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(&self.message))
+    }
+
+    /// Format the item with a template
+    ///
+    /// Supported placeholders are `{tag}`, `{line}`, `{column}`, `{message}`,
+    /// `{author}` (empty if none) and `{priority}`. Unknown placeholders are left as-is.
+    pub fn format(&self, template: &str) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+
+            let value = match &rest[1..end] {
+                "tag" => Some(self.tag.clone()),
+                "line" => Some(self.line.to_string()),
+                "column" => Some(self.column.to_string()),
+                "message" => Some(self.message.clone()),
+                "author" => Some(self.author.clone().unwrap_or_default()),
+                "priority" => Some(self.priority.to_string()),
+                _ => None,
+            };
+
+            match value {
+                Some(value) => {
+                    output.push_str(&value);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    // Keep the brace and continue after it, so `{{tag}` still expands
+                    output.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        output.push_str(rest);
+        output
+    }
 }
 
 /// A file containing TODO items (for JSON serialization)
@@ -60,6 +167,12 @@ pub struct Summary {
     pub files_scanned: usize,
     /// Count of items per tag type
     pub tag_counts: HashMap<String, usize>,
+    /// Whether the scan stopped early and the result is partial
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Whether the scan hit its time limit (`truncated` is set as well)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
 }
 
 impl Summary {
@@ -72,6 +185,16 @@ impl Summary {
         }
     }
 
+    /// Fraction of scanned files without any TODO items (1.0 if nothing was scanned)
+    pub fn coverage_ratio(&self) -> f64 {
+        if self.files_scanned > 0 {
+            self.files_scanned.saturating_sub(self.files_with_todos) as f64
+                / self.files_scanned as f64
+        } else {
+            1.0
+        }
+    }
+
     /// Calculate percentage for a given tag count
     pub fn tag_percentage(&self, count: usize) -> f64 {
         if self.total_count > 0 {
@@ -112,6 +235,8 @@ impl ScanResult {
                 files_with_todos: 0,
                 files_scanned: 0,
                 tag_counts: HashMap::new(),
+                truncated: false,
+                timed_out: false,
             },
             root: Some(root),
         }
@@ -136,6 +261,71 @@ impl ScanResult {
         }
     }
 
+    /// Fraction of scanned files without any TODO items (see [`Summary::coverage_ratio`])
+    pub fn coverage_ratio(&self) -> f64 {
+        self.summary.coverage_ratio()
+    }
+
+    /// Count items by age, measured from `created_at` to now
+    ///
+    /// `buckets` are the increasing boundaries between ranges: `[1 week, 1 month]`
+    /// yields `0..1 week`, `1 week..1 month` and `1 month..Duration::MAX`. Items
+    /// without a timestamp are not counted.
+    pub fn age_histogram(&self, buckets: &[Duration]) -> Vec<(Range<Duration>, usize)> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        let mut bounds = vec![Duration::ZERO];
+        bounds.extend_from_slice(buckets);
+        bounds.push(Duration::MAX);
+        let mut histogram: Vec<(Range<Duration>, usize)> = bounds
+            .windows(2)
+            .map(|bound| (bound[0]..bound[1], 0))
+            .collect();
+
+        for (_, item) in self.iter() {
+            let Some(created_at) = item.created_at else {
+                continue;
+            };
+            let age = Duration::from_secs(now.saturating_sub(created_at).max(0) as u64);
+            if let Some((_, count)) = histogram.iter_mut().find(|(range, _)| range.contains(&age)) {
+                *count += 1;
+            }
+        }
+
+        histogram
+    }
+
+    /// Number of files containing at least one TODO item (O(1), read from the summary)
+    pub fn file_count(&self) -> usize {
+        self.summary.files_with_todos
+    }
+
+    /// Unique tags found in the scan (O(t) in the number of distinct tags)
+    pub fn tag_set(&self) -> HashSet<&str> {
+        self.summary.tag_counts.keys().map(String::as_str).collect()
+    }
+
+    /// Tag with the most items, ties broken alphabetically (O(t))
+    pub fn most_common_tag(&self) -> Option<&str> {
+        self.summary
+            .tag_counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(tag, _)| tag.as_str())
+    }
+
+    /// Tag with the fewest items, ties broken alphabetically (O(t))
+    pub fn least_common_tag(&self) -> Option<&str> {
+        self.summary
+            .tag_counts
+            .iter()
+            .min_by(|a, b| a.1.cmp(b.1).then_with(|| a.0.cmp(b.0)))
+            .map(|(tag, _)| tag.as_str())
+    }
+
     /// Add TODO items for a file
     pub fn add_file(&mut self, path: PathBuf, items: Vec<TodoItem>) {
         self.summary.files_scanned += 1;
@@ -152,6 +342,22 @@ impl ScanResult {
         }
     }
 
+    /// Fold another result into this one, e.g. to combine scans of several roots
+    ///
+    /// This result's root is kept. Files present in both results keep the items
+    /// from `other` but are counted twice in `files_scanned`.
+    pub fn merge(&mut self, other: ScanResult) {
+        self.files_map.extend(other.files_map);
+        if let Some(files) = other.files {
+            self.files.get_or_insert_with(Vec::new).extend(files);
+        }
+        self.summary.files_scanned += other.summary.files_scanned;
+        self.summary.truncated |= other.summary.truncated;
+        self.summary.timed_out |= other.summary.timed_out;
+
+        self.recalculate_summary();
+    }
+
     /// Get all TODO items as a flat list
     pub fn all_items(&self) -> Vec<(PathBuf, TodoItem)> {
         let mut items = Vec::new();
@@ -163,6 +369,42 @@ impl ScanResult {
         items
     }
 
+    /// Iterate over all items without cloning, in no particular order
+    ///
+    /// Covers both the internal file map and JSON-style `files`.
+    pub fn iter(&self) -> Iter<'_> {
+        let from_map = self
+            .files_map
+            .iter()
+            .flat_map(|(path, items)| items.iter().map(move |item| (path.as_path(), item)));
+        let from_files = self.files.iter().flatten().flat_map(|file| {
+            file.items
+                .iter()
+                .map(move |item| (Path::new(&file.path), item))
+        });
+
+        Iter {
+            inner: Box::new(from_map.chain(from_files)),
+        }
+    }
+
+    /// Get the items of a file within `radius` lines of `line`, nearest first
+    ///
+    /// A radius of 0 only matches items on `line` itself. Items at the same
+    /// distance keep their file order, so the one above the line comes first
+    /// when the file is in line order. Unknown files have no items.
+    pub fn items_near_line(&self, path: &Path, line: usize, radius: usize) -> Vec<&TodoItem> {
+        let mut items: Vec<&TodoItem> = self
+            .files_map
+            .get(path)
+            .into_iter()
+            .flatten()
+            .filter(|item| item.line.abs_diff(line) <= radius)
+            .collect();
+        items.sort_by_key(|item| item.line.abs_diff(line));
+        items
+    }
+
     /// Get files sorted by path
     pub fn sorted_files(&self) -> Vec<(&PathBuf, &Vec<TodoItem>)> {
         let mut files: Vec<_> = self.files_map.iter().collect();
@@ -170,25 +412,143 @@ impl ScanResult {
         files
     }
 
-    /// Filter items by tag
+    /// Keep only the items matching the filter
+    ///
+    /// Files left without items are dropped. `files_scanned` is kept.
+    pub fn filter(&self, filter: &ItemFilter) -> ScanResult {
+        let mut result = self.clone();
+        result.retain_items(|item| item.matches_filter(filter));
+        result
+    }
+
+    /// Filter items by tag (case-insensitive)
     pub fn filter_by_tag(&self, tag: &str) -> ScanResult {
+        self.filter(&ItemFilter::tag(tag))
+    }
+
+    /// Split the result into one result per priority level
+    ///
+    /// Only priorities that have at least one item are present in the map.
+    /// Each result keeps the original `files_scanned` count.
+    pub fn partition_by_priority(&self) -> HashMap<Priority, ScanResult> {
         let root = self.root.clone().unwrap_or_else(|| PathBuf::from("."));
-        let mut result = ScanResult::new(root);
-        result.summary.files_scanned = self.summary.files_scanned;
+        let mut partitions: HashMap<Priority, ScanResult> = HashMap::new();
 
         for (path, items) in &self.files_map {
-            let filtered: Vec<TodoItem> = items
-                .iter()
-                .filter(|item| item.tag.eq_ignore_ascii_case(tag))
-                .cloned()
-                .collect();
+            let mut by_priority: HashMap<Priority, Vec<TodoItem>> = HashMap::new();
+            for item in items {
+                by_priority
+                    .entry(item.priority)
+                    .or_default()
+                    .push(item.clone());
+            }
 
-            if !filtered.is_empty() {
-                result.add_file(path.clone(), filtered);
+            for (priority, items) in by_priority {
+                partitions
+                    .entry(priority)
+                    .or_insert_with(|| ScanResult::new(root.clone()))
+                    .add_file(path.clone(), items);
             }
         }
 
-        result
+        // add_file counts every file as scanned, restore the original count
+        for result in partitions.values_mut() {
+            result.summary.files_scanned = self.summary.files_scanned;
+        }
+
+        partitions
+    }
+
+    /// Group files by their parent directory, relative to the scan root
+    ///
+    /// Files directly in the root are grouped under `.`. Each result keeps the
+    /// original root, and its `files_scanned` counts only the files in the group.
+    pub fn by_directory(&self) -> HashMap<PathBuf, ScanResult> {
+        let root = self.root.clone().unwrap_or_else(|| PathBuf::from("."));
+        let mut directories: HashMap<PathBuf, ScanResult> = HashMap::new();
+
+        for (path, items) in &self.files_map {
+            let relative = path.strip_prefix(&root).unwrap_or(path);
+            let directory = match relative.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+
+            directories
+                .entry(directory)
+                .or_insert_with(|| ScanResult::new(root.clone()))
+                .add_file(path.clone(), items.clone());
+        }
+
+        directories
+    }
+
+    /// Keep only the files whose path matches the predicate, updating the summary in place
+    ///
+    /// `files_scanned` is left untouched.
+    pub fn retain_files<F: FnMut(&Path) -> bool>(&mut self, mut predicate: F) {
+        self.files_map.retain(|path, _| predicate(path));
+        if let Some(files) = &mut self.files {
+            files.retain(|file| predicate(Path::new(&file.path)));
+        }
+
+        self.recalculate_summary();
+    }
+
+    /// Keep only the items matching the predicate, updating the summary in place
+    ///
+    /// Files left without items are removed. `files_scanned` is left untouched.
+    pub fn retain_items<F: FnMut(&TodoItem) -> bool>(&mut self, mut predicate: F) {
+        self.files_map.retain(|_, items| {
+            items.retain(&mut predicate);
+            !items.is_empty()
+        });
+        if let Some(files) = &mut self.files {
+            files.retain_mut(|file| {
+                file.items.retain(&mut predicate);
+                !file.items.is_empty()
+            });
+        }
+
+        self.recalculate_summary();
+    }
+
+    /// Remove the author from every item, e.g. to keep names out of shared reports
+    pub fn strip_authors(&mut self) {
+        let items = self
+            .files_map
+            .values_mut()
+            .chain(self.files.iter_mut().flatten().map(|file| &mut file.items));
+        for item in items.flatten() {
+            item.author = None;
+        }
+    }
+
+    /// Recompute item, file and tag counts from the stored files
+    ///
+    /// Called by the `retain_*` methods; call it yourself after changing
+    /// `files` or the items of a file directly. `files_scanned` and `truncated`
+    /// describe the scan rather than the items, so they are left untouched.
+    pub fn recalculate_summary(&mut self) {
+        let items = self
+            .files_map
+            .values()
+            .chain(self.files.iter().flatten().map(|file| &file.items));
+
+        let mut total_count = 0;
+        let mut files_with_todos = 0;
+        let mut tag_counts = HashMap::new();
+        for file_items in items.filter(|items| !items.is_empty()) {
+            files_with_todos += 1;
+            total_count += file_items.len();
+            for item in file_items {
+                *tag_counts.entry(item.tag.clone()).or_insert(0) += 1;
+            }
+        }
+
+        self.summary.total_count = total_count;
+        self.summary.files_with_todos = files_with_todos;
+        self.summary.tag_counts = tag_counts;
     }
 
     /// Convert to JSON-friendly format with FileResult list
@@ -213,6 +573,23 @@ impl ScanResult {
         }
     }
 
+    /// Write the JSON of [`to_json_format`](Self::to_json_format) without building it first
+    ///
+    /// Each file entry is serialized from the stored items as it is reached, so
+    /// the items are never copied. The output is compact and byte-for-byte what
+    /// `serde_json::to_writer` gives for `to_json_format()`. As in
+    /// [`get_files`](Self::get_files), JSON-style `files` are used when present.
+    pub fn serialize_incremental<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        serde_json::to_writer(writer, &self.json_view())?;
+        Ok(())
+    }
+
+    /// The value [`serialize_incremental`](Self::serialize_incremental) writes, for
+    /// embedding in a larger document or another formatter (e.g. pretty-printed)
+    pub fn json_view(&self) -> impl Serialize + '_ {
+        IncrementalJson(self)
+    }
+
     /// Get files from either format
     pub fn get_files(&self) -> Vec<FileResult> {
         if let Some(files) = &self.files {
@@ -223,9 +600,109 @@ impl ScanResult {
     }
 }
 
+/// Borrowing iterator over the `(path, item)` pairs of a [`ScanResult`]
+pub struct Iter<'a> {
+    inner: Box<dyn Iterator<Item = (&'a Path, &'a TodoItem)> + 'a>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a Path, &'a TodoItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Owning iterator over the `(path, item)` pairs of a [`ScanResult`]
+pub struct IntoIter {
+    inner: Box<dyn Iterator<Item = (PathBuf, TodoItem)>>,
+}
+
+impl Iterator for IntoIter {
+    type Item = (PathBuf, TodoItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'a> IntoIterator for &'a ScanResult {
+    type Item = (&'a Path, &'a TodoItem);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for ScanResult {
+    type Item = (PathBuf, TodoItem);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let from_map = self
+            .files_map
+            .into_iter()
+            .flat_map(|(path, items)| items.into_iter().map(move |item| (path.clone(), item)));
+        let from_files = self.files.into_iter().flatten().flat_map(|file| {
+            let path = PathBuf::from(file.path);
+            file.items.into_iter().map(move |item| (path.clone(), item))
+        });
+
+        IntoIter {
+            inner: Box::new(from_map.chain(from_files)),
+        }
+    }
+}
+
+/// Serializes a [`ScanResult`] in the `to_json_format` layout without cloning it
+struct IncrementalJson<'a>(&'a ScanResult);
+
+/// The `files` array of [`IncrementalJson`], written one entry at a time
+struct IncrementalFiles<'a>(&'a ScanResult);
+
+/// A borrowed [`FileResult`]
+#[derive(Serialize)]
+struct FileEntry<'a> {
+    path: &'a str,
+    items: &'a [TodoItem],
+}
+
+impl Serialize for IncrementalJson<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ScanResult", 2)?;
+        state.serialize_field("files", &IncrementalFiles(self.0))?;
+        state.serialize_field("summary", &self.0.summary)?;
+        state.end()
+    }
+}
+
+impl Serialize for IncrementalFiles<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let Some(files) = &self.0.files {
+            return serializer.collect_seq(files.iter().map(|file| FileEntry {
+                path: &file.path,
+                items: &file.items,
+            }));
+        }
+
+        // Sorted by displayed path, like `to_json_format`
+        let mut files: Vec<(String, &Vec<TodoItem>)> = self
+            .0
+            .files_map
+            .iter()
+            .map(|(path, items)| (path.display().to_string(), items))
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        serializer.collect_seq(files.iter().map(|(path, items)| FileEntry { path, items }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::NaiveDate;
 
     fn create_test_item(tag: &str, message: &str, line: usize) -> TodoItem {
         TodoItem {
@@ -236,6 +713,10 @@ mod tests {
             line_content: Some(format!("// {}: {}", tag, message)),
             author: None,
             priority: Priority::from_tag(tag),
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+            created_at: None,
+            file_extension: None,
         }
     }
 
@@ -248,6 +729,39 @@ mod tests {
         assert_eq!(item.format_author(), "(alice)");
     }
 
+    #[test]
+    fn test_todo_item_file_extension() {
+        let mut item = create_test_item("TODO", "Test", 1);
+        assert_eq!(item.file_extension(), None);
+
+        item.file_extension = Some("rs".to_string());
+        assert_eq!(item.file_extension(), Some("rs"));
+    }
+
+    #[test]
+    fn test_todo_item_format() {
+        let mut item = create_test_item("FIXME", "Fix the parser", 12);
+        item.column = 4;
+
+        assert_eq!(
+            item.format("{tag}:{line}:{column} {message} [{priority}] {author}"),
+            format!("FIXME:12:4 Fix the parser [{}] ", item.priority)
+        );
+
+        item.author = Some("alice".to_string());
+        assert_eq!(item.format("{author} - {tag}"), "alice - FIXME");
+    }
+
+    #[test]
+    fn test_todo_item_format_unknown_placeholders() {
+        let item = create_test_item("TODO", "Test", 1);
+
+        assert_eq!(item.format("{file}:{line}"), "{file}:1");
+        assert_eq!(item.format("{{tag}}"), "{TODO}");
+        assert_eq!(item.format("open { brace"), "open { brace");
+        assert_eq!(item.format("no placeholders"), "no placeholders");
+    }
+
     #[test]
     fn test_scan_result_new() {
         let root = PathBuf::from("/test");
@@ -283,6 +797,35 @@ mod tests {
         assert_eq!(result.summary.total_count, 0);
     }
 
+    #[test]
+    fn test_scan_result_merge() {
+        let mut result = ScanResult::new(PathBuf::from("/ws"));
+        result.add_file(
+            PathBuf::from("/ws/a/lib.rs"),
+            vec![create_test_item("TODO", "First", 1)],
+        );
+        result.add_file(PathBuf::from("/ws/a/empty.rs"), vec![]);
+
+        let mut other = ScanResult::new(PathBuf::from("/ws/b"));
+        other.add_file(
+            PathBuf::from("/ws/b/main.rs"),
+            vec![
+                create_test_item("FIXME", "Second", 2),
+                create_test_item("TODO", "Third", 3),
+            ],
+        );
+        other.summary.truncated = true;
+
+        result.merge(other);
+
+        assert_eq!(result.root, Some(PathBuf::from("/ws")));
+        assert_eq!(result.summary.files_scanned, 3);
+        assert_eq!(result.summary.files_with_todos, 2);
+        assert_eq!(result.summary.total_count, 3);
+        assert_eq!(result.summary.tag_counts.get("TODO"), Some(&2));
+        assert!(result.summary.truncated);
+    }
+
     #[test]
     fn test_scan_result_tag_counts() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
@@ -298,6 +841,34 @@ mod tests {
         assert_eq!(result.summary.tag_counts.get("FIXME"), Some(&1));
     }
 
+    #[test]
+    fn test_scan_result_accessors() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        assert_eq!(result.file_count(), 0);
+        assert!(result.tag_set().is_empty());
+        assert_eq!(result.most_common_tag(), None);
+        assert_eq!(result.least_common_tag(), None);
+
+        result.add_file(
+            PathBuf::from("/test/a.rs"),
+            vec![
+                create_test_item("TODO", "one", 1),
+                create_test_item("TODO", "two", 2),
+                create_test_item("FIXME", "three", 3),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("/test/b.rs"),
+            vec![create_test_item("NOTE", "four", 1)],
+        );
+
+        assert_eq!(result.file_count(), 2);
+        assert_eq!(result.tag_set(), HashSet::from(["TODO", "FIXME", "NOTE"]));
+        assert_eq!(result.most_common_tag(), Some("TODO"));
+        // FIXME and NOTE both appear once; the alphabetically first wins
+        assert_eq!(result.least_common_tag(), Some("FIXME"));
+    }
+
     #[test]
     fn test_scan_result_filter_by_tag() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
@@ -314,6 +885,153 @@ mod tests {
         assert_eq!(filtered.summary.tag_counts.get("TODO"), Some(&2));
     }
 
+    #[test]
+    fn test_todo_item_matches_filter() {
+        let mut item = create_test_item("FIXME", "Handle the timeout", 1);
+        item.author = Some("Alice".to_string());
+        item.priority = Priority::Critical;
+
+        assert!(item.matches_filter(&ItemFilter::default()));
+        assert!(item.matches_filter(&ItemFilter::tag("fixme")));
+        assert!(!item.matches_filter(&ItemFilter::tag("TODO")));
+
+        let filter = ItemFilter {
+            tags: Some(vec!["TODO".to_string(), "FIXME".to_string()]),
+            author: Some("alice".to_string()),
+            min_priority: Some(Priority::High),
+            message_regex: Some(regex::Regex::new("time(out)?").unwrap()),
+            since_date: None,
+        };
+        assert!(item.matches_filter(&filter));
+
+        let other_author = ItemFilter {
+            author: Some("bob".to_string()),
+            ..filter.clone()
+        };
+        assert!(!item.matches_filter(&other_author));
+
+        item.priority = Priority::Medium;
+        assert!(!item.matches_filter(&filter));
+
+        item.author = None;
+        assert!(!item.matches_filter(&ItemFilter {
+            author: Some("alice".to_string()),
+            ..Default::default()
+        }));
+
+        let since = ItemFilter {
+            since_date: NaiveDate::from_ymd_opt(2024, 1, 1),
+            ..Default::default()
+        };
+        assert!(!item.matches_filter(&since));
+        item.created_at = Some(1_704_067_200); // 2024-01-01T00:00:00Z
+        assert!(item.matches_filter(&since));
+        item.created_at = Some(1_704_067_199);
+        assert!(!item.matches_filter(&since));
+    }
+
+    #[test]
+    fn test_scan_result_filter() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![
+                create_test_item("TODO", "Add caching", 1),
+                create_test_item("BUG", "Crash on empty input", 2),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("b.rs"),
+            vec![create_test_item("NOTE", "Add docs", 1)],
+        );
+
+        let filtered = result.filter(&ItemFilter {
+            message_regex: Some(regex::Regex::new("^Add").unwrap()),
+            ..Default::default()
+        });
+        assert_eq!(filtered.summary.total_count, 2);
+        assert_eq!(filtered.summary.files_with_todos, 2);
+        assert_eq!(filtered.summary.files_scanned, 2);
+
+        let filtered = filtered.filter(&ItemFilter {
+            min_priority: Some(Priority::Medium),
+            ..Default::default()
+        });
+        assert_eq!(filtered.summary.total_count, 1);
+        assert_eq!(filtered.summary.tag_counts.get("TODO"), Some(&1));
+    }
+
+    #[test]
+    fn test_scan_result_partition_by_priority() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![
+                create_test_item("TODO", "First", 1),
+                create_test_item("BUG", "Second", 2),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("b.rs"),
+            vec![
+                create_test_item("FIXME", "Third", 1),
+                create_test_item("NOTE", "Fourth", 2),
+            ],
+        );
+        result.add_file(PathBuf::from("c.rs"), vec![]);
+
+        let partitions = result.partition_by_priority();
+        assert_eq!(partitions.len(), 3);
+        assert!(!partitions.contains_key(&Priority::High));
+
+        let critical = &partitions[&Priority::Critical];
+        assert_eq!(critical.summary.total_count, 2);
+        assert_eq!(critical.summary.files_with_todos, 2);
+        assert_eq!(critical.summary.files_scanned, 3);
+        assert_eq!(critical.summary.tag_counts.get("BUG"), Some(&1));
+        assert_eq!(critical.summary.tag_counts.get("FIXME"), Some(&1));
+
+        let medium = &partitions[&Priority::Medium];
+        assert_eq!(medium.summary.total_count, 1);
+        assert_eq!(medium.summary.files_with_todos, 1);
+    }
+
+    #[test]
+    fn test_scan_result_by_directory() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/src/a.rs"),
+            vec![
+                create_test_item("TODO", "First", 1),
+                create_test_item("BUG", "Second", 2),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("/test/src/b.rs"),
+            vec![create_test_item("TODO", "Third", 1)],
+        );
+        result.add_file(
+            PathBuf::from("/test/src/nested/c.rs"),
+            vec![create_test_item("NOTE", "Fourth", 1)],
+        );
+        result.add_file(
+            PathBuf::from("/test/main.rs"),
+            vec![create_test_item("FIXME", "Fifth", 1)],
+        );
+
+        let directories = result.by_directory();
+        assert_eq!(directories.len(), 3);
+
+        let src = &directories[Path::new("src")];
+        assert_eq!(src.summary.total_count, 3);
+        assert_eq!(src.summary.files_with_todos, 2);
+        assert_eq!(src.summary.tag_counts.get("TODO"), Some(&2));
+        assert_eq!(src.root, Some(PathBuf::from("/test")));
+
+        assert_eq!(directories[Path::new("src/nested")].summary.total_count, 1);
+        assert_eq!(directories[Path::new(".")].summary.total_count, 1);
+    }
+
     #[test]
     fn test_scan_result_all_items() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
@@ -330,6 +1048,84 @@ mod tests {
         assert_eq!(all.len(), 2);
     }
 
+    #[test]
+    fn test_scan_result_iter() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![
+                create_test_item("TODO", "First", 1),
+                create_test_item("FIXME", "Second", 2),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("b.rs"),
+            vec![create_test_item("NOTE", "Third", 1)],
+        );
+
+        let mut borrowed: Vec<(&Path, &str)> = Vec::new();
+        for (path, item) in &result {
+            borrowed.push((path, item.message.as_str()));
+        }
+        borrowed.sort();
+        assert_eq!(
+            borrowed,
+            vec![
+                (Path::new("a.rs"), "First"),
+                (Path::new("a.rs"), "Second"),
+                (Path::new("b.rs"), "Third"),
+            ]
+        );
+
+        let mut owned: Vec<(PathBuf, TodoItem)> = result.into_iter().collect();
+        owned.sort_by_key(|(path, item)| (path.clone(), item.line));
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned[2].0, PathBuf::from("b.rs"));
+        assert_eq!(owned[2].1.tag, "NOTE");
+    }
+
+    #[test]
+    fn test_items_near_line() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![
+                create_test_item("TODO", "Far", 1),
+                create_test_item("TODO", "Above", 8),
+                create_test_item("FIXME", "On", 10),
+                create_test_item("NOTE", "Below", 12),
+            ],
+        );
+        let path = Path::new("a.rs");
+        let messages = |items: Vec<&TodoItem>| -> Vec<String> {
+            items.iter().map(|item| item.message.clone()).collect()
+        };
+
+        assert_eq!(messages(result.items_near_line(path, 10, 0)), vec!["On"]);
+        assert_eq!(
+            messages(result.items_near_line(path, 10, 2)),
+            vec!["On", "Above", "Below"]
+        );
+        assert_eq!(messages(result.items_near_line(path, 5, 3)), vec!["Above"]);
+        assert!(result.items_near_line(path, 100, 5).is_empty());
+        assert!(result.items_near_line(Path::new("b.rs"), 10, 5).is_empty());
+    }
+
+    #[test]
+    fn test_scan_result_iter_json_format() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![create_test_item("TODO", "First", 1)],
+        );
+
+        let json = result.to_json_format();
+        let items: Vec<_> = json.iter().collect();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].0, Path::new("a.rs"));
+        assert_eq!(json.into_iter().count(), 1);
+    }
+
     #[test]
     fn test_scan_result_sorted_files() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
@@ -354,6 +1150,8 @@ mod tests {
             files_with_todos: 2,
             files_scanned: 5,
             tag_counts: HashMap::new(),
+            truncated: false,
+            timed_out: false,
         };
 
         assert!((summary.avg_items_per_file() - 5.0).abs() < 0.001);
@@ -366,11 +1164,63 @@ mod tests {
             files_with_todos: 0,
             files_scanned: 5,
             tag_counts: HashMap::new(),
+            truncated: false,
+            timed_out: false,
         };
 
         assert_eq!(summary.avg_items_per_file(), 0.0);
     }
 
+    #[test]
+    fn test_coverage_ratio() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        assert_eq!(result.coverage_ratio(), 1.0);
+
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![create_test_item("TODO", "A", 1)],
+        );
+        result.summary.files_scanned = 4;
+        assert_eq!(result.coverage_ratio(), 0.75);
+    }
+
+    #[test]
+    fn test_age_histogram() {
+        const DAY: u64 = 24 * 60 * 60;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let aged = |line, days: Option<i64>| TodoItem {
+            created_at: days.map(|days| now - days * DAY as i64),
+            ..create_test_item("TODO", "Aged", line)
+        };
+
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![
+                aged(1, Some(2)),
+                aged(2, Some(3)),
+                aged(3, Some(40)),
+                aged(4, Some(400)),
+                aged(5, None),
+            ],
+        );
+
+        let week = Duration::from_secs(7 * DAY);
+        let month = Duration::from_secs(30 * DAY);
+        let histogram = result.age_histogram(&[week, month]);
+        assert_eq!(
+            histogram,
+            vec![
+                (Duration::ZERO..week, 2),
+                (week..month, 0),
+                (month..Duration::MAX, 2),
+            ]
+        );
+    }
+
     #[test]
     fn test_summary_tag_percentage() {
         let summary = Summary {
@@ -378,6 +1228,8 @@ mod tests {
             files_with_todos: 2,
             files_scanned: 5,
             tag_counts: HashMap::new(),
+            truncated: false,
+            timed_out: false,
         };
 
         assert!((summary.tag_percentage(3) - 30.0).abs() < 0.001);
@@ -390,11 +1242,127 @@ mod tests {
             files_with_todos: 0,
             files_scanned: 5,
             tag_counts: HashMap::new(),
+            truncated: false,
+            timed_out: false,
         };
 
         assert_eq!(summary.tag_percentage(0), 0.0);
     }
 
+    #[test]
+    fn test_scan_result_retain_files() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("src/a.rs"),
+            vec![
+                create_test_item("TODO", "A", 1),
+                create_test_item("FIXME", "B", 2),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("tests/b.rs"),
+            vec![create_test_item("TODO", "C", 1)],
+        );
+
+        result.retain_files(|path| path.starts_with("src"));
+
+        assert_eq!(result.files_map.len(), 1);
+        assert_eq!(result.summary.total_count, 2);
+        assert_eq!(result.summary.files_with_todos, 1);
+        assert_eq!(result.summary.files_scanned, 2);
+        assert_eq!(result.summary.tag_counts.get("TODO"), Some(&1));
+        assert_eq!(result.summary.tag_counts.get("FIXME"), Some(&1));
+    }
+
+    #[test]
+    fn test_scan_result_retain_items() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![
+                create_test_item("TODO", "A", 1),
+                create_test_item("FIXME", "B", 2),
+            ],
+        );
+        result.add_file(
+            PathBuf::from("b.rs"),
+            vec![create_test_item("TODO", "C", 1)],
+        );
+
+        result.retain_items(|item| item.tag == "FIXME");
+
+        assert_eq!(result.files_map.len(), 1);
+        assert!(result.files_map.contains_key(Path::new("a.rs")));
+        assert_eq!(result.summary.total_count, 1);
+        assert_eq!(result.summary.files_with_todos, 1);
+        assert_eq!(result.summary.tag_counts.get("TODO"), None);
+    }
+
+    #[test]
+    fn test_scan_result_retain_json_format() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![create_test_item("TODO", "A", 1)],
+        );
+        result.add_file(
+            PathBuf::from("b.rs"),
+            vec![create_test_item("NOTE", "B", 1)],
+        );
+        let mut json_result = result.to_json_format();
+
+        json_result.retain_items(|item| item.tag == "NOTE");
+
+        let files = json_result.files.as_ref().unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "b.rs");
+        assert_eq!(json_result.summary.total_count, 1);
+    }
+
+    #[test]
+    fn test_scan_result_recalculate_summary() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("a.rs"),
+            vec![create_test_item("TODO", "A", 1)],
+        );
+        let mut json_result = result.to_json_format();
+
+        let files = json_result.files.as_mut().unwrap();
+        files[0].items.push(create_test_item("FIXME", "B", 2));
+        files.push(FileResult {
+            path: "b.rs".to_string(),
+            items: Vec::new(),
+        });
+        json_result.recalculate_summary();
+
+        assert_eq!(json_result.summary.total_count, 2);
+        assert_eq!(json_result.summary.files_with_todos, 1);
+        assert_eq!(json_result.summary.tag_counts.get("FIXME"), Some(&1));
+        assert_eq!(json_result.summary.tag_counts.get("TODO"), Some(&1));
+    }
+
+    #[test]
+    fn test_scan_result_strip_authors() {
+        let mut item = create_test_item("TODO", "A", 1);
+        item.author = Some("alice".to_string());
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(PathBuf::from("a.rs"), vec![item]);
+        let mut json_result = result.to_json_format();
+
+        result.strip_authors();
+        json_result.strip_authors();
+
+        assert!(
+            result
+                .all_items()
+                .iter()
+                .all(|(_, item)| item.author.is_none())
+        );
+        assert!(json_result.iter().all(|(_, item)| item.author.is_none()));
+        assert_eq!(result.summary.total_count, 1);
+    }
+
     #[test]
     fn test_scan_result_to_json_format() {
         let mut result = ScanResult::new(PathBuf::from("/test"));
@@ -412,6 +1380,35 @@ mod tests {
         assert_eq!(json_result.files.as_ref().unwrap().len(), 2);
     }
 
+    #[test]
+    fn test_scan_result_serialize_incremental() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        result.add_file(
+            PathBuf::from("/test/b.rs"),
+            vec![create_test_item("TODO", "B", 1)],
+        );
+        result.add_file(
+            PathBuf::from("/test/a-b.rs"),
+            vec![create_test_item("FIXME", "A \"quoted\"", 3)],
+        );
+        result.add_file(
+            PathBuf::from("/test/a/c.rs"),
+            vec![create_test_item("BUG", "C", 2)],
+        );
+        result.add_file(PathBuf::from("/test/empty.rs"), vec![]);
+        result.summary.truncated = true;
+
+        let mut output = Vec::new();
+        result.serialize_incremental(&mut output).unwrap();
+        let expected = serde_json::to_string(&result.to_json_format()).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+
+        let json_result = result.to_json_format();
+        let mut output = Vec::new();
+        json_result.serialize_incremental(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
     #[test]
     fn test_scan_result_from_json() {
         let files = vec![FileResult {
@@ -424,6 +1421,8 @@ mod tests {
             files_with_todos: 1,
             files_scanned: 1,
             tag_counts: HashMap::new(),
+            truncated: false,
+            timed_out: false,
         };
 
         let result = ScanResult::from_json(files, summary);
@@ -431,6 +1430,23 @@ mod tests {
         assert_eq!(result.summary.total_count, 1);
     }
 
+    #[test]
+    fn test_summary_truncated_serialization() {
+        let mut result = ScanResult::new(PathBuf::from("/test"));
+        let json = serde_json::to_value(&result.summary).unwrap();
+        assert!(json.get("truncated").is_none());
+
+        result.summary.truncated = true;
+        let json = serde_json::to_string(&result.summary).unwrap();
+        assert!(json.contains("\"truncated\":true"));
+
+        let summary: Summary = serde_json::from_str(
+            r#"{"total_count":0,"files_with_todos":0,"files_scanned":0,"tag_counts":{}}"#,
+        )
+        .unwrap();
+        assert!(!summary.truncated);
+    }
+
     #[test]
     fn test_todo_item_serialization() {
         let item = create_test_item("TODO", "Test", 1);